@@ -1,3 +1,29 @@
+/// Commands of overkami. Each of them must be allowed in `capabilities/` to be called.
+const COMMANDS: &[&str] = &[
+    "get_config",
+    "save_config",
+    "get_application_windows",
+    "apply_wallpaper",
+    "add_wallpaper",
+    "remove_wallpaper",
+    "apply_library_source",
+    "control_playback",
+    "get_diagnostics",
+    "get_wallpaper_manifest",
+    "import_wallpaper_engine_project",
+    "export_wallpaper_bundle",
+    "import_wallpaper_bundle",
+    "set_document_edited",
+    "get_system_metrics",
+    "subscribe_system_metrics",
+    "set_input_regions",
+    "report_media_load",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("Failed to build tauri application");
 }
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::{
+    ConfigState,
+    commands::{CommandError, sync::add_wallpaper},
+    config::Wallpaper,
+    wallpaper::bundle,
+};
+
+/// What to do when the wallpaper in a bundle has the same ID as an existing one.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum ConflictStrategy {
+    /// Remove the existing wallpaper and add the one in the bundle.
+    /// The existing one is removed only after the files of the bundle are in place.
+    Replace,
+    /// Add the one in the bundle as another wallpaper with a new ID.
+    KeepBoth,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedBundle {
+    id: Uuid,
+    wallpaper: Wallpaper,
+}
+
+fn bundle_error(code: &str) -> impl FnOnce(anyhow::Error) -> CommandError {
+    move |e| {
+        log::warn!("Failed to handle wallpaper bundle. Detail: {e:#}");
+
+        CommandError {
+            code: code.to_owned(),
+            detail: Some(format!("{e:#}")),
+        }
+    }
+}
+
+/// Writes the wallpaper and the files used by it to a bundle at `path`.
+#[tauri::command]
+pub async fn export_wallpaper_bundle(
+    app: AppHandle,
+    id: Uuid,
+    path: PathBuf,
+) -> Result<(), CommandError> {
+    log::info!("Export wallpaper `{id}` to {path:?}");
+
+    let wallpaper = {
+        let config = app.state::<ConfigState>();
+        let config = config.lock().await;
+
+        config
+            .wallpapers
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| CommandError {
+                code: "wallpaper_not_found".to_owned(),
+                detail: None,
+            })?
+    };
+
+    tauri::async_runtime::spawn_blocking(move || bundle::export(id, &wallpaper, &path))
+        .await
+        .expect("Failed to export wallpaper bundle")
+        .map_err(bundle_error("export_failed"))
+}
+
+/// Adds the wallpaper in the bundle at `path`, extracting its files to the app data directory.
+///
+/// If a wallpaper with the same ID exists, `duplicate_id` is returned with the name of it
+/// unless `on_conflict` is given.
+#[tauri::command]
+pub async fn import_wallpaper_bundle(
+    app: AppHandle,
+    path: PathBuf,
+    on_conflict: Option<ConflictStrategy>,
+) -> Result<ImportedBundle, CommandError> {
+    log::info!("Import wallpaper bundle {path:?}");
+
+    let definition = {
+        let path = path.clone();
+        tauri::async_runtime::spawn_blocking(move || bundle::read_definition(&path))
+            .await
+            .expect("Failed to read wallpaper bundle")
+            .map_err(bundle_error("invalid_bundle"))?
+    };
+
+    let existing = {
+        let config = app.state::<ConfigState>();
+        let config = config.lock().await;
+
+        config
+            .wallpapers
+            .get(&definition.id)
+            .map(|wallpaper| wallpaper.name.clone())
+    };
+    let (id, replace) = match (existing, on_conflict) {
+        (None, _) => (definition.id, false),
+        (Some(name), None) => {
+            return Err(CommandError {
+                code: "duplicate_id".to_owned(),
+                detail: Some(name),
+            });
+        }
+        (Some(_), Some(ConflictStrategy::Replace)) => (definition.id, true),
+        (Some(_), Some(ConflictStrategy::KeepBoth)) => (Uuid::new_v4(), false),
+    };
+
+    // The files are extracted aside first, so that a broken bundle doesn't remove
+    // the wallpaper it would replace.
+    let dir = bundle::files_dir(&app, id).map_err(bundle_error("import_failed"))?;
+    let staged =
+        tauri::async_runtime::spawn_blocking(move || bundle::extract(&path, definition, &dir))
+            .await
+            .expect("Failed to extract wallpaper bundle")
+            .map_err(bundle_error("import_failed"))?;
+
+    let wallpaper = tauri::async_runtime::spawn_blocking(move || staged.commit())
+        .await
+        .expect("Failed to move extracted wallpaper bundle")
+        .map_err(bundle_error("import_failed"))?;
+
+    // The files of the replaced wallpaper are already replaced by the committed ones,
+    // so they must not be removed with it.
+    if replace {
+        crate::wallpaper::stop_wallpaper(&app, id).await;
+    }
+
+    add_wallpaper(app, id, wallpaper.clone()).await;
+
+    Ok(ImportedBundle { id, wallpaper })
+}
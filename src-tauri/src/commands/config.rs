@@ -1,6 +1,8 @@
 use tauri::Manager;
 
-use crate::{ConfigPathState, ConfigState, config::Config};
+use crate::{
+    ConfigPathState, ConfigState, config::Config, wallpaper::asset_protocol::update_scope,
+};
 
 #[tauri::command]
 pub async fn get_config(app: tauri::AppHandle) -> serde_json::Value {
@@ -28,5 +30,7 @@ pub async fn save_config(app: tauri::AppHandle, config: Config) {
 
     // Update internal state.
     let state = app.state::<ConfigState>();
-    *state.lock().await = config;
+    let mut state = state.lock().await;
+    *state = config;
+    update_scope(&app, &state);
 }
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::{os::memory::get_memory_usage, wallpaper::WallpaperHostsState};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    /// Resident memory of overkami in bytes.
+    pub memory: MemoryDiagnostics,
+    pub wallpapers: HashMap<Uuid, WallpaperDiagnostics>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryDiagnostics {
+    pub main: u64,
+    pub children: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WallpaperDiagnostics {
+    pub live_overlays: usize,
+    pub suspended_overlays: usize,
+}
+
+#[tauri::command]
+pub async fn get_diagnostics(app: AppHandle) -> Diagnostics {
+    let memory = tauri::async_runtime::spawn_blocking(get_memory_usage)
+        .await
+        .expect("Failed to get memory usage");
+
+    let hosts = app.state::<WallpaperHostsState>();
+    let mut wallpapers = HashMap::new();
+
+    for (id, host) in hosts.lock().await.iter() {
+        let counts = host.overlay_counts().await;
+
+        wallpapers.insert(
+            *id,
+            WallpaperDiagnostics {
+                live_overlays: counts.live,
+                suspended_overlays: counts.suspended,
+            },
+        );
+    }
+
+    Diagnostics {
+        memory: MemoryDiagnostics {
+            main: memory.main,
+            children: memory.children,
+        },
+        wallpapers,
+    }
+}
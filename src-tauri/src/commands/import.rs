@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use crate::{
+    commands::CommandError,
+    wallpaper::wallpaper_engine::{ImportError, ImportedProject, import_project},
+};
+
+/// Makes a wallpaper from the Wallpaper Engine project in the directory.
+///
+/// Projects which overkami can't show are reported as `unsupported_project` with the type.
+#[tauri::command]
+pub async fn import_wallpaper_engine_project(
+    path: PathBuf,
+) -> Result<ImportedProject, CommandError> {
+    log::info!("Import Wallpaper Engine project: {path:?}");
+
+    tauri::async_runtime::spawn_blocking(move || import_project(&path))
+        .await
+        .expect("Failed to import Wallpaper Engine project")
+        .map_err(|e| {
+            log::warn!("Failed to import Wallpaper Engine project. Detail: {e}");
+
+            match e {
+                ImportError::Unsupported(kind) => CommandError {
+                    code: "unsupported_project".to_owned(),
+                    detail: Some(kind),
+                },
+                ImportError::Invalid(e) => CommandError {
+                    code: "invalid_project".to_owned(),
+                    detail: Some(format!("{e:#}")),
+                },
+            }
+        })
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use crate::{
+    commands::CommandError,
+    wallpaper::manifest::{WallpaperManifest, read_manifest},
+};
+
+/// Reads the manifest next to the HTML of a local web page wallpaper.
+#[tauri::command]
+pub async fn get_wallpaper_manifest(
+    path: PathBuf,
+) -> Result<Option<WallpaperManifest>, CommandError> {
+    tauri::async_runtime::spawn_blocking(move || read_manifest(&path))
+        .await
+        .expect("Failed to read wallpaper manifest")
+        .map_err(|e| CommandError {
+            code: "invalid_manifest".to_owned(),
+            detail: Some(format!("{e:#}")),
+        })
+}
@@ -1,6 +1,11 @@
+pub mod bundle;
 pub mod config;
+pub mod diagnostics;
+pub mod import;
+pub mod manifest;
 pub mod os;
 pub mod sync;
+pub mod system;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct CommandError {
@@ -1,10 +1,16 @@
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Webview};
 use uuid::Uuid;
 
 pub use payload::*;
 
 use crate::{
-    ConfigState, commands::CommandError, config::Wallpaper, wallpaper::WallpaperHostsState,
+    ConfigState,
+    commands::{CommandError, system::is_remote},
+    config::{LibrarySource, Wallpaper},
+    os::InputRegion,
+    wallpaper::{
+        PlaybackControl, WallpaperHostsState, asset_protocol::update_scope, wallpaper_id_from_label,
+    },
 };
 
 #[tauri::command]
@@ -28,6 +34,7 @@ pub async fn apply_wallpaper(
     // Update the wallpaper configuration with the provided payload.
     let old_wallpaper = wallpaper.clone();
     update_wallpaper_config(wallpaper, payload.clone()).await;
+    update_scope(&app, &config);
 
     // Sync the updated wallpaper configuration to wallpaper overlays.
     let hosts = app.state::<WallpaperHostsState>();
@@ -45,7 +52,10 @@ pub async fn apply_wallpaper(
     }
 }
 
-async fn update_wallpaper_config(wallpaper: &mut Wallpaper, payload: payload::ApplyWallpaper) {
+pub(crate) async fn update_wallpaper_config(
+    wallpaper: &mut Wallpaper,
+    payload: payload::ApplyWallpaper,
+) {
     if let Some(name) = payload.name {
         wallpaper.name = name;
     }
@@ -65,6 +75,69 @@ async fn update_wallpaper_config(wallpaper: &mut Wallpaper, payload: payload::Ap
     if let Some(source) = payload.source {
         wallpaper.source = source;
     }
+
+    if let Some(library_source) = payload.library_source {
+        wallpaper.library_source = library_source;
+    }
+
+    if let Some(playback) = payload.playback {
+        wallpaper.playback = playback;
+    }
+
+    if let Some(on_battery) = payload.on_battery {
+        wallpaper.on_battery = on_battery;
+    }
+
+    if let Some(max_live_overlays) = payload.max_live_overlays {
+        wallpaper.max_live_overlays = max_live_overlays;
+    }
+
+    if let Some(suspend_after) = payload.suspend_after {
+        wallpaper.suspend_after = suspend_after;
+    }
+
+    if let Some(transition) = payload.transition {
+        wallpaper.transition = transition;
+    }
+
+    if let Some(window_bridge) = payload.window_bridge {
+        wallpaper.window_bridge = window_bridge;
+    }
+
+    if let Some(allow_remote_system_data) = payload.allow_remote_system_data {
+        wallpaper.allow_remote_system_data = allow_remote_system_data;
+    }
+
+    if let Some(interactive) = payload.interactive {
+        wallpaper.interactive = interactive;
+    }
+
+    if let Some(allow_remote_input) = payload.allow_remote_input {
+        wallpaper.allow_remote_input = allow_remote_input;
+    }
+
+    if let Some(properties) = payload.properties {
+        wallpaper.properties = properties;
+    }
+
+    if let Some(navigation) = payload.navigation {
+        wallpaper.navigation = navigation;
+    }
+
+    if let Some(effects) = payload.effects {
+        wallpaper.effects = effects;
+    }
+
+    // An empty path removes the preview.
+    if let Some(preview) = payload.preview {
+        wallpaper.preview = Some(preview).filter(|preview| !preview.as_os_str().is_empty());
+    }
+
+    // An empty path removes the fallback.
+    if let Some(fallback) = payload.template_fallback {
+        wallpaper.template_fallback =
+            Some(fallback).filter(|fallback| !fallback.as_os_str().is_empty());
+    }
 }
 
 #[tauri::command]
@@ -72,6 +145,14 @@ pub async fn add_wallpaper(app: AppHandle, id: Uuid, payload: payload::AddWallpa
     log::info!("Add new wallpaper");
     log::debug!("Payload: {payload:#?}");
 
+    // Wallpapers can be tried before they are saved, so the config is updated here too.
+    {
+        let config = app.state::<ConfigState>();
+        let mut config = config.lock().await;
+        config.wallpapers.insert(id, payload.clone());
+        update_scope(&app, &config);
+    }
+
     crate::wallpaper::add_wallpaper(app, id, payload).await;
 }
 
@@ -79,16 +160,171 @@ pub async fn add_wallpaper(app: AppHandle, id: Uuid, payload: payload::AddWallpa
 pub async fn remove_wallpaper(app: AppHandle, id: Uuid) {
     log::info!("Remove wallpaper: id = {id}");
 
+    {
+        let config = app.state::<ConfigState>();
+        let mut config = config.lock().await;
+        config.wallpapers.remove(&id);
+        update_scope(&app, &config);
+    }
+
     crate::wallpaper::remove_wallpaper(&app, id).await;
 }
 
+/// Saves the entry of the source library, and applies it to every wallpaper referencing it.
+/// Wallpapers already showing the source are left as they are.
+#[tauri::command]
+pub async fn apply_library_source(app: AppHandle, id: Uuid, entry: LibrarySource) {
+    log::info!("Apply library source `{id}`");
+
+    let source = entry.source.clone();
+    let referencing = {
+        let config = app.state::<ConfigState>();
+        let mut config = config.lock().await;
+        config.sources.insert(id, entry);
+
+        config
+            .wallpapers
+            .iter()
+            .filter(|(_, wallpaper)| {
+                wallpaper.library_source == Some(id) && wallpaper.source != source
+            })
+            .map(|(wallpaper_id, _)| *wallpaper_id)
+            .collect::<Vec<_>>()
+    };
+
+    for wallpaper_id in referencing {
+        let payload = payload::ApplyWallpaper {
+            source: Some(source.clone()),
+            ..Default::default()
+        };
+
+        if let Err(e) = apply_wallpaper(app.clone(), wallpaper_id, payload).await {
+            log::warn!("Failed to apply library source to wallpaper `{wallpaper_id}`: {e:?}");
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn control_playback(
+    app: AppHandle,
+    id: Uuid,
+    control: PlaybackControl,
+) -> Result<(), CommandError> {
+    log::info!("Control playback of wallpaper `{id}`: {control:?}");
+
+    let hosts = app.state::<WallpaperHostsState>();
+    let hosts = hosts.lock().await;
+
+    if let Some(host) = hosts.get(&id) {
+        host.control_playback(control).await;
+
+        Ok(())
+    } else {
+        Err(CommandError {
+            code: "wallpaper_not_found".to_owned(),
+            detail: None,
+        })
+    }
+}
+
+/// Sets the regions where the calling wallpaper page accepts pointer events.
+/// This is called by wallpaper pages, and only works in interactive mode.
+#[tauri::command]
+pub async fn set_input_regions(
+    webview: Webview,
+    regions: Vec<InputRegion>,
+) -> Result<(), CommandError> {
+    let not_found = || CommandError {
+        code: "wallpaper_not_found".to_owned(),
+        detail: None,
+    };
+    let Some(id) = wallpaper_id_from_label(webview.label()) else {
+        return Err(not_found());
+    };
+
+    let config = webview.state::<ConfigState>();
+    let (interactive, allow_remote_input) = {
+        let config = config.lock().await;
+        let wallpaper = config.wallpapers.get(&id).ok_or_else(not_found)?;
+        (wallpaper.interactive, wallpaper.allow_remote_input)
+    };
+    if !interactive {
+        return Err(CommandError {
+            code: "not_interactive".to_owned(),
+            detail: Some("The interactive mode of the wallpaper is disabled.".to_owned()),
+        });
+    }
+
+    // Like the system data API, remote pages must also be allowed by the wallpaper itself.
+    let remote = match webview.url() {
+        Ok(url) => is_remote(&url),
+        Err(_) => true,
+    };
+    if remote && !allow_remote_input {
+        return Err(CommandError {
+            code: "forbidden".to_owned(),
+            detail: Some("Remote pages are not allowed to declare input regions.".to_owned()),
+        });
+    }
+
+    let hosts = webview.state::<WallpaperHostsState>();
+    let hosts = hosts.lock().await;
+    let Some(host) = hosts.get(&id) else {
+        return Err(not_found());
+    };
+
+    if host.set_input_regions(webview.label(), regions).await {
+        Ok(())
+    } else {
+        Err(not_found())
+    }
+}
+
+/// Tells whether the media of the calling built-in page is loaded.
+/// The incoming overlay window is swapped in only after this reports the success.
+#[tauri::command]
+pub async fn report_media_load(
+    webview: Webview,
+    error: Option<String>,
+) -> Result<(), CommandError> {
+    let not_found = || CommandError {
+        code: "wallpaper_not_found".to_owned(),
+        detail: None,
+    };
+    let Some(id) = wallpaper_id_from_label(webview.label()) else {
+        return Err(not_found());
+    };
+
+    let hosts = webview.state::<WallpaperHostsState>();
+    let hosts = hosts.lock().await;
+    let Some(host) = hosts.get(&id) else {
+        return Err(not_found());
+    };
+
+    let result = match error {
+        Some(detail) => Err(detail),
+        None => Ok(()),
+    };
+    if host.report_media_load(webview.label(), result).await {
+        Ok(())
+    } else {
+        Err(not_found())
+    }
+}
+
 mod payload {
-    use serde::{Deserialize, Serialize};
+    use std::{collections::HashMap, path::PathBuf};
+
+    use serde::{Deserialize, Deserializer, Serialize};
+    use uuid::Uuid;
 
-    use crate::config::{Filter, Wallpaper, WallpaperSource};
+    use crate::config::{
+        BatteryBehavior, Effect, Filter, NavigationPolicy, PlaybackPolicy, Transition, Wallpaper,
+        WallpaperSource,
+    };
 
     /// Represents the payload for applying wallpaper settings.
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct ApplyWallpaper {
         pub name: Option<String>,
@@ -96,6 +332,32 @@ mod payload {
         pub filters: Option<Vec<Filter>>,
         pub opacity: Option<f64>,
         pub source: Option<WallpaperSource>,
+        /// `Some(None)` makes the source inline.
+        #[serde(default, deserialize_with = "present")]
+        pub library_source: Option<Option<Uuid>>,
+        pub playback: Option<PlaybackPolicy>,
+        pub on_battery: Option<BatteryBehavior>,
+        pub max_live_overlays: Option<usize>,
+        pub suspend_after: Option<u64>,
+        pub transition: Option<Transition>,
+        pub window_bridge: Option<bool>,
+        pub allow_remote_system_data: Option<bool>,
+        pub interactive: Option<bool>,
+        pub allow_remote_input: Option<bool>,
+        pub properties: Option<HashMap<String, serde_json::Value>>,
+        pub navigation: Option<NavigationPolicy>,
+        pub effects: Option<Vec<Effect>>,
+        pub preview: Option<PathBuf>,
+        pub template_fallback: Option<PathBuf>,
+    }
+
+    /// Tells a field set to `null` from a missing one.
+    fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        T::deserialize(deserializer).map(Some)
     }
 
     /// Represents the payload for adding a new wallpaper configuration.
@@ -0,0 +1,122 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use tauri::{Manager, Url, Webview, ipc::Channel};
+
+use crate::{
+    ConfigState,
+    commands::CommandError,
+    os::system_metrics::{MIN_INTERVAL, SystemMetrics, get_system_metrics as sample},
+    wallpaper::wallpaper_id_from_label,
+};
+
+/// The generation of the subscription per webview label.
+/// A page can hold only one subscription, and a new one replaces the old one.
+static SUBSCRIPTIONS: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(Default::default);
+
+#[tauri::command]
+pub async fn get_system_metrics(webview: Webview) -> Result<SystemMetrics, CommandError> {
+    check_caller(&webview).await?;
+
+    Ok(tauri::async_runtime::spawn_blocking(sample)
+        .await
+        .expect("Failed to get system metrics"))
+}
+
+/// Sends system metrics to `on_event` every `interval` milliseconds.
+///
+/// The subscription ends when the page navigates away, the webview is closed,
+/// the page subscribes again or the wallpaper stops allowing the page to access.
+#[tauri::command]
+pub async fn subscribe_system_metrics(
+    webview: Webview,
+    interval: u64,
+    on_event: Channel<SystemMetrics>,
+) -> Result<(), CommandError> {
+    check_caller(&webview).await?;
+
+    let interval = Duration::from_millis(interval).max(MIN_INTERVAL);
+    let url = webview.url().ok();
+    let label = webview.label().to_owned();
+    let generation = {
+        let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+        let generation = subscriptions.entry(label.clone()).or_default();
+        *generation += 1;
+        *generation
+    };
+
+    log::info!("Webview `{label}` subscribed system metrics every {interval:?}.");
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let is_current = SUBSCRIPTIONS.lock().unwrap().get(&label) == Some(&generation);
+            if !is_current || webview.url().ok() != url || check_caller(&webview).await.is_err() {
+                break;
+            }
+
+            let metrics = tauri::async_runtime::spawn_blocking(sample)
+                .await
+                .expect("Failed to get system metrics");
+            if on_event.send(metrics).is_err() {
+                break;
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+
+        let mut subscriptions = SUBSCRIPTIONS.lock().unwrap();
+        if subscriptions.get(&label) == Some(&generation) {
+            subscriptions.remove(&label);
+        }
+
+        log::info!("Subscription of system metrics by `{label}` is ended.");
+    });
+
+    Ok(())
+}
+
+/// Checks that the caller is a wallpaper page which is allowed to read system data.
+///
+/// The capability already limits these commands to `wallpaper-*` windows,
+/// but remote pages must also be allowed by the wallpaper itself.
+async fn check_caller(webview: &Webview) -> Result<(), CommandError> {
+    let forbidden = |detail: &str| CommandError {
+        code: "forbidden".to_owned(),
+        detail: Some(detail.to_owned()),
+    };
+
+    let Some(id) = wallpaper_id_from_label(webview.label()) else {
+        return Err(forbidden("The caller is not a wallpaper."));
+    };
+
+    let config = webview.state::<ConfigState>();
+    let config = config.lock().await;
+    let Some(wallpaper) = config.wallpapers.get(&id) else {
+        return Err(forbidden("The wallpaper of the caller is not found."));
+    };
+
+    let url = webview
+        .url()
+        .map_err(|_| forbidden("The URL of the caller is unknown."))?;
+    if is_remote(&url) && !wallpaper.allow_remote_system_data {
+        return Err(forbidden(
+            "Remote pages are not allowed to access system data.",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether the URL is out of the app, i.e. not the app itself nor the asset protocol.
+pub(crate) fn is_remote(url: &Url) -> bool {
+    match url.scheme() {
+        // On Windows and Android, the custom protocols are served as `http://<scheme>.localhost`.
+        "http" | "https" => !url
+            .host_str()
+            .is_some_and(|host| host.ends_with(".localhost")),
+        _ => false,
+    }
+}
@@ -15,6 +15,26 @@ mod model {
         pub version: String,
         pub open_window_on_startup: bool,
         pub wallpapers: HashMap<Uuid, Wallpaper>,
+        /// The sources which wallpapers can share.
+        #[serde(default)]
+        pub sources: HashMap<Uuid, LibrarySource>,
+    }
+
+    impl Config {
+        /// Copies the sources in the library to the wallpapers referencing them.
+        /// Wallpapers referencing a removed entry keep their last source as an inline one.
+        pub fn sync_library_sources(&mut self) {
+            for wallpaper in self.wallpapers.values_mut() {
+                let Some(id) = wallpaper.library_source else {
+                    continue;
+                };
+
+                match self.sources.get(&id) {
+                    Some(entry) => wallpaper.source = entry.source.clone(),
+                    None => wallpaper.library_source = None,
+                }
+            }
+        }
     }
 
     impl Default for Config {
@@ -23,23 +43,262 @@ mod model {
                 version: VERSION.to_owned(),
                 open_window_on_startup: true,
                 wallpapers: HashMap::new(),
+                sources: HashMap::new(),
             }
         }
     }
 
     mod wallpaper {
-        use std::path::PathBuf;
+        use std::{collections::HashMap, path::PathBuf};
 
         use serde::{Deserialize, Serialize};
+        use uuid::Uuid;
 
-        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
         #[serde(tag = "type")]
         pub enum WallpaperSource {
-            RemoteWebPage { location: String },
-            LocalWebPage { location: PathBuf },
-            YouTube { location: String },
-            Picture { location: PathBuf },
-            Video { location: PathBuf },
+            RemoteWebPage {
+                location: String,
+            },
+            LocalWebPage {
+                location: PathBuf,
+            },
+            YouTube {
+                location: String,
+                #[serde(default)]
+                options: YouTubeOptions,
+            },
+            Picture {
+                location: PathBuf,
+                #[serde(default)]
+                layout: Layout,
+            },
+            Video {
+                location: PathBuf,
+                #[serde(default)]
+                layout: Layout,
+                #[serde(default)]
+                options: VideoOptions,
+            },
+            /// A picture on the web. It is downloaded and cached, and checked for updates at the
+            /// interval in seconds.
+            RemotePicture {
+                location: String,
+                #[serde(default)]
+                layout: Layout,
+                #[serde(rename = "refreshInterval", default = "default_refresh_interval")]
+                refresh_interval: u64,
+            },
+            /// The pictures and videos in a folder, or listed in a playlist file, in turn.
+            Slideshow {
+                location: PathBuf,
+                #[serde(default)]
+                options: SlideshowOptions,
+            },
+            /// A plain color written in CSS, like `#1e1e2e`.
+            Color {
+                color: String,
+            },
+            Gradient {
+                kind: GradientKind,
+                /// The direction of linear gradients in degrees, where `0` goes upward.
+                #[serde(default)]
+                angle: f64,
+                /// Fewer than two stops are shown as the solid color of the stop, if any.
+                stops: Vec<GradientStop>,
+            },
+        }
+
+        impl WallpaperSource {
+            /// How the picture or the video is laid out in the window.
+            pub fn layout(&self) -> Option<&Layout> {
+                match self {
+                    Self::Picture { layout, .. }
+                    | Self::RemotePicture { layout, .. }
+                    | Self::Video { layout, .. } => Some(layout),
+                    _ => None,
+                }
+            }
+        }
+
+        fn default_refresh_interval() -> u64 {
+            60 * 60
+        }
+
+        /// How a video is played. Positions are in seconds of the video.
+        #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase", default)]
+        pub struct VideoOptions {
+            /// From `0` to `100`. `0` keeps the video muted.
+            pub volume: f64,
+            /// Whether to mute the video unless the target window is focused.
+            pub mute_when_unfocused: bool,
+            /// Multiplies the rate of the playback clock.
+            pub rate: f64,
+            /// Where the first playback starts.
+            pub start: f64,
+            pub loop_start: f64,
+            /// `None` loops at the end of the video.
+            pub loop_end: Option<f64>,
+        }
+
+        impl Default for VideoOptions {
+            fn default() -> Self {
+                Self {
+                    volume: 0.,
+                    mute_when_unfocused: true,
+                    rate: 1.,
+                    start: 0.,
+                    loop_start: 0.,
+                    loop_end: None,
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase", default)]
+        pub struct YouTubeOptions {
+            /// Where the video starts in seconds.
+            pub start: u64,
+            /// The ID or the URL of the playlist to play instead of the single video.
+            pub playlist: Option<String>,
+            #[serde(rename = "loop")]
+            pub looped: bool,
+        }
+
+        impl Default for YouTubeOptions {
+            fn default() -> Self {
+                Self {
+                    start: 0,
+                    playlist: None,
+                    looped: true,
+                }
+            }
+        }
+
+        /// How a picture or a video fits in the window.
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum Fit {
+            /// Fill the window keeping the aspect ratio. Overflowing parts are cropped.
+            #[default]
+            Cover,
+            /// Fit inside the window keeping the aspect ratio.
+            Contain,
+            /// Stretch to the window.
+            Fill,
+            /// Keep the original size.
+            None,
+            /// Repeat in the original size.
+            Tile,
+        }
+
+        /// The point of the window where a picture or a video is aligned.
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum Anchor {
+            TopLeft,
+            Top,
+            TopRight,
+            Left,
+            #[default]
+            Center,
+            Right,
+            BottomLeft,
+            Bottom,
+            BottomRight,
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase", default)]
+        pub struct Layout {
+            pub fit: Fit,
+            /// Which part is kept when the picture is cropped, or where it is put when smaller.
+            pub anchor: Anchor,
+            /// Multiplies the size given by `fit`.
+            pub scale: f64,
+            /// Moves the picture by percent of the width of the window.
+            pub offset_x: f64,
+            /// Moves the picture by percent of the height of the window.
+            pub offset_y: f64,
+        }
+
+        impl Default for Layout {
+            fn default() -> Self {
+                Self {
+                    fit: Fit::default(),
+                    anchor: Anchor::default(),
+                    scale: 1.,
+                    offset_x: 0.,
+                    offset_y: 0.,
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum GradientKind {
+            #[default]
+            Linear,
+            /// Spreads from the center. The angle is not used.
+            Radial,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        pub struct GradientStop {
+            /// A color written in CSS.
+            pub color: String,
+            /// The position from `0` to `100` percent.
+            pub position: f64,
+        }
+
+        /// The order of the items of a slideshow.
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum SlideshowOrder {
+            /// The order in the playlist, or the order of names for folders.
+            #[default]
+            Listed,
+            Name,
+            /// From the oldest modified one.
+            Modified,
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct SlideshowOptions {
+            /// Seconds to show each item.
+            pub interval: u64,
+            /// Whether to include the files in subfolders of the folder.
+            #[serde(default)]
+            pub recursive: bool,
+            #[serde(default)]
+            pub order: SlideshowOrder,
+            /// Whether to show the items in random order. The order is shuffled every round.
+            #[serde(default)]
+            pub shuffle: bool,
+            /// Whether each window rotates on its own, instead of all windows together.
+            #[serde(default)]
+            pub independent: bool,
+        }
+
+        impl Default for SlideshowOptions {
+            fn default() -> Self {
+                Self {
+                    interval: 60,
+                    recursive: false,
+                    order: SlideshowOrder::default(),
+                    shuffle: false,
+                    independent: false,
+                }
+            }
+        }
+
+        /// A named source in the library.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        pub struct LibrarySource {
+            pub name: String,
+            pub source: WallpaperSource,
+        }
+
+        fn default_suspend_after() -> u64 {
+            60
         }
 
         #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,6 +307,8 @@ mod model {
             Suffix,
             Contains,
             Exact,
+            /// A regular expression. Its capture groups can be used in templated sources.
+            Regex,
         }
 
         #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +320,91 @@ mod model {
             },
         }
 
+        /// When animated wallpapers such as videos should be played.
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum PlaybackPolicy {
+            #[default]
+            Always,
+            /// Play only while the target window is focused.
+            WhenFocused,
+            /// Play only while the target window is visible.
+            WhenVisible,
+        }
+
+        /// What to do with the wallpaper while the computer is running on battery.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        pub enum BatteryBehavior {
+            #[default]
+            Keep,
+            PauseAnimation,
+            /// Show the picture instead of the wallpaper source.
+            StaticFallback {
+                location: PathBuf,
+            },
+            Disable,
+        }
+
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+        pub enum TransitionKind {
+            /// Switch to the new source at once.
+            Cut,
+            /// Fade out the old source, and then fade in the new one.
+            Fade,
+            #[default]
+            Crossfade,
+        }
+
+        /// How the overlay switches to a new source.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+        pub struct Transition {
+            pub kind: TransitionKind,
+            /// The duration in milliseconds.
+            pub duration: u64,
+        }
+
+        impl Default for Transition {
+            fn default() -> Self {
+                Self {
+                    kind: TransitionKind::default(),
+                    duration: 300,
+                }
+            }
+        }
+
+        /// An effect applied to any wallpaper, for example to keep the app on it readable.
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        pub enum Effect {
+            /// Blurs by the radius in pixels.
+            Blur { radius: f64 },
+            /// In percent, where `100` keeps the original and lower values darken.
+            Brightness { amount: f64 },
+            /// In percent, where `100` keeps the original.
+            Contrast { amount: f64 },
+            /// In percent, where `100` keeps the original and `0` removes colors.
+            Saturation { amount: f64 },
+            /// In percent, where `0` keeps the original and `100` is fully gray.
+            Grayscale { amount: f64 },
+            /// Covers the wallpaper with the color written in CSS.
+            /// `strength` is the opacity of the color in percent.
+            Tint { color: String, strength: f64 },
+        }
+
+        /// Where web page wallpapers can navigate to.
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        pub enum NavigationPolicy {
+            /// Stay on the origin of the page.
+            #[default]
+            SameOrigin,
+            /// The origin of the page and the listed origins like `https://example.com`.
+            /// A host starting with `*.` matches its subdomains.
+            Allowlist { origins: Vec<String> },
+            /// Stay on the page.
+            Block,
+        }
+
         #[derive(Debug, Clone, Serialize, Deserialize)]
         #[serde(rename_all = "camelCase")]
         pub struct Wallpaper {
@@ -66,7 +412,52 @@ mod model {
             pub application_name: String,
             pub filters: Vec<Filter>,
             pub source: WallpaperSource,
+            /// The ID of the source in the library which `source` follows.
+            /// `source` keeps a copy of it, so hosts never have to look up the library.
+            #[serde(default)]
+            pub library_source: Option<Uuid>,
             pub opacity: f64,
+            #[serde(default)]
+            pub playback: PlaybackPolicy,
+            #[serde(default)]
+            pub on_battery: BatteryBehavior,
+            /// The maximum number of overlays whose webviews are alive at the same time.
+            /// `0` means unlimited.
+            #[serde(default)]
+            pub max_live_overlays: usize,
+            /// Seconds after which hidden overlays are suspended. `0` means never.
+            #[serde(default = "default_suspend_after")]
+            pub suspend_after: u64,
+            #[serde(default)]
+            pub transition: Transition,
+            /// Whether to expose the state of the target window to the page through `window.overkami`.
+            #[serde(default)]
+            pub window_bridge: bool,
+            /// Whether to allow remote pages to read system data through the system data API.
+            /// Local pages are always allowed.
+            #[serde(default)]
+            pub allow_remote_system_data: bool,
+            /// Whether the page can accept pointer events inside the regions it declares.
+            #[serde(default)]
+            pub interactive: bool,
+            /// Whether to allow remote pages to declare input regions in interactive mode.
+            /// Local pages are always allowed.
+            #[serde(default)]
+            pub allow_remote_input: bool,
+            /// The values of the properties declared in the manifest of the page.
+            #[serde(default)]
+            pub properties: HashMap<String, serde_json::Value>,
+            #[serde(default)]
+            pub navigation: NavigationPolicy,
+            /// The effects applied to the wallpaper in order.
+            #[serde(default)]
+            pub effects: Vec<Effect>,
+            /// The image shown in the list of wallpapers instead of the source.
+            #[serde(default)]
+            pub preview: Option<PathBuf>,
+            /// The picture shown when the file that the templated source points to does not exist.
+            #[serde(default)]
+            pub template_fallback: Option<PathBuf>,
         }
     }
 }
@@ -76,11 +467,14 @@ pub mod state {
 
     use tauri::{Manager, async_runtime::Mutex};
 
+    use crate::wallpaper::asset_protocol::update_scope;
+
     use super::Config;
 
     pub type ConfigState = Mutex<Config>;
 
     pub fn set_config_state(app: &tauri::AppHandle, config: Config) {
+        update_scope(app, &config);
         app.manage(Mutex::new(config));
     }
 
@@ -131,8 +525,11 @@ mod setup {
                 Err(error) => failed_to_read_config_file(app, error.to_string()),
             };
 
-            match serde_json::from_slice(&raw) {
-                Ok(config) => config,
+            match serde_json::from_slice::<Config>(&raw) {
+                Ok(mut config) => {
+                    config.sync_library_sources();
+                    config
+                }
                 Err(error) => failed_to_parse_config_file(app, error.to_string()),
             }
         } else {
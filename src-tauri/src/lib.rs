@@ -14,6 +14,7 @@ fn setup(app: &mut tauri::App) {
     os::application_monitor::auto_refresh::start().unwrap();
 
     wallpaper::setup_wallpapers(app);
+    wallpaper::setup_system_state_sync(app);
     tray_icon::setup_tray_icon(app);
 
     if app
@@ -33,6 +34,15 @@ pub fn run() {
         .plugin(tauri_plugin_log::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .manage(wallpaper::asset_protocol::AssetScope::default())
+        .register_asynchronous_uri_scheme_protocol(
+            wallpaper::asset_protocol::SCHEME,
+            wallpaper::asset_protocol::handle,
+        )
+        .register_asynchronous_uri_scheme_protocol(
+            wallpaper::protocol::SCHEME,
+            wallpaper::protocol::handle,
+        )
         .setup(|app| {
             setup(app);
 
@@ -45,12 +55,25 @@ pub fn run() {
             commands::sync::apply_wallpaper,
             commands::sync::add_wallpaper,
             commands::sync::remove_wallpaper,
+            commands::sync::apply_library_source,
+            commands::sync::control_playback,
+            commands::sync::report_media_load,
+            commands::sync::set_input_regions,
+            commands::diagnostics::get_diagnostics,
+            commands::manifest::get_wallpaper_manifest,
+            commands::import::import_wallpaper_engine_project,
+            commands::bundle::export_wallpaper_bundle,
+            commands::bundle::import_wallpaper_bundle,
+            commands::system::get_system_metrics,
+            commands::system::subscribe_system_metrics,
             #[cfg(target_os = "macos")]
             commands::os::platform_custom_feature::set_document_edited
         ])
         .build(tauri::generate_context!())
         .expect("Failed to run tauri application");
 
+    // The scope of the asset protocol, computed with the config, includes cached remote pictures.
+    app.manage(wallpaper::RemotePictures::new(app.handle()));
     config::setup_config(&app);
 
     #[cfg(target_os = "macos")]
@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Memory used by overkami in bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryUsage {
+    /// Resident memory of the overkami process itself.
+    pub main: u64,
+    /// Resident memory of the processes for overkami such as WebView2 processes on Windows.
+    ///
+    /// On macOS, WebKit processes are XPC services started by launchd, so they are not
+    /// descendants of overkami. They are counted as the processes which overkami is
+    /// responsible for, like Activity Monitor does.
+    pub children: u64,
+}
+
+pub fn get_memory_usage() -> MemoryUsage {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+
+    let main_pid = Pid::from_u32(std::process::id());
+    let mut usage = MemoryUsage {
+        main: system
+            .process(main_pid)
+            .map_or(0, |process| process.memory()),
+        children: 0,
+    };
+
+    let mut descendants = HashSet::from([main_pid]);
+    for (pid, process) in system.processes() {
+        if *pid != main_pid && is_responsible_for(main_pid, *pid) {
+            descendants.insert(*pid);
+            usage.children += process.memory();
+        }
+    }
+
+    // Walk the process tree from overkami and the processes attributed to it.
+    let mut found = true;
+    while found {
+        found = false;
+
+        for (pid, process) in system.processes() {
+            if descendants.contains(pid) {
+                continue;
+            }
+
+            if process
+                .parent()
+                .is_some_and(|parent| descendants.contains(&parent))
+            {
+                descendants.insert(*pid);
+                usage.children += process.memory();
+                found = true;
+            }
+        }
+    }
+
+    usage
+}
+
+/// Whether macOS attributes the process to `responsible`, even if it is not a descendant.
+#[cfg(target_os = "macos")]
+fn is_responsible_for(responsible: Pid, pid: Pid) -> bool {
+    unsafe extern "C" {
+        // A private function of libSystem. It returns -1 on failure.
+        fn responsibility_get_pid_responsible_for_pid(pid: i32) -> i32;
+    }
+
+    let result = unsafe { responsibility_get_pid_responsible_for_pid(pid.as_u32() as i32) };
+    result >= 0 && result as u32 == responsible.as_u32()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_responsible_for(_responsible: Pid, _pid: Pid) -> bool {
+    false
+}
@@ -1,6 +1,10 @@
 pub mod application_monitor;
 pub mod application_observer;
+pub mod memory;
 pub mod platform_impl;
+pub mod power;
+pub mod system_metrics;
+pub mod system_state;
 
 pub use platform_impl::*;
 
@@ -12,7 +12,8 @@ pub fn get_ns_window(window: &WebviewWindow) -> Retained<NSWindow> {
 
 mod webview_window {
     use anyhow::{Context, Result};
-    use tauri::{LogicalPosition, Manager, WebviewWindow};
+    use objc2::runtime::NSObjectProtocol as _;
+    use tauri::{LogicalPosition, LogicalSize, Manager, WebviewWindow};
     use window_getter::WindowId;
 
     use super::core_graphics_services;
@@ -61,9 +62,134 @@ mod webview_window {
             Ok(self.set_ignore_cursor_events(ignore)?)
         }
 
-        fn set_position_with_adjustment(&self, x: f64, y: f64) -> anyhow::Result<()> {
+        fn set_input_regions(
+            &self,
+            regions: Option<Vec<crate::os::InputRegion>>,
+        ) -> anyhow::Result<()> {
+            if crate::os::input_region::set(self, regions) {
+                crate::os::input_region::track(self.clone(), |window, ignore| {
+                    Ok(window.set_ignore_cursor_events(ignore)?)
+                });
+            }
+            Ok(())
+        }
+
+        fn deny_permissions(&self) -> anyhow::Result<()> {
+            super::permission::deny(self)
+        }
+
+        fn monitor_refresh_rate(&self) -> anyhow::Result<f64> {
+            let ns_window = super::get_ns_window(self);
+            let screen = ns_window.screen().context("The window is on no screen")?;
+            // It is available since macOS 12.
+            anyhow::ensure!(
+                screen.respondsToSelector(objc2::sel!(maximumFramesPerSecond)),
+                "The refresh rate is unknown"
+            );
+
+            Ok(screen.maximumFramesPerSecond() as f64)
+        }
+
+        fn set_position_with_adjustment(
+            &self,
+            x: f64,
+            y: f64,
+            _scale_factor: f64,
+        ) -> anyhow::Result<()> {
             Ok(self.set_position(LogicalPosition::new(x, y))?)
         }
+
+        fn set_size_with_adjustment(
+            &self,
+            width: f64,
+            height: f64,
+            _scale_factor: f64,
+        ) -> anyhow::Result<()> {
+            Ok(self.set_size(LogicalSize::new(width, height))?)
+        }
+    }
+}
+
+/// Denial of permission requests from the pages in overlay windows.
+///
+/// The UI delegate of wry grants media capture, so the class of the delegate is swapped
+/// for a subclass which denies it instead.
+mod permission {
+    use std::{ffi::CString, ptr};
+
+    use anyhow::Context as _;
+    use block2::Block;
+    use objc2::{
+        ffi,
+        runtime::{AnyClass, AnyObject, ClassBuilder, Sel},
+        sel,
+    };
+    use objc2_web_kit::{WKPermissionDecision, WKWebView};
+    use tauri::WebviewWindow;
+
+    const SUBCLASS_PREFIX: &str = "OverkamiDenyPermissions";
+
+    pub fn deny(window: &WebviewWindow) -> anyhow::Result<()> {
+        window
+            .with_webview(|webview| {
+                // SAFETY: The pointer is the `WKWebView` of the window,
+                // and this closure runs on the main thread.
+                let webview = unsafe { &*webview.inner().cast::<WKWebView>() };
+                // SAFETY: The delegate is only read on the main thread.
+                let Some(delegate) = (unsafe { webview.UIDelegate() }) else {
+                    log::warn!("The webview has no UI delegate to deny permissions.");
+                    return;
+                };
+
+                install((*delegate).as_ref());
+            })
+            .context("Failed to start permission denial on main thread")
+    }
+
+    /// Swaps the class of the delegate for its denying subclass, unless it is already swapped.
+    fn install(delegate: &AnyObject) {
+        let class = delegate.class();
+        if class.name().to_string_lossy().starts_with(SUBCLASS_PREFIX) {
+            return;
+        }
+
+        let subclass = subclass_of(class);
+        // SAFETY: The subclass only overrides a method, so the instance layout is the same.
+        unsafe { ffi::object_setClass(ptr::from_ref(delegate).cast_mut(), subclass) };
+    }
+
+    fn subclass_of(class: &'static AnyClass) -> &'static AnyClass {
+        let name = format!("{SUBCLASS_PREFIX}{}", class.name().to_string_lossy());
+        let name = CString::new(name).expect("Class names never contain NUL");
+        if let Some(subclass) = AnyClass::get(&name) {
+            return subclass;
+        }
+
+        let mut builder =
+            ClassBuilder::new(&name, class).expect("Failed to declare permission denying class");
+        // SAFETY: The signature matches `-[WKUIDelegate webView:requestMediaCapturePermission...]`.
+        unsafe {
+            builder.add_method(
+                sel!(webView:requestMediaCapturePermissionForOrigin:initiatedByFrame:type:decisionHandler:),
+                request_media_capture_permission
+                    as unsafe extern "C-unwind" fn(_, _, _, _, _, _, _),
+            );
+        }
+
+        builder.register()
+    }
+
+    /// Denies the camera and the microphone, which wry grants to every page.
+    unsafe extern "C-unwind" fn request_media_capture_permission(
+        _this: &AnyObject,
+        _cmd: Sel,
+        _webview: &AnyObject,
+        _origin: &AnyObject,
+        _frame: &AnyObject,
+        _capture_type: isize,
+        decision_handler: &Block<dyn Fn(WKPermissionDecision)>,
+    ) {
+        decision_handler.call((WKPermissionDecision::Deny,));
     }
 }
 
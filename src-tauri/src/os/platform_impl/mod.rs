@@ -11,13 +11,31 @@ pub use macos::custom_feature;
 #[allow(unused_imports)]
 pub use windows::custom_feature;
 
+pub use input_region::InputRegion;
+
 pub trait WebviewWindowPlatformExt {
     fn setup_platform_specific(&self) -> anyhow::Result<()>;
     fn set_opacity(&self, opacity: f64) -> anyhow::Result<()>;
     fn set_order_above(&self, relative_to: WindowId) -> anyhow::Result<()>;
     fn merge_always_on_top(&self, top: bool) -> anyhow::Result<()>;
     fn merge_ignore_cursor_events(&self, ignore: bool) -> anyhow::Result<()>;
-    fn set_position_with_adjustment(&self, x: f64, y: f64) -> anyhow::Result<()>;
+    /// Accepts pointer events only inside `regions`, and lets them through everywhere else.
+    /// `None` stops it, and then the window follows [`Self::merge_ignore_cursor_events`] again.
+    fn set_input_regions(&self, regions: Option<Vec<InputRegion>>) -> anyhow::Result<()>;
+    /// Denies the permission requests of the page in the webview, such as camera and microphone.
+    fn deny_permissions(&self) -> anyhow::Result<()>;
+    /// Returns the refresh rate in Hz of the monitor that the window is on.
+    fn monitor_refresh_rate(&self) -> anyhow::Result<f64>;
+    /// Sets the logical position. `scale_factor` is the one of the monitor that the position belongs to.
+    fn set_position_with_adjustment(&self, x: f64, y: f64, scale_factor: f64)
+    -> anyhow::Result<()>;
+    /// Sets the logical size. `scale_factor` is the one of the monitor that the window belongs to.
+    fn set_size_with_adjustment(
+        &self,
+        width: f64,
+        height: f64,
+        scale_factor: f64,
+    ) -> anyhow::Result<()>;
 }
 
 pub trait WindowPlatformExt {
@@ -30,3 +48,126 @@ pub fn activate_another_app(target: &window_getter::Window) -> anyhow::Result<()
     #[cfg(target_os = "windows")]
     return windows::set_foreground_window(target);
 }
+
+/// Input shapes made of rectangles.
+///
+/// Neither platform lets clicks outside an input shape through to the window below.
+/// A view which refuses hit testing on macOS still takes the click, and Windows can't give
+/// a layered window an input shape without clipping its drawing too. So the cursor is
+/// tracked and pointer events of the whole window are toggled instead.
+pub mod input_region {
+    use std::{
+        collections::HashMap,
+        sync::{LazyLock, Mutex},
+        time::Duration,
+    };
+
+    use serde::Deserialize;
+    use tauri::WebviewWindow;
+
+    /// A rectangle in logical pixels relative to the top left corner of the window.
+    #[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+    pub struct InputRegion {
+        pub x: f64,
+        pub y: f64,
+        pub width: f64,
+        pub height: f64,
+    }
+
+    impl InputRegion {
+        #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+        pub fn contains(&self, x: f64, y: f64) -> bool {
+            self.x <= x && x < self.x + self.width && self.y <= y && y < self.y + self.height
+        }
+    }
+
+    /// How often the cursor is checked against the input regions.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    const POLLING_INTERVAL: Duration = Duration::from_millis(33);
+
+    /// The input regions per window label.
+    static REGIONS: LazyLock<Mutex<HashMap<String, Vec<InputRegion>>>> =
+        LazyLock::new(Default::default);
+
+    /// Records the input regions applied to the window by the platform implementation.
+    /// Returns `true` if the window had no input regions before.
+    pub fn set(window: &WebviewWindow, regions: Option<Vec<InputRegion>>) -> bool {
+        let mut all = REGIONS.lock().unwrap();
+        let label = window.label().to_owned();
+
+        match regions {
+            Some(regions) => all.insert(label, regions).is_none(),
+            None => {
+                all.remove(&label);
+                false
+            }
+        }
+    }
+
+    /// Whether the window accepts pointer events only inside its input regions.
+    pub fn is_tracked(window: &WebviewWindow) -> bool {
+        REGIONS.lock().unwrap().contains_key(window.label())
+    }
+
+    /// Calls `f` with the input regions of the window, or returns `None` if it has none.
+    ///
+    /// The regions are locked while `f` runs, so that they can't be removed in the meantime.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    pub fn with_regions<R>(
+        window: &WebviewWindow,
+        f: impl FnOnce(&[InputRegion]) -> R,
+    ) -> Option<R> {
+        REGIONS
+            .lock()
+            .unwrap()
+            .get(window.label())
+            .map(|regions| f(regions))
+    }
+
+    /// Calls `set_ignore` whenever the cursor moves into or out of the input regions,
+    /// until the regions of the window are removed or the window is closed.
+    /// `set_ignore` receives whether the window should let pointer events through.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    pub fn track(
+        window: WebviewWindow,
+        set_ignore: impl Fn(&WebviewWindow, bool) -> anyhow::Result<()> + Send + 'static,
+    ) {
+        std::thread::spawn(move || {
+            let mut previous = None;
+
+            loop {
+                std::thread::sleep(POLLING_INTERVAL);
+
+                let (Ok(cursor), Ok(origin), Ok(scale_factor)) = (
+                    window.cursor_position(),
+                    window.inner_position(),
+                    window.scale_factor(),
+                ) else {
+                    // The window is closed.
+                    break;
+                };
+                let x = (cursor.x - origin.x as f64) / scale_factor;
+                let y = (cursor.y - origin.y as f64) / scale_factor;
+
+                // Toggle while the regions are locked, so that it can't overwrite
+                // the state set by the owner after the regions are removed.
+                let tracked = with_regions(&window, |regions| {
+                    let inside = regions.iter().any(|region| region.contains(x, y));
+                    if previous == Some(inside) {
+                        return;
+                    }
+                    previous = Some(inside);
+
+                    if let Err(e) = set_ignore(&window, !inside) {
+                        log::warn!("Failed to toggle pointer events of input regions. Detail: {e}");
+                    }
+                });
+                if tracked.is_none() {
+                    break;
+                }
+            }
+
+            log::debug!("Stop tracking input regions of `{}`.", window.label());
+        });
+    }
+}
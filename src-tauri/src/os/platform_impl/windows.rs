@@ -1,5 +1,5 @@
 use anyhow::Context as _;
-use tauri::{LogicalPosition, Manager, WebviewWindow};
+use tauri::{LogicalPosition, LogicalSize, Manager, WebviewWindow};
 use windows::Win32::{Foundation::*, UI::WindowsAndMessaging::*};
 
 fn get_ex_style(hwnd: HWND) -> anyhow::Result<WINDOW_EX_STYLE> {
@@ -133,14 +133,112 @@ impl crate::os::WebviewWindowPlatformExt for WebviewWindow {
         manage_window_ex_style(hwnd, ignore, WS_EX_TRANSPARENT)
     }
 
-    fn set_position_with_adjustment(&self, x: f64, y: f64) -> anyhow::Result<()> {
+    fn set_input_regions(
+        &self,
+        regions: Option<Vec<crate::os::InputRegion>>,
+    ) -> anyhow::Result<()> {
+        // The window is drawn as a whole, and only pointer events are toggled.
+        if crate::os::input_region::set(self, regions) {
+            crate::os::input_region::track(self.clone(), |window, ignore| {
+                manage_window_ex_style(HWND(window.hwnd()?.0), ignore, WS_EX_TRANSPARENT)
+            });
+        }
+        Ok(())
+    }
+
+    fn deny_permissions(&self) -> anyhow::Result<()> {
+        use webview2_com::{
+            Microsoft::Web::WebView2::Win32::COREWEBVIEW2_PERMISSION_STATE_DENY,
+            PermissionRequestedEventHandler,
+        };
+
+        self.with_webview(|webview| {
+            let handler = PermissionRequestedEventHandler::create(Box::new(|_, args| {
+                if let Some(args) = args {
+                    unsafe { args.SetState(COREWEBVIEW2_PERMISSION_STATE_DENY)? };
+                }
+                Ok(())
+            }));
+
+            // The handler is kept until the webview is closed, so the token is not needed.
+            let mut token = 0;
+            let result = unsafe {
+                webview
+                    .controller()
+                    .CoreWebView2()
+                    .and_then(|core| core.add_PermissionRequested(&handler, &mut token))
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to deny permissions of webview. Detail: {e}");
+            }
+        })
+        .context("Failed to access webview")
+    }
+
+    fn monitor_refresh_rate(&self) -> anyhow::Result<f64> {
+        use windows::{
+            Win32::Graphics::Gdi::{
+                DEVMODEW, ENUM_CURRENT_SETTINGS, EnumDisplaySettingsW, GetMonitorInfoW,
+                MONITOR_DEFAULTTONEAREST, MONITORINFOEXW, MonitorFromWindow,
+            },
+            core::PCWSTR,
+        };
+
+        let hwnd = HWND(self.hwnd().unwrap().0);
+        let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = size_of::<MONITORINFOEXW>() as u32;
+        unsafe { GetMonitorInfoW(monitor, &mut info.monitorInfo) }
+            .ok()
+            .context("Failed to get monitor info")?;
+
+        let mut mode = DEVMODEW {
+            dmSize: size_of::<DEVMODEW>() as u16,
+            ..Default::default()
+        };
+        unsafe {
+            EnumDisplaySettingsW(
+                PCWSTR(info.szDevice.as_ptr()),
+                ENUM_CURRENT_SETTINGS,
+                &mut mode,
+            )
+        }
+        .ok()
+        .context("Failed to get display settings")?;
+
+        // `0` and `1` mean the default rate of the hardware, which is unknown.
+        anyhow::ensure!(mode.dmDisplayFrequency > 1, "The refresh rate is unknown");
+        Ok(mode.dmDisplayFrequency as f64)
+    }
+
+    fn set_position_with_adjustment(
+        &self,
+        x: f64,
+        y: f64,
+        scale_factor: f64,
+    ) -> anyhow::Result<()> {
         let diff = self.state::<DiffPos>();
 
-        let pos = LogicalPosition::new(x + diff.x, y + diff.y);
+        // Tauri converts logical position with the current scale factor of the overlay window.
+        // But the overlay may be still on the previous monitor, so convert it by ourselves.
+        let pos = LogicalPosition::new(x + diff.x, y + diff.y).to_physical::<f64>(scale_factor);
         self.set_position(pos)?;
 
         Ok(())
     }
+
+    fn set_size_with_adjustment(
+        &self,
+        width: f64,
+        height: f64,
+        scale_factor: f64,
+    ) -> anyhow::Result<()> {
+        let size = LogicalSize::new(width, height).to_physical::<f64>(scale_factor);
+        self.set_size(size)?;
+
+        Ok(())
+    }
 }
 
 impl crate::os::WindowPlatformExt for window_getter::Window {
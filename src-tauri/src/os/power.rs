@@ -0,0 +1,199 @@
+/// The source of power that the computer is running on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+}
+
+pub fn get_power_source() -> anyhow::Result<PowerSource> {
+    #[cfg(target_os = "macos")]
+    return macos::get_power_source();
+    #[cfg(target_os = "windows")]
+    return windows::get_power_source();
+    #[cfg(target_os = "linux")]
+    return sysfs::get_power_source_from(&sysfs::power_supply_dir());
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use anyhow::Context as _;
+    use objc2_io_kit::{IOPSCopyPowerSourcesInfo, IOPSGetProvidingPowerSourceType};
+
+    use super::PowerSource;
+
+    /// The value of `kIOPMBatteryPowerKey`.
+    const BATTERY_POWER: &str = "Battery Power";
+
+    pub fn get_power_source() -> anyhow::Result<PowerSource> {
+        let info = IOPSCopyPowerSourcesInfo().context("Failed to get power sources info")?;
+
+        // SAFETY: `info` is the snapshot returned by `IOPSCopyPowerSourcesInfo`.
+        let source_type = unsafe { IOPSGetProvidingPowerSourceType(Some(&info)) };
+
+        match source_type {
+            Some(source_type) if source_type.to_string() == BATTERY_POWER => {
+                Ok(PowerSource::Battery)
+            }
+            _ => Ok(PowerSource::Ac),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use anyhow::Context as _;
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    use super::PowerSource;
+
+    pub fn get_power_source() -> anyhow::Result<PowerSource> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.context("Failed to get power status")?;
+
+        // 0 means offline, 1 means online and 255 means unknown.
+        if status.ACLineStatus == 0 {
+            Ok(PowerSource::Battery)
+        } else {
+            Ok(PowerSource::Ac)
+        }
+    }
+}
+
+/// Power source detection through sysfs.
+///
+/// Set `OVERKAMI_POWER_SUPPLY_DIR` to read another directory than `/sys/class/power_supply`.
+/// e.g. a fake sysfs directory.
+/// Reading the directory works on any platform, so it is tested everywhere.
+#[cfg(any(target_os = "linux", test))]
+mod sysfs {
+    use std::path::Path;
+
+    use anyhow::Context as _;
+
+    use super::PowerSource;
+
+    #[cfg(target_os = "linux")]
+    const DEFAULT_POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+    #[cfg(target_os = "linux")]
+    pub fn power_supply_dir() -> std::path::PathBuf {
+        use std::path::PathBuf;
+
+        std::env::var_os("OVERKAMI_POWER_SUPPLY_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_POWER_SUPPLY_DIR))
+    }
+
+    fn read_attribute(supply: &Path, name: &str) -> String {
+        std::fs::read_to_string(supply.join(name))
+            .map(|value| value.trim().to_owned())
+            .unwrap_or_default()
+    }
+
+    pub fn get_power_source_from(dir: &Path) -> anyhow::Result<PowerSource> {
+        let mut discharging = false;
+
+        for entry in std::fs::read_dir(dir).context("Failed to read power supply directory")? {
+            let supply = entry.context("Failed to read power supply entry")?.path();
+
+            match read_attribute(&supply, "type").as_str() {
+                "Mains" | "USB" if read_attribute(&supply, "online") == "1" => {
+                    return Ok(PowerSource::Ac);
+                }
+                "Battery" if read_attribute(&supply, "status") == "Discharging" => {
+                    discharging = true;
+                }
+                _ => {}
+            }
+        }
+
+        // Desktop computers have no battery, so treat unknown states as AC.
+        if discharging {
+            Ok(PowerSource::Battery)
+        } else {
+            Ok(PowerSource::Ac)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use tempfile::TempDir;
+
+        use super::*;
+
+        /// Adds a power supply with the attributes to the fake sysfs directory.
+        fn supply(dir: &TempDir, name: &str, attributes: &[(&str, &str)]) {
+            let supply = dir.path().join(name);
+            std::fs::create_dir(&supply).unwrap();
+
+            for (attribute, value) in attributes {
+                std::fs::write(supply.join(attribute), format!("{value}\n")).unwrap();
+            }
+        }
+
+        #[test]
+        fn ac_online() {
+            let dir = tempfile::tempdir().unwrap();
+            supply(&dir, "AC", &[("type", "Mains"), ("online", "1")]);
+            supply(&dir, "BAT0", &[("type", "Battery"), ("status", "Charging")]);
+
+            assert_eq!(get_power_source_from(dir.path()).unwrap(), PowerSource::Ac);
+        }
+
+        #[test]
+        fn battery_only() {
+            let dir = tempfile::tempdir().unwrap();
+            supply(
+                &dir,
+                "BAT0",
+                &[("type", "Battery"), ("status", "Discharging")],
+            );
+
+            assert_eq!(
+                get_power_source_from(dir.path()).unwrap(),
+                PowerSource::Battery
+            );
+        }
+
+        #[test]
+        fn mixed_supplies() {
+            let dir = tempfile::tempdir().unwrap();
+            supply(&dir, "AC", &[("type", "Mains"), ("online", "0")]);
+            supply(
+                &dir,
+                "BAT0",
+                &[("type", "Battery"), ("status", "Discharging")],
+            );
+            supply(&dir, "BAT1", &[("type", "Battery"), ("status", "Unknown")]);
+            supply(&dir, "hidpp_battery_0", &[("type", "Battery")]);
+
+            assert_eq!(
+                get_power_source_from(dir.path()).unwrap(),
+                PowerSource::Battery
+            );
+
+            // A charger over USB-C is enough to run on AC.
+            supply(
+                &dir,
+                "ucsi-source-psy-1",
+                &[("type", "USB"), ("online", "1")],
+            );
+
+            assert_eq!(get_power_source_from(dir.path()).unwrap(), PowerSource::Ac);
+        }
+
+        #[test]
+        fn empty_dir() {
+            let dir = tempfile::tempdir().unwrap();
+
+            assert_eq!(get_power_source_from(dir.path()).unwrap(), PowerSource::Ac);
+        }
+
+        #[test]
+        fn missing_dir() {
+            let dir = tempfile::tempdir().unwrap();
+
+            assert!(get_power_source_from(&dir.path().join("power_supply")).is_err());
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    collections::BTreeSet,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use sysinfo::System;
+
+use crate::os::application_monitor::get_application_process;
+
+/// The minimum interval of sampling. Requests within this interval share the same sample.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A read-only snapshot of the system that is exposed to wallpaper pages.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemMetrics {
+    /// The global CPU usage in percent.
+    pub cpu_usage: f32,
+    /// Used memory in bytes.
+    pub used_memory: u64,
+    /// Total memory in bytes.
+    pub total_memory: u64,
+    /// The names of the applications which have windows. Sorted and deduplicated.
+    pub applications: Vec<String>,
+    /// When this sample is taken, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+struct Sampler {
+    system: System,
+    last: Option<(Instant, SystemMetrics)>,
+}
+
+static SAMPLER: LazyLock<Mutex<Sampler>> = LazyLock::new(|| {
+    Mutex::new(Sampler {
+        system: System::new(),
+        last: None,
+    })
+});
+
+/// Returns the latest sample, taking a new one only if the cached one is older than [`MIN_INTERVAL`].
+pub fn get_system_metrics() -> SystemMetrics {
+    let mut sampler = SAMPLER.lock().unwrap();
+
+    if let Some((taken_at, metrics)) = &sampler.last
+        && taken_at.elapsed() < MIN_INTERVAL
+    {
+        return metrics.clone();
+    }
+
+    // CPU usage is computed from the difference to the previous refresh,
+    // so the first sample is always `0`.
+    sampler.system.refresh_cpu_usage();
+    sampler.system.refresh_memory();
+
+    let metrics = SystemMetrics {
+        cpu_usage: sampler.system.global_cpu_usage(),
+        used_memory: sampler.system.used_memory(),
+        total_memory: sampler.system.total_memory(),
+        applications: get_application_names(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64),
+    };
+    sampler.last = Some((Instant::now(), metrics.clone()));
+
+    metrics
+}
+
+fn get_application_names() -> Vec<String> {
+    let windows = match window_getter::get_windows() {
+        Ok(windows) => windows,
+        Err(e) => {
+            log::warn!("Failed to get windows for system metrics. Detail: {e:?}");
+            return Vec::new();
+        }
+    };
+
+    let names: BTreeSet<_> = windows
+        .iter()
+        .filter_map(|window| window.owner_pid().ok())
+        .filter(|pid| std::process::id() != *pid as u32)
+        .filter_map(|pid| get_application_process(pid as _))
+        .map(|process| process.name)
+        .collect();
+
+    names.into_iter().collect()
+}
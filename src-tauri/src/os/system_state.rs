@@ -0,0 +1,538 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, async_runtime::Sender};
+
+use crate::os::power::PowerSource;
+
+/// Events of system states that affect overlays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemEvent {
+    Suspended,
+    Resumed,
+    Locked,
+    Unlocked,
+    DisplaysChanged,
+    PowerSourceChanged(PowerSource),
+}
+
+pub type SystemEventTx = Sender<SystemEvent>;
+
+/// The interval of polling system states that can't be notified by the platform.
+const POLLING_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Starts observing system states and sends events to the provided channel.
+pub fn start(app: AppHandle, tx: SystemEventTx) -> anyhow::Result<()> {
+    displays::start(app, tx.clone());
+    power::start(tx.clone());
+
+    #[cfg(target_os = "linux")]
+    logind::start(tx)?;
+    #[cfg(target_os = "macos")]
+    workspace_notifications::start(tx)?;
+    #[cfg(target_os = "windows")]
+    session_messages::start(tx)?;
+
+    Ok(())
+}
+
+/// Detection of monitor hot-plug and configuration changes by polling monitors.
+mod displays {
+    use tauri::{AppHandle, Monitor};
+
+    use super::{POLLING_INTERVAL, SystemEvent, SystemEventTx};
+
+    #[derive(Debug, PartialEq)]
+    struct MonitorSnapshot {
+        name: Option<String>,
+        position: (i32, i32),
+        size: (u32, u32),
+        scale_factor: f64,
+    }
+
+    impl From<&Monitor> for MonitorSnapshot {
+        fn from(monitor: &Monitor) -> Self {
+            Self {
+                name: monitor.name().cloned(),
+                position: (monitor.position().x, monitor.position().y),
+                size: (monitor.size().width, monitor.size().height),
+                scale_factor: monitor.scale_factor(),
+            }
+        }
+    }
+
+    fn snapshot(app: &AppHandle) -> Option<Vec<MonitorSnapshot>> {
+        match app.available_monitors() {
+            Ok(monitors) => Some(monitors.iter().map(MonitorSnapshot::from).collect()),
+            Err(e) => {
+                log::warn!("Failed to get monitors. Detail: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn start(app: AppHandle, tx: SystemEventTx) {
+        std::thread::spawn(move || {
+            let mut previous = snapshot(&app);
+
+            loop {
+                std::thread::sleep(POLLING_INTERVAL);
+
+                let current = snapshot(&app);
+                if current.is_none() || current == previous {
+                    continue;
+                }
+                previous = current;
+
+                log::info!("Display configuration is changed.");
+                if tx.blocking_send(SystemEvent::DisplaysChanged).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Detection of switches between AC and battery power by polling the power source.
+mod power {
+    use crate::os::power::get_power_source;
+
+    use super::{POLLING_INTERVAL, SystemEvent, SystemEventTx};
+
+    pub fn start(tx: SystemEventTx) {
+        std::thread::spawn(move || {
+            // The first reading is always sent, so that the initial state is known.
+            let mut previous = None;
+
+            loop {
+                match get_power_source() {
+                    Ok(current) if previous != Some(current) => {
+                        previous = Some(current);
+
+                        log::info!("Power source is changed to {current:?}.");
+                        if tx
+                            .blocking_send(SystemEvent::PowerSourceChanged(current))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Failed to get power source. Detail: {e}"),
+                }
+
+                std::thread::sleep(POLLING_INTERVAL);
+            }
+        });
+    }
+}
+
+/// Observation of sleep and screen lock through logind D-Bus signals.
+///
+/// Set `OVERKAMI_LOGIND_BUS_ADDRESS` to connect to another bus than the system bus.
+/// e.g. a local D-Bus session that stands in for logind.
+#[cfg(target_os = "linux")]
+mod logind {
+    use anyhow::Context as _;
+    use zbus::{
+        blocking::{Connection, Proxy, connection},
+        zvariant::OwnedObjectPath,
+    };
+
+    use super::{SystemEvent, SystemEventTx};
+
+    const DESTINATION: &str = "org.freedesktop.login1";
+    const MANAGER_PATH: &str = "/org/freedesktop/login1";
+    const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+    const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+    fn connect() -> anyhow::Result<Connection> {
+        match std::env::var("OVERKAMI_LOGIND_BUS_ADDRESS") {
+            Ok(address) => Ok(connection::Builder::address(address.as_str())?.build()?),
+            Err(_) => Ok(Connection::system()?),
+        }
+    }
+
+    pub fn start(tx: SystemEventTx) -> anyhow::Result<()> {
+        let connection = connect().context("Failed to connect to D-Bus")?;
+        listen(&connection, tx)
+    }
+
+    /// Listens to the signals of logind on the bus of `connection`.
+    fn listen(connection: &Connection, tx: SystemEventTx) -> anyhow::Result<()> {
+        let manager = Proxy::new(connection, DESTINATION, MANAGER_PATH, MANAGER_INTERFACE)
+            .context("Failed to create logind manager proxy")?;
+        let session_path: OwnedObjectPath = manager
+            .call("GetSessionByPID", &(std::process::id()))
+            .context("Failed to get current logind session")?;
+
+        // Sleep
+        let sleep_signals = manager
+            .receive_signal("PrepareForSleep")
+            .context("Failed to listen sleep signal")?;
+
+        std::thread::spawn({
+            let tx = tx.clone();
+
+            move || {
+                for message in sleep_signals {
+                    let Ok(start) = message.body().deserialize::<bool>() else {
+                        log::warn!("Received invalid `PrepareForSleep` signal.");
+                        continue;
+                    };
+
+                    let event = if start {
+                        SystemEvent::Suspended
+                    } else {
+                        SystemEvent::Resumed
+                    };
+
+                    if tx.blocking_send(event).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        // Screen lock
+        let session = Proxy::new(connection, DESTINATION, session_path, SESSION_INTERFACE)
+            .context("Failed to create logind session proxy")?;
+
+        for (signal, event) in [
+            ("Lock", SystemEvent::Locked),
+            ("Unlock", SystemEvent::Unlocked),
+        ] {
+            let signals = session
+                .receive_signal(signal)
+                .with_context(|| format!("Failed to listen `{signal}` signal"))?;
+            let tx = tx.clone();
+
+            std::thread::spawn(move || {
+                for _ in signals {
+                    if tx.blocking_send(event).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            io::{BufRead, BufReader},
+            process::{Child, Command, Stdio},
+            time::Duration,
+        };
+
+        use zbus::zvariant::ObjectPath;
+
+        use super::*;
+
+        const SESSION_PATH: &str = "/org/freedesktop/login1/session/test";
+
+        /// A D-Bus daemon only for a test.
+        struct Bus {
+            daemon: Child,
+            address: String,
+        }
+
+        impl Bus {
+            fn start() -> Self {
+                let mut daemon = Command::new("dbus-daemon")
+                    .args(["--session", "--nofork", "--print-address"])
+                    .stdout(Stdio::piped())
+                    .spawn()
+                    .expect("dbus-daemon is required to run this test");
+
+                let mut address = String::new();
+                BufReader::new(daemon.stdout.take().unwrap())
+                    .read_line(&mut address)
+                    .unwrap();
+
+                Self {
+                    daemon,
+                    address: address.trim().to_owned(),
+                }
+            }
+
+            fn builder(&self) -> connection::Builder<'_> {
+                connection::Builder::address(self.address.as_str()).unwrap()
+            }
+
+            fn connect(&self) -> Connection {
+                self.builder().build().unwrap()
+            }
+        }
+
+        impl Drop for Bus {
+            fn drop(&mut self) {
+                let _ = self.daemon.kill();
+                let _ = self.daemon.wait();
+            }
+        }
+
+        /// Stands in for the manager of logind.
+        struct Manager;
+
+        #[zbus::interface(name = "org.freedesktop.login1.Manager")]
+        impl Manager {
+            #[zbus(name = "GetSessionByPID")]
+            fn get_session_by_pid(&self, _pid: u32) -> OwnedObjectPath {
+                ObjectPath::from_static_str_unchecked(SESSION_PATH).into()
+            }
+        }
+
+        /// Starts logind which only answers the session of the process.
+        fn start_logind(bus: &Bus) -> Connection {
+            bus.builder()
+                .name(DESTINATION)
+                .unwrap()
+                .serve_at(MANAGER_PATH, Manager)
+                .unwrap()
+                .build()
+                .unwrap()
+        }
+
+        /// Waits for the next event for a while.
+        fn next_event(rx: &mut tauri::async_runtime::Receiver<SystemEvent>) -> SystemEvent {
+            for _ in 0..50 {
+                if let Ok(event) = rx.try_recv() {
+                    return event;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            panic!("No event was sent");
+        }
+
+        #[test]
+        fn logind_signals_are_sent_as_events() {
+            let bus = Bus::start();
+            let logind = start_logind(&bus);
+
+            let (tx, mut rx) = tauri::async_runtime::channel(8);
+            listen(&bus.connect(), tx).unwrap();
+
+            // Both sleep signals arrive in order since they are listened on one thread.
+            for start in [true, false] {
+                logind
+                    .emit_signal(
+                        None::<()>,
+                        MANAGER_PATH,
+                        MANAGER_INTERFACE,
+                        "PrepareForSleep",
+                        &start,
+                    )
+                    .unwrap();
+            }
+            assert_eq!(next_event(&mut rx), SystemEvent::Suspended);
+            assert_eq!(next_event(&mut rx), SystemEvent::Resumed);
+
+            for (signal, expected) in [
+                ("Lock", SystemEvent::Locked),
+                ("Unlock", SystemEvent::Unlocked),
+            ] {
+                // `Lock` and `Unlock` are listened on separate threads, so wait for each event.
+                logind
+                    .emit_signal(None::<()>, SESSION_PATH, SESSION_INTERFACE, signal, &())
+                    .unwrap();
+                assert_eq!(next_event(&mut rx), expected);
+            }
+        }
+
+        #[test]
+        fn start_fails_without_logind() {
+            let bus = Bus::start();
+            let (tx, _rx) = tauri::async_runtime::channel(8);
+
+            assert!(listen(&bus.connect(), tx).is_err());
+        }
+    }
+}
+
+/// Observation of sleep and screen lock through notifications.
+///
+/// Sleep is notified by the notification center of `NSWorkspace`, and screen lock by the
+/// distributed notifications that the login window posts.
+#[cfg(target_os = "macos")]
+mod workspace_notifications {
+    use std::ptr::NonNull;
+
+    use anyhow::Context as _;
+    use block2::RcBlock;
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification,
+    };
+    use objc2_foundation::{
+        NSDistributedNotificationCenter, NSNotification, NSNotificationCenter, NSNotificationName,
+        NSString,
+    };
+
+    use super::{SystemEvent, SystemEventTx};
+
+    /// Must be called on the main thread, whose run loop delivers the notifications.
+    pub fn start(tx: SystemEventTx) -> anyhow::Result<()> {
+        MainThreadMarker::new().context("Notifications must be observed on the main thread")?;
+
+        let workspace_center = NSWorkspace::sharedWorkspace().notificationCenter();
+        for (name, event) in unsafe {
+            [
+                (NSWorkspaceWillSleepNotification, SystemEvent::Suspended),
+                (NSWorkspaceDidWakeNotification, SystemEvent::Resumed),
+            ]
+        } {
+            observe(&workspace_center, name, event, &tx);
+        }
+
+        let distributed_center = NSDistributedNotificationCenter::defaultCenter();
+        for (name, event) in [
+            ("com.apple.screenIsLocked", SystemEvent::Locked),
+            ("com.apple.screenIsUnlocked", SystemEvent::Unlocked),
+        ] {
+            observe(&distributed_center, &NSString::from_str(name), event, &tx);
+        }
+
+        Ok(())
+    }
+
+    fn observe(
+        center: &NSNotificationCenter,
+        name: &NSNotificationName,
+        event: SystemEvent,
+        tx: &SystemEventTx,
+    ) {
+        let tx = tx.clone();
+        let block = RcBlock::new(move |_: NonNull<NSNotification>| {
+            // This runs on the main thread, so it must not wait for the receiver.
+            if let Err(e) = tx.try_send(event) {
+                log::warn!("Failed to send system event {event:?}. Detail: {e}");
+            }
+        });
+
+        // The observer stays registered as long as the app runs, so the token is not kept.
+        let _ = unsafe {
+            center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &block)
+        };
+    }
+}
+
+/// Observation of sleep and screen lock through the messages to a hidden window.
+///
+/// `WM_POWERBROADCAST` is only sent to top-level windows, so the window is not a message-only one.
+#[cfg(target_os = "windows")]
+mod session_messages {
+    use std::cell::OnceCell;
+
+    use anyhow::Context as _;
+    use windows::{
+        Win32::{
+            Foundation::*,
+            System::{
+                LibraryLoader::GetModuleHandleW,
+                RemoteDesktop::{NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification},
+            },
+            UI::WindowsAndMessaging::*,
+        },
+        core::w,
+    };
+
+    use super::{SystemEvent, SystemEventTx};
+
+    thread_local! {
+        /// The sender for the window procedure, which runs on the thread of the window.
+        static TX: OnceCell<SystemEventTx> = const { OnceCell::new() };
+    }
+
+    pub fn start(tx: SystemEventTx) -> anyhow::Result<()> {
+        let (created_tx, created_rx) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            TX.with(|cell| {
+                let _ = cell.set(tx);
+            });
+
+            let created = create_window();
+            let failed = created.is_err();
+            let _ = created_tx.send(created);
+            if failed {
+                return;
+            }
+
+            let mut message = MSG::default();
+            while unsafe { GetMessageW(&mut message, None, 0, 0) }.as_bool() {
+                unsafe { DispatchMessageW(&message) };
+            }
+        });
+
+        created_rx
+            .recv()
+            .context("The thread of the hidden window is stopped")?
+    }
+
+    fn create_window() -> anyhow::Result<()> {
+        let instance = unsafe { GetModuleHandleW(None) }.context("Failed to get module handle")?;
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(window_procedure),
+            hInstance: instance.into(),
+            lpszClassName: w!("OverkamiSystemState"),
+            ..Default::default()
+        };
+        anyhow::ensure!(
+            unsafe { RegisterClassW(&class) } != 0,
+            "Failed to register window class"
+        );
+
+        let hwnd = unsafe {
+            CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                class.lpszClassName,
+                w!(""),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                None,
+                None,
+                Some(class.hInstance),
+                None,
+            )
+        }
+        .context("Failed to create hidden window")?;
+
+        unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) }
+            .context("Failed to register session notification")?;
+
+        Ok(())
+    }
+
+    extern "system" fn window_procedure(
+        hwnd: HWND,
+        message: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        let event = match (message, wparam.0 as u32) {
+            (WM_POWERBROADCAST, PBT_APMSUSPEND) => Some(SystemEvent::Suspended),
+            // This is sent on every wake, while `PBT_APMRESUMESUSPEND` is only sent
+            // when the wake is caused by the user.
+            (WM_POWERBROADCAST, PBT_APMRESUMEAUTOMATIC) => Some(SystemEvent::Resumed),
+            (WM_WTSSESSION_CHANGE, WTS_SESSION_LOCK) => Some(SystemEvent::Locked),
+            (WM_WTSSESSION_CHANGE, WTS_SESSION_UNLOCK) => Some(SystemEvent::Unlocked),
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            TX.with(|tx| {
+                if let Some(tx) = tx.get() {
+                    let _ = tx.blocking_send(event);
+                }
+            });
+        }
+
+        unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+    }
+}
@@ -1,6 +1,6 @@
 use percent_encoding::utf8_percent_encode;
 
-pub use scale_factor::{adjust_position, adjust_size};
+pub use scale_factor::{MonitorArea, adjust_position, adjust_size, scale_factor_for_bounds};
 
 /// JavaScriptの`convertFileSrc`APIのRust版。
 pub fn convert_file_src(path: impl AsRef<std::path::Path>) -> std::io::Result<String> {
@@ -18,8 +18,71 @@ pub fn convert_file_src(path: impl AsRef<std::path::Path>) -> std::io::Result<St
 
 /// Adjusts the size and position of a window based on the scale factor of the current platform.
 /// Windows will return physical window rect so we need to convert it to logical version on windows.
+/// The scale factor must be the one of the monitor that contains the target window.
 mod scale_factor {
-    use tauri::{LogicalPosition, LogicalSize};
+    use tauri::{LogicalPosition, LogicalSize, Monitor};
+
+    /// The area of a monitor in the same coordinate space as target window bounds.
+    /// It is logical on macOS and physical on Windows.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct MonitorArea {
+        pub x: f64,
+        pub y: f64,
+        pub width: f64,
+        pub height: f64,
+        pub scale_factor: f64,
+    }
+
+    impl MonitorArea {
+        pub fn from_monitor(monitor: &Monitor) -> Self {
+            let scale_factor = monitor.scale_factor();
+
+            #[cfg(target_os = "macos")]
+            let (position, size) = (
+                monitor.position().to_logical::<f64>(scale_factor),
+                monitor.size().to_logical::<f64>(scale_factor),
+            );
+            #[cfg(target_os = "windows")]
+            let (position, size) = (
+                monitor.position().cast::<f64>(),
+                monitor.size().cast::<f64>(),
+            );
+
+            Self {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                scale_factor,
+            }
+        }
+
+        fn intersection_area(&self, x: f64, y: f64, width: f64, height: f64) -> f64 {
+            let overlap_width = ((x + width).min(self.x + self.width) - x.max(self.x)).max(0.);
+            let overlap_height = ((y + height).min(self.y + self.height) - y.max(self.y)).max(0.);
+
+            overlap_width * overlap_height
+        }
+    }
+
+    /// Returns the scale factor of the monitor that contains the given bounds.
+    /// When the bounds span multiple monitors, the monitor that has the largest
+    /// intersection with them is used. This is the same as how Windows decides the DPI of a window.
+    /// On a tie, the monitor listed first is used.
+    pub fn scale_factor_for_bounds(
+        monitors: &[MonitorArea],
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) -> Option<f64> {
+        monitors
+            .iter()
+            .map(|monitor| (monitor, monitor.intersection_area(x, y, width, height)))
+            .filter(|(_, area)| *area > 0.)
+            .reduce(|largest, next| if next.1 > largest.1 { next } else { largest })
+            .map(|(monitor, _)| monitor.scale_factor)
+    }
 
     pub fn adjust_size(_scale_factor: f64, width: f64, height: f64) -> LogicalSize<f64> {
         #[cfg(target_os = "macos")]
@@ -42,4 +105,80 @@ mod scale_factor {
             tauri::PhysicalPosition::new(x, y).to_logical(_scale_factor)
         }
     }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn monitor(x: f64, width: f64, scale_factor: f64) -> MonitorArea {
+            MonitorArea {
+                x,
+                y: 0.,
+                width,
+                height: 1080.,
+                scale_factor,
+            }
+        }
+
+        #[test]
+        fn single_monitor() {
+            let monitors = [monitor(0., 1920., 2.)];
+
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, 100., 100., 800., 600.),
+                Some(2.)
+            );
+        }
+
+        #[test]
+        fn straddling_target_uses_monitor_with_larger_intersection() {
+            let monitors = [monitor(0., 1920., 1.), monitor(1920., 1920., 2.)];
+
+            // 300px on the first monitor and 500px on the second one.
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, 1620., 100., 800., 600.),
+                Some(2.)
+            );
+            // 500px on the first monitor and 300px on the second one.
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, 1420., 100., 800., 600.),
+                Some(1.)
+            );
+        }
+
+        #[test]
+        fn off_screen_target_has_no_scale_factor() {
+            let monitors = [monitor(0., 1920., 1.), monitor(1920., 1920., 2.)];
+
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, 4000., 100., 800., 600.),
+                None
+            );
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, 100., -700., 800., 600.),
+                None
+            );
+            // Touching an edge is not an intersection.
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, -800., 100., 800., 600.),
+                None
+            );
+        }
+
+        #[test]
+        fn exact_tie_uses_first_monitor() {
+            let target = (1520., 100., 800., 600.);
+
+            let monitors = [monitor(0., 1920., 1.), monitor(1920., 1920., 2.)];
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, target.0, target.1, target.2, target.3),
+                Some(1.)
+            );
+
+            let monitors = [monitor(1920., 1920., 2.), monitor(0., 1920., 1.)];
+            assert_eq!(
+                scale_factor_for_bounds(&monitors, target.0, target.1, target.2, target.3),
+                Some(2.)
+            );
+        }
+    }
 }
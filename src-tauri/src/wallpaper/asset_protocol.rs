@@ -0,0 +1,186 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use percent_encoding::percent_decode_str;
+use tauri::{
+    AppHandle, Manager, UriSchemeContext, UriSchemeResponder,
+    http::{Request, StatusCode},
+};
+use uuid::Uuid;
+use window_getter::WindowId;
+
+use crate::{
+    config::{BatteryBehavior, Config, Wallpaper, WallpaperSource},
+    wallpaper::{
+        manifest::property_files,
+        protocol::{serve_file, status},
+        remote_picture::RemotePictures,
+        template,
+    },
+};
+
+/// The scheme of the protocol used by `convertFileSrc`. This replaces the one of Tauri.
+pub const SCHEME: &str = "asset";
+
+/// The files that the asset protocol serves. Everything else is denied.
+///
+/// Tauri's scope of the asset protocol can only be extended at runtime,
+/// so the protocol is served by ourselves with this scope instead.
+#[derive(Default)]
+pub struct AssetScope {
+    files: RwLock<HashSet<PathBuf>>,
+    /// The files filled from templated sources, per wallpaper and target window of the overlay.
+    resolved: RwLock<HashMap<(Uuid, WindowId), HashSet<PathBuf>>>,
+    /// The items of the slideshow per wallpaper, which the slideshows keep up to date.
+    slideshows: RwLock<HashMap<Uuid, HashSet<PathBuf>>>,
+}
+
+/// Recomputes the scope from the files used by the wallpapers in `config`.
+pub fn update_scope(app: &AppHandle, config: &Config) {
+    let files: HashSet<_> = config
+        .wallpapers
+        .values()
+        .flat_map(|wallpaper| wallpaper_files(app, wallpaper))
+        .map(|path| dunce::canonicalize(&path).unwrap_or(path))
+        .collect();
+    log::debug!("Update asset protocol scope: {files:?}");
+
+    let scope = app.state::<AssetScope>();
+    *scope.files.write().unwrap() = files;
+    scope
+        .slideshows
+        .write()
+        .unwrap()
+        .retain(|id, _| config.wallpapers.contains_key(id));
+    // Overlays fill the templates again when their sources are applied.
+    scope.resolved.write().unwrap().retain(|(id, _), _| {
+        config
+            .wallpapers
+            .get(id)
+            .is_some_and(|wallpaper| template::is_templated(&wallpaper.source))
+    });
+}
+
+/// Replaces the items of the slideshow of the wallpaper in the scope.
+pub fn set_slideshow_files(app: &AppHandle, wallpaper_id: Uuid, items: Vec<PathBuf>) {
+    let items: HashSet<_> = items
+        .into_iter()
+        .map(|path| dunce::canonicalize(&path).unwrap_or(path))
+        .collect();
+    log::debug!("Update slideshow files of wallpaper `{wallpaper_id}` in asset protocol scope");
+
+    let scope = app.state::<AssetScope>();
+    let mut slideshows = scope.slideshows.write().unwrap();
+    if items.is_empty() {
+        slideshows.remove(&wallpaper_id);
+    } else {
+        slideshows.insert(wallpaper_id, items);
+    }
+}
+
+/// Replaces the files filled from templated sources by the overlay for the window in the scope.
+pub fn set_overlay_files(
+    app: &AppHandle,
+    wallpaper_id: Uuid,
+    window_id: WindowId,
+    files: Vec<PathBuf>,
+) {
+    let files: HashSet<_> = files
+        .into_iter()
+        .filter_map(|path| dunce::canonicalize(path).ok())
+        .collect();
+    log::debug!("Update templated files in asset protocol scope: {files:?}");
+
+    let scope = app.state::<AssetScope>();
+    let mut resolved = scope.resolved.write().unwrap();
+    if files.is_empty() {
+        resolved.remove(&(wallpaper_id, window_id));
+    } else {
+        resolved.insert((wallpaper_id, window_id), files);
+    }
+}
+
+/// Removes the files filled by the overlay for the window from the scope.
+pub fn forget_overlay(app: &AppHandle, wallpaper_id: Uuid, window_id: WindowId) {
+    let scope = app.state::<AssetScope>();
+    scope
+        .resolved
+        .write()
+        .unwrap()
+        .remove(&(wallpaper_id, window_id));
+}
+
+fn wallpaper_files(app: &AppHandle, wallpaper: &Wallpaper) -> Vec<PathBuf> {
+    let mut files = property_files(&wallpaper.source, &wallpaper.properties);
+
+    match &wallpaper.source {
+        WallpaperSource::Picture { location, .. }
+        | WallpaperSource::Video { location, .. }
+        | WallpaperSource::LocalWebPage { location } => files.push(location.clone()),
+        // The items of slideshows are added by the slideshows themselves.
+        WallpaperSource::Slideshow { .. } => {}
+        WallpaperSource::RemotePicture { location, .. } => {
+            files.extend(app.state::<RemotePictures>().file(location))
+        }
+        WallpaperSource::RemoteWebPage { .. }
+        | WallpaperSource::YouTube { .. }
+        | WallpaperSource::Color { .. }
+        | WallpaperSource::Gradient { .. } => {}
+    }
+
+    if let BatteryBehavior::StaticFallback { location } = &wallpaper.on_battery {
+        files.push(location.clone());
+    }
+
+    files.extend(wallpaper.template_fallback.clone());
+    files.extend(wallpaper.preview.clone());
+
+    files
+}
+
+/// Handles requests to the protocol, whose URLs are like `asset://localhost/<encoded path>`.
+pub fn handle(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+
+    tauri::async_runtime::spawn(async move {
+        let path = request.uri().path().trim_start_matches('/');
+        let path = PathBuf::from(percent_decode_str(path).decode_utf8_lossy().as_ref());
+
+        let response = if is_allowed(&app, &path) {
+            serve_file(path, &request).await
+        } else {
+            log::warn!("Blocked request to the file out of asset protocol scope: {path:?}");
+            status(StatusCode::FORBIDDEN)
+        };
+
+        responder.respond(response);
+    });
+}
+
+fn is_allowed(app: &AppHandle, path: &Path) -> bool {
+    let Ok(path) = dunce::canonicalize(path) else {
+        return false;
+    };
+
+    let scope = app.state::<AssetScope>();
+    scope.files.read().unwrap().contains(&path)
+        || scope
+            .resolved
+            .read()
+            .unwrap()
+            .values()
+            .any(|files| files.contains(&path))
+        || scope
+            .slideshows
+            .read()
+            .unwrap()
+            .values()
+            .any(|items| items.contains(&path))
+}
@@ -0,0 +1,71 @@
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::WebviewWindow;
+
+/// The state of the target window. `darkMode` is added by the page side.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetState {
+    /// The logical width of the target window.
+    pub width: f64,
+    /// The logical height of the target window.
+    pub height: f64,
+    pub focused: bool,
+    pub visible: bool,
+    pub title: Option<String>,
+    pub application_name: Option<String>,
+}
+
+pub type SharedTargetState = Arc<Mutex<TargetState>>;
+
+/// The script of the opt-in JavaScript API injected into overlay webviews before pages are loaded.
+///
+/// Pages read the state from `window.overkami.window` and are notified by `overkami:window`,
+/// `overkami:show` and `overkami:hide` events on `window`.
+pub const INIT_SCRIPT: &str = r#"
+(() => {
+  const darkMode = window.matchMedia("(prefers-color-scheme: dark)");
+  let state = null;
+
+  const current = () =>
+    state === null ? null : { ...state, darkMode: darkMode.matches };
+  const notify = (name) =>
+    window.dispatchEvent(new CustomEvent(name, { detail: current() }));
+
+  Object.defineProperty(window, "overkami", {
+    value: Object.freeze({
+      get window() {
+        return current();
+      },
+    }),
+  });
+
+  Object.defineProperty(window, "__overkamiBridge", {
+    value: Object.freeze({
+      update(next) {
+        const previous = state;
+        state = next;
+
+        notify("overkami:window");
+        if (previous !== null && previous.visible !== next.visible) {
+          notify(next.visible ? "overkami:show" : "overkami:hide");
+        }
+      },
+    }),
+  });
+
+  darkMode.addEventListener("change", () => {
+    if (state !== null) notify("overkami:window");
+  });
+})();
+"#;
+
+/// Sends the state of the target window to the page.
+pub fn send(window: &WebviewWindow, state: &TargetState) {
+    let state = serde_json::to_string(state).expect("Failed to serialize target window state");
+
+    if let Err(e) = window.eval(format!("window.__overkamiBridge?.update({state});")) {
+        log::warn!("Failed to send target window state to overlay. Detail: {e}");
+    }
+}
@@ -0,0 +1,644 @@
+//! Wallpaper bundles, which are zip archives with the `.overkami` extension.
+//!
+//! A bundle has `wallpaper.json` and the files used by the wallpaper under `files/`.
+//! The paths in `wallpaper.json` are relative to the root of the bundle.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context as _, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    config::{BatteryBehavior, Wallpaper, WallpaperSource},
+    wallpaper::{manifest::file_property_keys, slideshow, template},
+};
+
+const DEFINITION_FILE_NAME: &str = "wallpaper.json";
+const FILES_DIR: &str = "files";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Definition {
+    version: u32,
+    pub id: Uuid,
+    wallpaper: Wallpaper,
+}
+
+/// What a path referenced by a wallpaper is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PathKind {
+    File,
+    /// The page of a web page wallpaper, which needs the whole directory of it.
+    Page,
+    /// The folder or the playlist of a slideshow.
+    Slideshow,
+}
+
+/// The files put in the bundle with their paths in it.
+#[derive(Default)]
+struct Entries {
+    files: Vec<(PathBuf, String)>,
+    /// The files made for the bundle, such as playlists rewritten for it.
+    generated: Vec<(String, String)>,
+    /// The paths in the bundle of the paths already added, so that they are added only once.
+    added: HashMap<(PathBuf, PathKind), PathBuf>,
+}
+
+impl Entries {
+    /// Adds the file with what it needs, and returns the path of the file in the bundle.
+    fn add(&mut self, path: &Path, kind: PathKind) -> anyhow::Result<PathBuf> {
+        if let Some(added) = self.added.get(&(path.to_path_buf(), kind)) {
+            return Ok(added.clone());
+        }
+
+        let name = path
+            .file_name()
+            .with_context(|| format!("`{}` is not a file", path.display()))?
+            .to_string_lossy()
+            .into_owned();
+        let root = format!("{FILES_DIR}/{}", self.added.len());
+
+        match kind {
+            PathKind::File => {
+                anyhow::ensure!(path.is_file(), "`{}` is not found", path.display());
+                self.files
+                    .push((path.to_path_buf(), format!("{root}/{name}")));
+            }
+            PathKind::Page => {
+                let dir = path.parent().context("The page has no directory")?;
+                self.add_dir(dir, &root)?;
+            }
+            PathKind::Slideshow if path.is_dir() => {
+                self.add_dir(path, &format!("{root}/{name}"))?
+            }
+            PathKind::Slideshow => self.add_playlist(path, &root, &name)?,
+        }
+
+        let added = PathBuf::from(format!("{root}/{name}"));
+        self.added.insert((path.to_path_buf(), kind), added.clone());
+
+        Ok(added)
+    }
+
+    /// Adds the files listed in the playlist next to a new playlist listing them in the bundle.
+    fn add_playlist(&mut self, path: &Path, root: &str, name: &str) -> anyhow::Result<()> {
+        let mut playlist = String::new();
+
+        for (index, item) in slideshow::read_playlist(path)?.into_iter().enumerate() {
+            // Missing files are skipped like the slideshow does.
+            let Some(item_name) = item.file_name().filter(|_| item.is_file()) else {
+                continue;
+            };
+            let item_name = format!("{index}-{}", item_name.to_string_lossy());
+
+            playlist.push_str(&item_name);
+            playlist.push('\n');
+            self.files.push((item, format!("{root}/{item_name}")));
+        }
+
+        self.generated.push((format!("{root}/{name}"), playlist));
+
+        Ok(())
+    }
+
+    fn add_dir(&mut self, dir: &Path, root: &str) -> anyhow::Result<()> {
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory `{}`", dir.display()))?;
+
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+
+            // Linked directories are skipped since they may make loops.
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                self.add_dir(&path, &format!("{root}/{name}"))?;
+            } else if path.is_file() {
+                self.files.push((path, format!("{root}/{name}")));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Calls `f` with every local path referenced by the wallpaper and what the path is.
+fn for_each_path(
+    wallpaper: &mut Wallpaper,
+    file_properties: &[String],
+    mut f: impl FnMut(&mut PathBuf, PathKind) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    // The files of templated sources depend on the windows, so they can't be put in bundles.
+    // Taken from a bundle, they could also point to anywhere.
+    if template::is_templated(&wallpaper.source) {
+        bail!("Wallpapers with templated sources can't be bundled");
+    }
+
+    match &mut wallpaper.source {
+        WallpaperSource::Picture { location, .. } | WallpaperSource::Video { location, .. } => {
+            f(location, PathKind::File)?
+        }
+        WallpaperSource::LocalWebPage { location } => f(location, PathKind::Page)?,
+        WallpaperSource::Slideshow { location, .. } => f(location, PathKind::Slideshow)?,
+        WallpaperSource::RemoteWebPage { .. }
+        | WallpaperSource::RemotePicture { .. }
+        | WallpaperSource::YouTube { .. }
+        | WallpaperSource::Color { .. }
+        | WallpaperSource::Gradient { .. } => {}
+    }
+
+    if let BatteryBehavior::StaticFallback { location } = &mut wallpaper.on_battery {
+        f(location, PathKind::File)?;
+    }
+
+    if let Some(fallback) = &mut wallpaper.template_fallback {
+        f(fallback, PathKind::File)?;
+    }
+
+    if let Some(preview) = &mut wallpaper.preview {
+        f(preview, PathKind::File)?;
+    }
+
+    for key in file_properties {
+        if let Some(Value::String(value)) = wallpaper.properties.get_mut(key)
+            && !value.is_empty()
+        {
+            let mut path = PathBuf::from(&*value);
+            f(&mut path, PathKind::File)?;
+            *value = path.to_string_lossy().into_owned();
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the wallpaper and the files used by it to `path`.
+pub fn export(id: Uuid, wallpaper: &Wallpaper, path: &Path) -> anyhow::Result<()> {
+    let file_properties = file_property_keys(&wallpaper.source);
+    let mut wallpaper = wallpaper.clone();
+    // The library is not in the bundle, so the source is put as an inline one.
+    wallpaper.library_source = None;
+    let mut entries = Entries::default();
+
+    for_each_path(&mut wallpaper, &file_properties, |path, kind| {
+        *path = entries.add(path, kind)?;
+        Ok(())
+    })?;
+
+    let definition = Definition {
+        version: FORMAT_VERSION,
+        id,
+        wallpaper,
+    };
+
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create bundle `{}`", path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file(DEFINITION_FILE_NAME, options)?;
+    serde_json::to_writer_pretty(&mut zip, &definition)?;
+
+    for (source, name) in entries.files {
+        zip.start_file(name, options)?;
+        let mut source = File::open(&source)
+            .with_context(|| format!("Failed to open `{}`", source.display()))?;
+        io::copy(&mut source, &mut zip)?;
+    }
+
+    for (name, content) in entries.generated {
+        zip.start_file(name, options)?;
+        io::Write::write_all(&mut zip, content.as_bytes())?;
+    }
+
+    zip.finish().context("Failed to write bundle")?;
+
+    Ok(())
+}
+
+fn open(path: &Path) -> anyhow::Result<ZipArchive<BufReader<File>>> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open bundle `{}`", path.display()))?;
+
+    ZipArchive::new(BufReader::new(file)).context("The file is not a bundle")
+}
+
+/// Reads the definition of the wallpaper in the bundle.
+pub fn read_definition(path: &Path) -> anyhow::Result<Definition> {
+    let mut archive = open(path)?;
+    let definition = archive
+        .by_name(DEFINITION_FILE_NAME)
+        .with_context(|| format!("`{DEFINITION_FILE_NAME}` is not found in the bundle"))?;
+
+    let definition: Definition =
+        serde_json::from_reader(definition).context("Failed to parse the wallpaper")?;
+    if definition.version > FORMAT_VERSION {
+        bail!(
+            "The bundle is made by a newer version of overkami (format {})",
+            definition.version
+        );
+    }
+
+    Ok(definition)
+}
+
+/// The files of a bundle extracted next to the directory they are for.
+///
+/// The files are moved to the directory by [`Staged::commit`], so that the existing files
+/// are kept until the bundle is extracted successfully.
+pub struct Staged {
+    staging: PathBuf,
+    dir: PathBuf,
+    wallpaper: Wallpaper,
+}
+
+impl Staged {
+    /// Replaces the files in the directory with the extracted ones,
+    /// and returns the wallpaper using them.
+    pub fn commit(self) -> anyhow::Result<Wallpaper> {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir).with_context(|| {
+                format!("Failed to remove old files in `{}`", self.dir.display())
+            })?;
+        }
+        std::fs::rename(&self.staging, &self.dir).with_context(|| {
+            format!("Failed to move extracted files to `{}`", self.dir.display())
+        })?;
+
+        Ok(self.wallpaper)
+    }
+}
+
+/// Extracts the files of the bundle for `dir`, and returns them with the wallpaper using them.
+pub fn extract(path: &Path, definition: Definition, dir: &Path) -> anyhow::Result<Staged> {
+    let staging = dir.with_file_name(format!(
+        "{}.{}.importing",
+        dir.file_name().unwrap_or_default().to_string_lossy(),
+        Uuid::new_v4()
+    ));
+
+    match extract_to(path, definition, &staging, dir) {
+        Ok(wallpaper) => Ok(Staged {
+            staging,
+            dir: dir.to_path_buf(),
+            wallpaper,
+        }),
+        Err(e) => {
+            if staging.exists()
+                && let Err(e) = std::fs::remove_dir_all(&staging)
+            {
+                log::warn!("Failed to remove `{}`. Detail: {e}", staging.display());
+            }
+
+            Err(e)
+        }
+    }
+}
+
+/// Extracts the files to `staging`, and returns the wallpaper using them in `dir`.
+fn extract_to(
+    path: &Path,
+    definition: Definition,
+    staging: &Path,
+    dir: &Path,
+) -> anyhow::Result<Wallpaper> {
+    let mut archive = open(path)?;
+    std::fs::create_dir_all(staging)
+        .with_context(|| format!("Failed to create `{}`", staging.display()))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        let Some(name) = entry.enclosed_name() else {
+            bail!("The bundle has an invalid path `{}`", entry.name());
+        };
+        if !name.starts_with(FILES_DIR) {
+            continue;
+        }
+
+        let target = staging.join(name);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&target)
+            .with_context(|| format!("Failed to create `{}`", target.display()))?;
+        io::copy(&mut entry, &mut file)?;
+    }
+
+    let mut wallpaper = definition.wallpaper;
+
+    // Which properties are files is told only by the manifest in the bundle,
+    // since `wallpaper.json` can name any property.
+    let file_properties = match &wallpaper.source {
+        WallpaperSource::LocalWebPage { location } if is_enclosed(location) => {
+            file_property_keys(&WallpaperSource::LocalWebPage {
+                location: staging.join(location),
+            })
+        }
+        _ => Vec::new(),
+    };
+
+    // The paths come from the bundle, so they must not point to the outside of `dir`.
+    for_each_path(&mut wallpaper, &file_properties, |path, kind| {
+        if !is_enclosed(path) || !path.starts_with(FILES_DIR) {
+            bail!("The bundle has an invalid path `{}`", path.display());
+        }
+
+        // So must the files listed in playlists.
+        let extracted = staging.join(&*path);
+        if kind == PathKind::Slideshow && extracted.is_file() {
+            for item in slideshow::read_playlist(&extracted)? {
+                if !item.strip_prefix(staging).is_ok_and(is_enclosed) {
+                    bail!("The playlist has an invalid path `{}`", item.display());
+                }
+            }
+        }
+
+        *path = dir.join(&*path);
+        Ok(())
+    })?;
+
+    Ok(wallpaper)
+}
+
+fn is_enclosed(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Returns the directory where the files of the bundle of the wallpaper are extracted.
+pub fn files_dir(app: &AppHandle, id: Uuid) -> anyhow::Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_local_data_dir()
+        .context("Failed to get app local data directory")?;
+
+    Ok(dir.join("bundles").join(id.to_string()))
+}
+
+/// Removes the files extracted from the bundle of the wallpaper, if any.
+pub async fn remove_files(app: &AppHandle, id: Uuid) {
+    let result = match files_dir(app, id) {
+        Ok(dir) if dir.exists() => async_fs::remove_dir_all(dir)
+            .await
+            .map_err(anyhow::Error::from),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        log::warn!("Failed to remove bundle files of wallpaper `{id}`. Detail: {e:#}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::{Layout, SlideshowOptions};
+
+    fn wallpaper(source: WallpaperSource) -> Wallpaper {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "applicationName": "Test",
+            "filters": [],
+            "source": source,
+            "opacity": 1.0,
+        }))
+        .unwrap()
+    }
+
+    fn picture(location: impl Into<PathBuf>) -> WallpaperSource {
+        WallpaperSource::Picture {
+            location: location.into(),
+            layout: Layout::default(),
+        }
+    }
+
+    /// Writes a bundle with the wallpaper and the entries as they are.
+    fn write_bundle(path: &Path, wallpaper: Wallpaper, entries: &[(&str, &str)]) -> Definition {
+        let definition = Definition {
+            version: FORMAT_VERSION,
+            id: Uuid::new_v4(),
+            wallpaper,
+        };
+
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        let options = SimpleFileOptions::default();
+        zip.start_file(DEFINITION_FILE_NAME, options).unwrap();
+        serde_json::to_writer(&mut zip, &definition).unwrap();
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            io::Write::write_all(&mut zip, content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        read_definition(path).unwrap()
+    }
+
+    /// Asserts that nothing is left in `root` but the bundle.
+    fn assert_nothing_extracted(root: &Path) {
+        let names: Vec<_> = std::fs::read_dir(root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["test.overkami"]);
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let source_dir = root.join("source");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("a.png"), "a").unwrap();
+        std::fs::write(source_dir.join("b.png"), "b").unwrap();
+        std::fs::write(source_dir.join("preview.png"), "preview").unwrap();
+        std::fs::write(
+            source_dir.join("list.m3u"),
+            "# slides\na.png\nmissing.png\nb.png\n",
+        )
+        .unwrap();
+
+        let mut original = wallpaper(WallpaperSource::Slideshow {
+            location: source_dir.join("list.m3u"),
+            options: SlideshowOptions::default(),
+        });
+        original.preview = Some(source_dir.join("preview.png"));
+        let id = Uuid::new_v4();
+        let path = root.join("test.overkami");
+        export(id, &original, &path).unwrap();
+
+        let definition = read_definition(&path).unwrap();
+        assert_eq!(definition.id, id);
+
+        let dir = root.join("imported");
+        let imported = extract(&path, definition, &dir).unwrap().commit().unwrap();
+
+        let WallpaperSource::Slideshow { location, .. } = &imported.source else {
+            panic!("The source is not a slideshow: {:?}", imported.source);
+        };
+        assert!(location.starts_with(&dir));
+        let items = slideshow::read_playlist(location).unwrap();
+        let contents: Vec<_> = items
+            .iter()
+            .map(|item| std::fs::read_to_string(item).unwrap())
+            .collect();
+        assert_eq!(contents, ["a", "b"]);
+
+        let preview = imported.preview.unwrap();
+        assert!(preview.starts_with(&dir));
+        assert_eq!(std::fs::read_to_string(preview).unwrap(), "preview");
+    }
+
+    #[test]
+    fn commit_replaces_old_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let path = root.join("test.overkami");
+        let definition = write_bundle(
+            &path,
+            wallpaper(picture("files/0/new.png")),
+            &[("files/0/new.png", "new")],
+        );
+        let dir = root.join("imported");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("old.png"), "old").unwrap();
+
+        let staged = extract(&path, definition, &dir).unwrap();
+        // The old files are kept until the new ones are committed.
+        assert!(dir.join("old.png").exists());
+
+        let imported = staged.commit().unwrap();
+        assert!(!dir.join("old.png").exists());
+        assert_eq!(imported.source, picture(dir.join("files/0/new.png")));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("files/0/new.png")).unwrap(),
+            "new"
+        );
+    }
+
+    #[test]
+    fn rejects_entry_with_parent_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let path = root.join("test.overkami");
+        let definition = write_bundle(
+            &path,
+            wallpaper(picture("files/0/a.png")),
+            &[("files/0/a.png", "a"), ("files/../../evil.txt", "evil")],
+        );
+
+        let error = extract(&path, definition, &root.join("imported"))
+            .err()
+            .unwrap();
+
+        assert!(format!("{error:#}").contains("invalid path"));
+        assert!(!root.parent().unwrap().join("evil.txt").exists());
+        assert_nothing_extracted(root);
+    }
+
+    #[test]
+    fn rejects_absolute_path_in_definition() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let outside = root.join("outside.png");
+        std::fs::write(&outside, "outside").unwrap();
+        let path = root.join("test.overkami");
+        let definition = write_bundle(&path, wallpaper(picture(&outside)), &[]);
+        std::fs::remove_file(&outside).unwrap();
+
+        let error = extract(&path, definition, &root.join("imported"))
+            .err()
+            .unwrap();
+
+        assert!(format!("{error:#}").contains("invalid path"));
+        assert_nothing_extracted(root);
+    }
+
+    #[test]
+    fn rejects_playlist_pointing_outside() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let path = root.join("test.overkami");
+        let definition = write_bundle(
+            &path,
+            wallpaper(WallpaperSource::Slideshow {
+                location: PathBuf::from("files/0/list.m3u"),
+                options: SlideshowOptions::default(),
+            }),
+            &[
+                ("files/0/list.m3u", "a.png\n../../../outside.png\n"),
+                ("files/0/a.png", "a"),
+            ],
+        );
+
+        let error = extract(&path, definition, &root.join("imported"))
+            .err()
+            .unwrap();
+
+        assert!(format!("{error:#}").contains("The playlist has an invalid path"));
+        assert_nothing_extracted(root);
+    }
+
+    #[test]
+    fn rejects_templated_source() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let path = root.join("test.overkami");
+        let definition = write_bundle(&path, wallpaper(picture("/{title}.png")), &[]);
+
+        let error = extract(&path, definition, &root.join("imported"))
+            .err()
+            .unwrap();
+
+        assert!(format!("{error:#}").contains("templated"));
+        assert_nothing_extracted(root);
+    }
+
+    #[test]
+    fn file_properties_come_from_manifest_in_bundle() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let path = root.join("test.overkami");
+        let mut page = wallpaper(WallpaperSource::LocalWebPage {
+            location: PathBuf::from("files/0/index.html"),
+        });
+        // `wallpaper.json` doesn't tell that `image` is a file.
+        page.properties
+            .insert("image".to_owned(), json!(root.join("secret.png")));
+        let definition = write_bundle(
+            &path,
+            page,
+            &[
+                ("files/0/index.html", ""),
+                (
+                    "files/0/overkami.json",
+                    r#"{ "properties": [{ "key": "image", "type": "file" }] }"#,
+                ),
+            ],
+        );
+
+        let error = extract(&path, definition, &root.join("imported"))
+            .err()
+            .unwrap();
+
+        assert!(format!("{error:#}").contains("invalid path"));
+        assert_nothing_extracted(root);
+    }
+}
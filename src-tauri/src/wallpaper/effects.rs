@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+
+use tauri::WebviewWindow;
+
+use crate::config::Effect;
+
+pub type SharedEffects = Arc<Mutex<Vec<Effect>>>;
+
+/// The script applying the effects to the page, which is called with the list of the effects.
+///
+/// The filters are set to the root element, and tints are put over the page in the order
+/// of the list. Calling it again replaces the effects applied before.
+const APPLY_SCRIPT: &str = r#"
+((effects) => {
+  const root = document.documentElement;
+  const filters = [];
+  const tints = [];
+
+  for (const effect of effects) {
+    switch (effect.type) {
+      case "Blur":
+        filters.push(`blur(${effect.radius}px)`);
+        break;
+      case "Brightness":
+        filters.push(`brightness(${effect.amount}%)`);
+        break;
+      case "Contrast":
+        filters.push(`contrast(${effect.amount}%)`);
+        break;
+      case "Saturation":
+        filters.push(`saturate(${effect.amount}%)`);
+        break;
+      case "Grayscale":
+        filters.push(`grayscale(${effect.amount}%)`);
+        break;
+      case "Tint":
+        tints.push(effect);
+        break;
+    }
+  }
+
+  root.style.filter = filters.join(" ");
+  document.getElementById("__overkami-tint")?.remove();
+  if (tints.length === 0) return;
+
+  const layer = document.createElement("div");
+  layer.id = "__overkami-tint";
+  layer.style.cssText =
+    "position: fixed; inset: 0; pointer-events: none; z-index: 2147483647;";
+
+  for (const tint of tints) {
+    const color = document.createElement("div");
+    color.style.cssText = "position: absolute; inset: 0;";
+    color.style.background = tint.color;
+    color.style.opacity = String(tint.strength / 100);
+    layer.append(color);
+  }
+
+  root.append(layer);
+})
+"#;
+
+/// Applies the effects to the page, replacing the effects applied before.
+pub fn apply(window: &WebviewWindow, effects: &[Effect]) {
+    let effects = serde_json::to_string(effects).expect("Failed to serialize effects");
+
+    if let Err(e) = window.eval(format!("{APPLY_SCRIPT}({effects});")) {
+        log::warn!("Failed to apply effects to overlay. Detail: {e}");
+    }
+}
@@ -6,7 +6,11 @@ use tauri::{
 };
 use uuid::Uuid;
 
-use crate::{ConfigState, commands::sync::AddWallpaper, wallpaper::wallpaper_host::WallpaperHost};
+use crate::{
+    ConfigState,
+    commands::sync::AddWallpaper,
+    wallpaper::{bundle, sandbox, slideshow, wallpaper_host::WallpaperHost},
+};
 
 pub type WallpaperHostsState = Mutex<HashMap<Uuid, WallpaperHost>>;
 
@@ -45,6 +49,13 @@ pub async fn add_wallpaper(app: AppHandle, id: Uuid, payload: AddWallpaper) {
 }
 
 pub async fn remove_wallpaper(app: &AppHandle, id: Uuid) {
+    stop_wallpaper(app, id).await;
+    bundle::remove_files(app, id).await;
+}
+
+/// Removes the wallpaper host and the data of the wallpaper, but keeps the files extracted
+/// from its bundle. This is for a bundle replacing the wallpaper, whose files are already there.
+pub async fn stop_wallpaper(app: &AppHandle, id: Uuid) {
     log::info!("Removing wallpaper host for ID: {id}");
 
     let wallpaper_hosts = app.state::<WallpaperHostsState>();
@@ -55,4 +66,7 @@ pub async fn remove_wallpaper(app: &AppHandle, id: Uuid) {
     } else {
         log::warn!("There were no wallpaper to remove.")
     };
+
+    sandbox::remove_data(app, id).await;
+    slideshow::remove_state(app, id).await;
 }
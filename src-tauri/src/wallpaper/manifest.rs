@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{config::WallpaperSource, utils::convert_file_src, wallpaper::wallpaper_engine};
+
+/// The name of the manifest file placed next to the HTML of a wallpaper.
+pub const MANIFEST_FILE_NAME: &str = "overkami.json";
+
+/// The property values resolved for the page. This is shared with the page load handler.
+pub type SharedProperties = Arc<Mutex<Map<String, Value>>>;
+
+/// The manifest of a local web page wallpaper.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WallpaperManifest {
+    /// The properties that users can configure per wallpaper, in the order of the settings UI.
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Property {
+    pub key: String,
+    /// The label shown in the settings UI. `key` is shown if this is not set.
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub kind: PropertyKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PropertyKind {
+    /// A CSS color like `#ff8800`.
+    Color {
+        #[serde(default = "default_color")]
+        default: String,
+    },
+    Number {
+        #[serde(default)]
+        default: f64,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+        #[serde(default)]
+        step: Option<f64>,
+    },
+    Bool {
+        #[serde(default)]
+        default: bool,
+    },
+    Text {
+        #[serde(default)]
+        default: String,
+    },
+    /// One of the options, passed as the value of the option.
+    Select {
+        #[serde(default)]
+        default: String,
+        options: Vec<SelectOption>,
+    },
+    /// A local file. The page receives it as a URL of the asset protocol.
+    File {
+        /// A relative path in the directory of the manifest.
+        #[serde(default)]
+        default: Option<PathBuf>,
+        /// The extensions offered in the file dialog. Any file can be chosen if empty.
+        #[serde(default)]
+        extensions: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectOption {
+    pub label: String,
+    pub value: String,
+}
+
+fn default_color() -> String {
+    "#000000".to_owned()
+}
+
+impl PropertyKind {
+    /// Returns the file chosen by `value` or the default, if this is a file property.
+    fn file_path(&self, value: Option<&Value>, dir: &Path) -> Option<PathBuf> {
+        let Self::File { default, .. } = self else {
+            return None;
+        };
+
+        value
+            .and_then(Value::as_str)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| default_file(default.as_deref()?, dir))
+    }
+
+    /// Returns the value passed to the page, falling back to the default if `value` doesn't fit.
+    fn resolve(&self, value: Option<&Value>, dir: &Path) -> Value {
+        match self {
+            Self::Color { default } | Self::Text { default } => {
+                value.and_then(Value::as_str).unwrap_or(default).into()
+            }
+            Self::Number {
+                default, min, max, ..
+            } => {
+                let mut number = value.and_then(Value::as_f64).unwrap_or(*default);
+                if let Some(min) = min {
+                    number = number.max(*min);
+                }
+                if let Some(max) = max {
+                    number = number.min(*max);
+                }
+
+                number.into()
+            }
+            Self::Bool { default } => value.and_then(Value::as_bool).unwrap_or(*default).into(),
+            Self::Select { default, options } => value
+                .and_then(Value::as_str)
+                .filter(|value| options.iter().any(|option| option.value == *value))
+                .unwrap_or(default)
+                .into(),
+            Self::File { .. } => match self.file_path(value, dir).map(convert_file_src) {
+                Some(Ok(url)) => url.into(),
+                Some(Err(e)) => {
+                    log::warn!("Failed to resolve file property. Detail: {e}");
+                    Value::Null
+                }
+                None => Value::Null,
+            },
+        }
+    }
+}
+
+/// Resolves the default file of a file property in `dir`.
+///
+/// Manifests may come from imported bundles, so defaults can't point out of `dir`.
+fn default_file(default: &Path, dir: &Path) -> Option<PathBuf> {
+    let plain = default.components().next().is_some()
+        && default
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !plain {
+        log::warn!(
+            "Ignored default file which is not a relative path: {}",
+            default.display()
+        );
+        return None;
+    }
+
+    // Symbolic links may still point to the outside.
+    let path = dunce::canonicalize(dir.join(default)).ok()?;
+    let dir = dunce::canonicalize(dir).ok()?;
+    if !path.starts_with(&dir) {
+        log::warn!(
+            "Ignored default file linked out of the wallpaper directory: {}",
+            default.display()
+        );
+        return None;
+    }
+
+    Some(path)
+}
+
+/// Reads the manifest next to the HTML of a wallpaper. Returns `None` if there is no manifest.
+///
+/// Wallpaper Engine projects without the manifest use the properties of `project.json` instead.
+pub fn read_manifest(page: &Path) -> anyhow::Result<Option<WallpaperManifest>> {
+    let Some(dir) = page.parent() else {
+        return Ok(None);
+    };
+    let path = dir.join(MANIFEST_FILE_NAME);
+
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return wallpaper_engine::read_manifest(dir);
+        }
+        Err(e) => return Err(e).context("Failed to read wallpaper manifest"),
+    };
+
+    let manifest = serde_json::from_slice(&data)
+        .with_context(|| format!("Failed to parse wallpaper manifest `{}`", path.display()))?;
+
+    Ok(Some(manifest))
+}
+
+/// Reads the manifest of `source` with the directory of it, if `source` is a local web page.
+fn manifest_of(source: &WallpaperSource) -> Option<(WallpaperManifest, &Path)> {
+    let WallpaperSource::LocalWebPage { location } = source else {
+        return None;
+    };
+
+    match read_manifest(location) {
+        Ok(manifest) => Some((manifest?, location.parent()?)),
+        Err(e) => {
+            log::warn!("{e:#}");
+            None
+        }
+    }
+}
+
+/// Resolves the property values passed to the page of `source`.
+/// Only the properties declared in the manifest are passed.
+pub fn resolve_properties(
+    source: &WallpaperSource,
+    values: &HashMap<String, Value>,
+) -> Map<String, Value> {
+    let Some((manifest, dir)) = manifest_of(source) else {
+        return Map::new();
+    };
+
+    manifest
+        .properties
+        .iter()
+        .map(|property| {
+            (
+                property.key.clone(),
+                property.kind.resolve(values.get(&property.key), dir),
+            )
+        })
+        .collect()
+}
+
+/// Returns the files chosen for the file properties of the page of `source`.
+pub fn property_files(source: &WallpaperSource, values: &HashMap<String, Value>) -> Vec<PathBuf> {
+    let Some((manifest, dir)) = manifest_of(source) else {
+        return Vec::new();
+    };
+
+    manifest
+        .properties
+        .iter()
+        .filter_map(|property| property.kind.file_path(values.get(&property.key), dir))
+        .collect()
+}
+
+/// Returns the keys of the file properties of the page of `source`.
+pub fn file_property_keys(source: &WallpaperSource) -> Vec<String> {
+    let Some((manifest, _)) = manifest_of(source) else {
+        return Vec::new();
+    };
+
+    manifest
+        .properties
+        .into_iter()
+        .filter(|property| matches!(property.kind, PropertyKind::File { .. }))
+        .map(|property| property.key)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn kind(value: Value) -> PropertyKind {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn file(default: &str) -> PropertyKind {
+        kind(json!({ "type": "file", "default": default }))
+    }
+
+    #[test]
+    fn resolve_falls_back_to_default() {
+        let dir = Path::new("");
+        let color = kind(json!({ "type": "color", "default": "#ff8800" }));
+        let select = kind(json!({
+            "type": "select",
+            "default": "a",
+            "options": [
+                { "label": "A", "value": "a" },
+                { "label": "B", "value": "b" },
+            ],
+        }));
+
+        assert_eq!(color.resolve(None, dir), json!("#ff8800"));
+        assert_eq!(color.resolve(Some(&json!(1)), dir), json!("#ff8800"));
+        assert_eq!(
+            color.resolve(Some(&json!("#ffffff")), dir),
+            json!("#ffffff")
+        );
+        assert_eq!(select.resolve(Some(&json!("b")), dir), json!("b"));
+        assert_eq!(select.resolve(Some(&json!("c")), dir), json!("a"));
+    }
+
+    #[test]
+    fn resolve_clamps_numbers() {
+        let dir = Path::new("");
+        let number = kind(json!({ "type": "number", "default": 5, "min": 0, "max": 10 }));
+
+        assert_eq!(number.resolve(None, dir), json!(5.));
+        assert_eq!(number.resolve(Some(&json!(-1)), dir), json!(0.));
+        assert_eq!(number.resolve(Some(&json!(11)), dir), json!(10.));
+        assert_eq!(number.resolve(Some(&json!("7")), dir), json!(5.));
+    }
+
+    #[test]
+    fn file_path_prefers_chosen_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("images")).unwrap();
+        fs::write(dir.path().join("images").join("default.png"), "").unwrap();
+        let property = file("images/default.png");
+
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        assert_eq!(
+            property.file_path(None, dir.path()),
+            Some(root.join("images").join("default.png"))
+        );
+        assert_eq!(
+            property.file_path(Some(&json!("")), dir.path()),
+            Some(root.join("images").join("default.png"))
+        );
+        assert_eq!(
+            property.file_path(Some(&json!("/chosen.png")), dir.path()),
+            Some(PathBuf::from("/chosen.png"))
+        );
+    }
+
+    #[test]
+    fn file_path_rejects_default_out_of_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("page")).unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        let page = dir.path().join("page");
+        let secret = dir.path().join("secret.txt");
+        assert_eq!(file("../secret.txt").file_path(None, &page), None);
+        assert_eq!(file("./../secret.txt").file_path(None, &page), None);
+        assert_eq!(file(secret.to_str().unwrap()).file_path(None, &page), None);
+        assert_eq!(file("").file_path(None, &page), None);
+        assert_eq!(file("missing.png").file_path(None, &page), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_path_rejects_symlink_escape() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("page")).unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        let page = dir.path().join("page");
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), page.join("link.txt")).unwrap();
+
+        assert_eq!(file("link.txt").file_path(None, &page), None);
+    }
+}
@@ -1,6 +1,27 @@
+pub mod asset_protocol;
+mod bridge;
+pub mod bundle;
+mod effects;
 mod manager;
+pub mod manifest;
 mod overlay;
 mod overlay_host;
+mod playback_clock;
+mod power_saving;
+pub mod protocol;
+mod remote_picture;
+mod sandbox;
+mod slideshow;
+mod system_state;
+mod template;
+mod transition;
+pub mod wallpaper_engine;
 mod wallpaper_host;
 
-pub use manager::{WallpaperHostsState, add_wallpaper, remove_wallpaper, setup_wallpapers};
+pub use manager::{
+    WallpaperHostsState, add_wallpaper, remove_wallpaper, setup_wallpapers, stop_wallpaper,
+};
+pub use overlay::wallpaper_id_from_label;
+pub use playback_clock::PlaybackControl;
+pub use remote_picture::RemotePictures;
+pub use system_state::setup_system_state_sync;
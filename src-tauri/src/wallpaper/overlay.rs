@@ -1,34 +1,158 @@
-use tauri::{AppHandle, WebviewWindow, WebviewWindowBuilder};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, atomic},
+    time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Manager, WebviewWindow, WebviewWindowBuilder, webview::PageLoadEvent};
 use uuid::Uuid;
 use window_getter::Window;
 use window_observer::Event;
 
 use crate::{
-    config::{Filter, WallpaperSource},
-    os::{WebviewWindowPlatformExt, platform_impl::WindowPlatformExt},
-    utils::{adjust_position, adjust_size},
+    commands::sync::ApplyWallpaper,
+    config::{NavigationPolicy, PlaybackPolicy, Transition, Wallpaper, WallpaperSource},
+    os::{
+        InputRegion, WebviewWindowPlatformExt, application_monitor::get_application_process,
+        input_region, platform_impl::WindowPlatformExt,
+    },
+    utils::{MonitorArea, adjust_position, adjust_size, scale_factor_for_bounds},
+    wallpaper::{
+        asset_protocol,
+        bridge::{self, SharedTargetState, TargetState},
+        effects::{self, SharedEffects},
+        manifest::{SharedProperties, resolve_properties},
+        playback_clock::{ClockState, SharedPlaybackClock},
+        power_saving::{PowerSaving, effective_source},
+        remote_picture::RemotePictures,
+        sandbox,
+        slideshow::SharedSlideshow,
+        system_state,
+        template::{self, TemplateValues},
+        transition::{self, TransitionEvent, TransitionTx},
+        wallpaper_engine,
+    },
 };
 
 #[cfg(target_os = "windows")]
 use crate::os::platform_impl::custom_feature::set_visible_window;
 
+use filter::Filters;
+
 /// Represents an overlay window for a wallpaper.
 ///
 /// This struct has responsibility for managing overlay window.
 /// e.g. position, size, opacity, source, and opacity or source configuration updates.
 pub struct Overlay {
+    app: AppHandle,
     wallpaper_id: Uuid,
     target_window: Window,
-    overlay_window: WebviewWindow,
+    /// The overlay window. This is `None` while the overlay is suspended.
+    overlay_window: Option<WebviewWindow>,
+    /// The overlay window which is preloading a new source.
+    /// It replaces `overlay_window` once the source is ready.
+    incoming: Option<Incoming>,
+    /// How many times the overlay window has been recreated. This is a part of the window label.
+    generation: u32,
+    /// The source shown in `overlay_window`.
+    source: WallpaperSource,
+    /// The file filled from the templated source shown in `overlay_window`, if any.
+    shown_file: Option<PathBuf>,
+    opacity: f64,
+    transition: Transition,
+    transitions: TransitionTx,
     hidden: bool,
+    /// When the overlay was hidden.
+    hidden_since: Option<Instant>,
+    /// When the overlay was shown or its target window was focused last time.
+    last_used: Instant,
+    /// Whether rendering is paused due to system states such as sleep or screen lock.
+    paused: bool,
+    power_saving: PowerSaving,
+    /// Whether the target window is frontmost.
+    foreground: bool,
+    playback: PlaybackPolicy,
+    /// Whether the wallpaper page is told to play. This is shared with the page load handler.
+    playing: Arc<atomic::AtomicBool>,
+    /// Whether the wallpaper page may play sound. This is shared with the page load handler.
+    audible: Arc<atomic::AtomicBool>,
+    clock: SharedPlaybackClock,
+    slideshow: SharedSlideshow,
+    /// The target window state exposed to the page. `None` if the bridge is disabled.
+    bridge: Option<SharedTargetState>,
+    application_name: Option<String>,
+    /// The filters of the wallpaper, whose capture groups fill templated sources.
+    filters: Filters,
+    /// The values filling the placeholders of templated sources for the target window.
+    template_values: TemplateValues,
+    template_fallback: Option<PathBuf>,
+    /// The property values configured by users.
+    property_values: HashMap<String, serde_json::Value>,
+    /// The property values resolved with the manifest of the source.
+    properties: SharedProperties,
+    /// The effects applied to the page. This is shared with the page load handler.
+    effects: SharedEffects,
+    /// Whether the page can accept pointer events inside the regions it declares.
+    interactive: bool,
+    /// The input regions declared by the page, with the label of the window that has the page.
+    input_regions: Option<(String, Vec<InputRegion>)>,
+    navigation: NavigationPolicy,
+    target_bounds: TargetBounds,
+    scale_factor: Option<f64>,
+}
+
+/// Things shared by the overlays of an overlay host.
+#[derive(Clone)]
+pub struct OverlayContext {
+    pub clock: SharedPlaybackClock,
+    pub slideshow: SharedSlideshow,
+    pub transitions: TransitionTx,
+}
+
+/// What the overlay window tells its page when the page is loaded.
+#[derive(Clone)]
+pub struct PageHooks {
+    playing: Arc<atomic::AtomicBool>,
+    audible: Arc<atomic::AtomicBool>,
+    clock: SharedPlaybackClock,
+    bridge: Option<SharedTargetState>,
+    properties: SharedProperties,
+    effects: SharedEffects,
+}
+
+/// What is fixed when the overlay window is created. Changing them needs a new window.
+#[derive(Clone)]
+pub struct WindowOptions {
+    interactive: bool,
+    navigation: NavigationPolicy,
+}
+
+struct Incoming {
+    window: WebviewWindow,
+    source: WallpaperSource,
+    /// The file filled from the templated source, if any.
+    file: Option<PathBuf>,
+    /// The number of signals still needed before the window is ready.
+    /// See [`transition::required_signals`].
+    pending: u8,
+}
+
+/// The last known bounds of the target window.
+#[derive(Debug, Clone, Copy, Default)]
+struct TargetBounds {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
 }
 
 impl Overlay {
     /// Check if the overlay should be created for the given target window.
-    pub async fn should_handle(target_window: &Window, filters: &[Filter]) -> bool {
+    pub async fn should_handle(target_window: &Window, filters: &Filters) -> bool {
         match target_window.title() {
             Ok(title) => {
-                if !filter::wallpaper_filter(title, filters) {
+                if !filters.matches(title.as_deref()) {
                     return false;
                 }
             }
@@ -70,12 +194,16 @@ impl Overlay {
     pub async fn new(
         wallpaper_id: Uuid,
         target_window: Window,
-        source: &WallpaperSource,
-        opacity: f64,
-        filters: &[Filter],
+        wallpaper: &Wallpaper,
+        OverlayContext {
+            clock,
+            slideshow,
+            transitions,
+        }: OverlayContext,
         app: AppHandle,
     ) -> Option<Self> {
-        if !Self::should_handle(&target_window, filters).await {
+        let filters = Filters::new(&wallpaper.filters);
+        if !Self::should_handle(&target_window, &filters).await {
             return None;
         }
 
@@ -84,24 +212,178 @@ impl Overlay {
             target_window.id()
         );
 
-        let overlay_window = create_window(&app, &wallpaper_id, &target_window, source, opacity);
+        let on_battery = system_state::is_on_battery();
+        let application_name = target_window
+            .owner_pid()
+            .ok()
+            .and_then(|pid| get_application_process(pid as _))
+            .map(|process| process.name);
+
+        let source = effective_source(wallpaper, on_battery);
+        let properties = resolve_properties(&source, &wallpaper.properties);
+        let template_values = template::values(
+            target_window.title().ok().flatten().as_deref(),
+            application_name.as_deref(),
+            filters.regexes(),
+        );
 
         // Listen for updates of config
-        let overlay = Self {
+        let mut overlay = Self {
+            app,
             wallpaper_id,
             target_window,
-            overlay_window,
+            overlay_window: None,
+            incoming: None,
+            generation: 0,
+            source,
+            shown_file: None,
+            opacity: wallpaper.opacity,
+            transition: wallpaper.transition,
+            transitions,
             hidden: false,
+            hidden_since: None,
+            last_used: Instant::now(),
+            paused: false,
+            power_saving: PowerSaving::None,
+            foreground: false,
+            playback: wallpaper.playback,
+            playing: Arc::new(atomic::AtomicBool::new(true)),
+            audible: Arc::new(atomic::AtomicBool::new(false)),
+            clock,
+            slideshow,
+            bridge: wallpaper.window_bridge.then(Default::default),
+            application_name,
+            filters,
+            template_values,
+            template_fallback: wallpaper.template_fallback.clone(),
+            property_values: wallpaper.properties.clone(),
+            properties: Arc::new(std::sync::Mutex::new(properties)),
+            effects: Arc::new(std::sync::Mutex::new(wallpaper.effects.clone())),
+            interactive: wallpaper.interactive,
+            input_regions: None,
+            navigation: wallpaper.navigation.clone(),
+            target_bounds: TargetBounds::default(),
+            scale_factor: None,
         };
+        overlay.update_audio();
+        let shown = overlay.shown_source(&overlay.source);
+        overlay.shown_file = templated_file(&overlay.source, &shown);
+        overlay.allow_files(None);
+        overlay.overlay_window = Some(create_window(
+            &overlay.app,
+            &window_label(&wallpaper_id, &overlay.target_window, 0),
+            &shown,
+            overlay.opacity,
+            overlay.window_options(),
+            overlay.page_hooks(),
+            || {},
+        ));
 
-        overlay.setup_initial_window_state().await;
-        overlay.setup_activate_intercept().await;
+        overlay.set_paused(system_state::is_paused());
+        overlay.set_power_saving(PowerSaving::new(&wallpaper.on_battery, on_battery));
+        overlay.resync(None).await;
+        overlay.setup_activate_intercept();
 
         Some(overlay)
     }
 
-    async fn setup_initial_window_state(&self) {
-        // Set initial overlay order.
+    pub fn is_suspended(&self) -> bool {
+        self.overlay_window.is_none()
+    }
+
+    /// Whether the overlay window is shown, or would be shown if it were not suspended.
+    pub fn is_visible(&self) -> bool {
+        !self.hidden && !self.paused && self.power_saving != PowerSaving::Disable
+    }
+
+    /// Whether the target window is hidden.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// How long the target window has been hidden.
+    pub fn hidden_duration(&self) -> Duration {
+        self.hidden_since
+            .map(|since| since.elapsed())
+            .unwrap_or_default()
+    }
+
+    pub fn last_used(&self) -> Instant {
+        self.last_used
+    }
+
+    /// Destroys the overlay window to reclaim the memory of its webview.
+    /// The window is recreated by [`Overlay::resume`] when it is needed again.
+    pub fn suspend(&mut self) {
+        // The preloading source is shown on resume instead.
+        if let Some(incoming) = self.discard_incoming() {
+            self.source = incoming.source;
+        }
+
+        let Some(overlay_window) = self.overlay_window.take() else {
+            return;
+        };
+        self.shown_file = None;
+        self.allow_files(None);
+        if let Err(e) = overlay_window.set_input_regions(None) {
+            log::warn!("Failed to remove input regions of suspended overlay. Detail: {e}");
+        }
+
+        log::info!(
+            "Suspend overlay: wallpaper_id = {}, target_window_id = {:?}",
+            self.wallpaper_id,
+            self.target_window.id()
+        );
+
+        if let Err(e) = overlay_window.destroy() {
+            log::warn!("Failed to destroy the suspended overlay window. Detail: {e}");
+        }
+    }
+
+    /// Recreates the overlay window if the overlay is suspended.
+    pub async fn resume(&mut self) {
+        if !self.is_suspended() {
+            return;
+        }
+
+        log::info!(
+            "Resume overlay: wallpaper_id = {}, target_window_id = {:?}",
+            self.wallpaper_id,
+            self.target_window.id()
+        );
+
+        // The label of the destroyed window may not be released yet, so use another one.
+        self.generation += 1;
+        let shown = self.shown_source(&self.source);
+        self.shown_file = templated_file(&self.source, &shown);
+        self.allow_files(None);
+        self.overlay_window = Some(create_window(
+            &self.app,
+            &window_label(&self.wallpaper_id, &self.target_window, self.generation),
+            &shown,
+            self.opacity,
+            self.window_options(),
+            self.page_hooks(),
+            || {},
+        ));
+
+        self.update_window_visibility();
+        self.resync(None).await;
+        self.setup_activate_intercept();
+    }
+
+    /// Synchronizes the order, position and size of the overlay window with the target window.
+    /// If `latest` is passed, it is used as the new target window information.
+    pub async fn resync(&mut self, latest: Option<Window>) {
+        if let Some(latest) = latest {
+            self.target_window = latest;
+        }
+
+        if self.hidden {
+            return;
+        }
+
+        // Set overlay order.
         match self.target_window.is_frontmost() {
             Err(e) => log::warn!(
                 "Failed to check if window {:?} is frontmost, \
@@ -112,28 +394,491 @@ impl Overlay {
             Ok(false) => self.set_background().await,
         }
 
-        // Set initial position and size
-        let bounds = self.target_window.bounds().unwrap();
-        self.move_(bounds.clone().into());
-        self.resize(bounds.into());
+        // Set position and size
+        match self.target_window.bounds() {
+            Ok(bounds) => {
+                self.target_bounds = TargetBounds {
+                    x: bounds.x,
+                    y: bounds.y,
+                    width: bounds.width,
+                    height: bounds.height,
+                };
+                self.scale_factor = None;
+                self.sync_geometry(true, true);
+            }
+            Err(e) => log::warn!(
+                "Failed to get window bounds for {:?}, skipping geometry sync. Detail: {e}",
+                self.target_window.id()
+            ),
+        }
     }
 
     /// Apply the wallpaper settings.
-    pub fn apply_wallpaper(&self, opacity: Option<f64>, source: Option<WallpaperSource>) {
-        if let Some(opacity) = opacity {
+    pub fn apply_wallpaper(&mut self, payload: &ApplyWallpaper) {
+        if let Some(opacity) = payload.opacity {
             log::info!("Update wallpaper overlay opacity to {opacity}");
 
-            self.overlay_window.set_opacity(opacity).unwrap();
+            self.opacity = opacity;
+            if let Some(overlay_window) = &self.overlay_window {
+                overlay_window.set_opacity(opacity).unwrap();
+            }
         }
 
-        if let Some(source) = source {
-            log::info!("Update wallpaper overlay opacity to {source:?}");
+        if let Some(transition) = payload.transition {
+            log::info!("Update wallpaper overlay transition to {transition:?}");
+
+            self.transition = transition;
+        }
 
-            let url = source::get_wallpaper_url(&source);
+        // Whether the page must be loaded in a new window for the options to take effect.
+        let mut needs_reload = false;
 
-            self.overlay_window
-                .eval(format!("window.location.replace('{url}');"))
-                .unwrap();
+        if let Some(window_bridge) = payload.window_bridge
+            && window_bridge != self.bridge.is_some()
+        {
+            log::info!("Update wallpaper overlay window bridge to {window_bridge}");
+
+            self.bridge = window_bridge.then(Default::default);
+
+            // The script of the bridge is injected only when the page is loaded.
+            needs_reload = true;
+        }
+
+        if let Some(interactive) = payload.interactive
+            && interactive != self.interactive
+        {
+            log::info!("Update wallpaper overlay interactive mode to {interactive}");
+
+            self.interactive = interactive;
+
+            // Whether the first click reaches the page is decided when the window is created.
+            needs_reload = true;
+            self.update_input_regions();
+        }
+
+        if let Some(navigation) = &payload.navigation
+            && *navigation != self.navigation
+        {
+            log::info!("Update wallpaper overlay navigation policy to {navigation:?}");
+
+            self.navigation = navigation.clone();
+
+            // The navigation handler is set when the window is created.
+            needs_reload = true;
+        }
+
+        // A new source is loaded in a new window anyway, unless only its layout is changed.
+        if needs_reload && payload.source.is_none() && !self.is_suspended() {
+            self.begin_transition(self.source.clone());
+        }
+
+        if let Some(effects) = &payload.effects {
+            log::info!("Update wallpaper overlay effects to {effects:?}");
+
+            // Pages loaded meanwhile read the new ones in the page load handler.
+            *self.effects.lock().unwrap() = effects.clone();
+            for overlay_window in self.windows() {
+                effects::apply(overlay_window, effects);
+            }
+        }
+
+        if let Some(values) = &payload.properties {
+            log::info!("Update wallpaper overlay properties to {values:?}");
+
+            self.property_values = values.clone();
+        }
+
+        let mut template_changed = false;
+        if let Some(filters) = &payload.filters {
+            self.filters = Filters::new(filters);
+            template_changed = true;
+        }
+        if let Some(fallback) = &payload.template_fallback {
+            self.template_fallback =
+                Some(fallback.clone()).filter(|path| !path.as_os_str().is_empty());
+            template_changed = true;
+        }
+        if template_changed || payload.source.as_ref().is_some_and(template::is_templated) {
+            self.template_values = self.read_template_values();
+
+            if payload.source.is_none()
+                && template::is_templated(self.latest_source())
+                && !self.is_suspended()
+            {
+                self.begin_transition(self.latest_source().clone());
+            }
+        }
+
+        if payload.properties.is_some() || payload.source.is_some() {
+            let source = payload.source.as_ref().unwrap_or(self.latest_source());
+            let properties = resolve_properties(source, &self.property_values);
+
+            // The page of a new source receives them when it is loaded.
+            if payload.source.is_none() {
+                for overlay_window in self.windows() {
+                    message::send_properties(overlay_window, &properties);
+                }
+            }
+            *self.properties.lock().unwrap() = properties;
+        }
+
+        if let Some(source) = &payload.source {
+            log::info!("Update wallpaper overlay source to {source:?}");
+
+            if self.is_suspended() {
+                self.source = source.clone();
+            } else if !needs_reload
+                && self.incoming.is_none()
+                && changes_only_layout(&self.source, source)
+            {
+                // The page follows the layout without being reloaded.
+                if let (Some(overlay_window), Some(layout)) =
+                    (&self.overlay_window, source.layout())
+                {
+                    message::send_layout(overlay_window, layout);
+                }
+                self.source = source.clone();
+            } else {
+                self.begin_transition(source.clone());
+            }
+            self.update_audio();
+        }
+
+        if let Some(playback) = payload.playback {
+            log::info!("Update wallpaper overlay playback policy to {playback:?}");
+
+            self.playback = playback;
+            self.update_playback();
+        }
+    }
+
+    /// Starts preloading the new source in another overlay window.
+    /// The current one is kept until the new source is ready.
+    fn begin_transition(&mut self, source: WallpaperSource) {
+        self.discard_incoming();
+
+        let shown = self.shown_source(&source);
+        if let Err(e) = transition::check_source(&shown) {
+            transition::report_error(&self.app, self.wallpaper_id, format!("{e:#}"));
+            return;
+        }
+        let file = templated_file(&source, &shown);
+        self.allow_files(file.as_ref());
+
+        self.generation += 1;
+        let label = window_label(&self.wallpaper_id, &self.target_window, self.generation);
+        let target_window_id = self.target_window.id();
+
+        let window = create_window(
+            &self.app,
+            &label,
+            &shown,
+            0.,
+            self.window_options(),
+            self.page_hooks(),
+            {
+                let tx = self.transitions.clone();
+                let label = label.clone();
+
+                move || {
+                    let _ = tx.try_send(TransitionEvent {
+                        target_window_id,
+                        label: label.clone(),
+                        result: Ok(()),
+                    });
+                }
+            },
+        );
+        if let WallpaperSource::RemoteWebPage { location } = &shown {
+            transition::spawn_remote_check(
+                self.transitions.clone(),
+                target_window_id,
+                label.clone(),
+                location.clone(),
+            );
+        }
+        transition::spawn_timeout(self.transitions.clone(), target_window_id, label);
+
+        self.incoming = Some(Incoming {
+            window,
+            source,
+            file,
+            pending: transition::required_signals(&shown),
+        });
+        self.update_window_visibility();
+        if !self.hidden {
+            self.scale_factor = None;
+            self.sync_geometry(true, true);
+        }
+    }
+
+    /// Swaps in the incoming overlay window once its source is ready.
+    /// If any check fails, the current overlay window is kept and the error is reported.
+    pub async fn handle_transition_event(&mut self, event: TransitionEvent) {
+        let Some(incoming) = self
+            .incoming
+            .as_mut()
+            .filter(|incoming| incoming.window.label() == event.label)
+        else {
+            // The event is for an outdated or already swapped window.
+            return;
+        };
+
+        if let Err(detail) = event.result {
+            self.discard_incoming();
+            transition::report_error(&self.app, self.wallpaper_id, detail);
+            return;
+        }
+
+        incoming.pending = incoming.pending.saturating_sub(1);
+        if incoming.pending > 0 {
+            return;
+        }
+
+        let Some(Incoming {
+            window,
+            source,
+            file,
+            ..
+        }) = self.incoming.take()
+        else {
+            return;
+        };
+        log::info!(
+            "Swap overlay window to `{}`: target_window_id = {:?}",
+            window.label(),
+            self.target_window.id()
+        );
+
+        self.source = source;
+        self.shown_file = file;
+        self.allow_files(None);
+        let outgoing = self.overlay_window.replace(window.clone());
+        if let Some(outgoing) = &outgoing {
+            self.clear_input_regions(outgoing);
+        }
+        self.setup_activate_intercept();
+        self.resync(None).await;
+
+        match outgoing {
+            Some(outgoing) => {
+                transition::spawn_animation(outgoing, window, self.transition, self.opacity)
+            }
+            None => window.set_opacity(self.opacity).unwrap(),
+        }
+    }
+
+    fn discard_incoming(&mut self) -> Option<Incoming> {
+        let incoming = self.incoming.take()?;
+
+        if let Err(e) = incoming.window.destroy() {
+            log::warn!("Failed to destroy the incoming overlay window. Detail: {e}");
+        }
+        self.allow_files(None);
+
+        Some(incoming)
+    }
+
+    /// Lets the asset protocol serve the files filled from templates which the overlay window
+    /// and `incoming` show, instead of the ones shown before.
+    fn allow_files(&self, incoming: Option<&PathBuf>) {
+        let files = self.shown_file.iter().chain(incoming).cloned().collect();
+        asset_protocol::set_overlay_files(
+            &self.app,
+            self.wallpaper_id,
+            self.target_window.id(),
+            files,
+        );
+    }
+
+    /// Shows the current item of the slideshow or the latest remote picture, if the source is
+    /// either of them.
+    pub fn show_slide(&mut self) {
+        let source = self.latest_source().clone();
+        let changing = matches!(
+            source,
+            WallpaperSource::Slideshow { .. } | WallpaperSource::RemotePicture { .. }
+        );
+        if changing && !self.is_suspended() {
+            self.begin_transition(source);
+        }
+    }
+
+    /// Fills the templated source again for the current title of the target window,
+    /// and shows it if it is changed.
+    pub fn update_template(&mut self) {
+        let source = self.latest_source().clone();
+        if !template::is_templated(&source) {
+            return;
+        }
+
+        let values = self.read_template_values();
+        if values == self.template_values {
+            return;
+        }
+
+        let shown = self.shown_source(&source);
+        self.template_values = values;
+
+        // Suspended overlays show the new one when they are resumed.
+        if self.shown_source(&source) != shown && !self.is_suspended() {
+            log::info!(
+                "Update templated source for the new title: target_window_id = {:?}",
+                self.target_window.id()
+            );
+            self.begin_transition(source);
+        }
+    }
+
+    fn read_template_values(&self) -> TemplateValues {
+        template::values(
+            self.target_window.title().ok().flatten().as_deref(),
+            self.application_name.as_deref(),
+            self.filters.regexes(),
+        )
+    }
+
+    /// Returns what the overlay window shows for the source.
+    /// A templated source is filled for the target window, a slideshow is its current item for
+    /// the target window, and a remote picture is its cached file.
+    fn shown_source(&self, source: &WallpaperSource) -> WallpaperSource {
+        let source = &template::resolve(
+            source,
+            &self.template_values,
+            self.template_fallback.as_deref(),
+        );
+
+        match source {
+            WallpaperSource::Slideshow { .. } => self
+                .slideshow
+                .current(self.target_window.id())
+                .unwrap_or_else(|| source.clone()),
+            WallpaperSource::RemotePicture {
+                location, layout, ..
+            } => self
+                .app
+                .state::<RemotePictures>()
+                .current(location, layout)
+                .unwrap_or_else(|| source.clone()),
+            _ => source.clone(),
+        }
+    }
+
+    /// The source which is shown, or is going to be shown by the incoming overlay window.
+    fn latest_source(&self) -> &WallpaperSource {
+        self.incoming
+            .as_ref()
+            .map_or(&self.source, |incoming| &incoming.source)
+    }
+
+    /// The overlay windows including the incoming one.
+    fn windows(&self) -> impl Iterator<Item = &WebviewWindow> {
+        self.overlay_window
+            .iter()
+            .chain(self.incoming.as_ref().map(|incoming| &incoming.window))
+    }
+
+    /// Whether the overlay window or the incoming one has `label`.
+    pub fn has_window(&self, label: &str) -> bool {
+        self.windows().any(|window| window.label() == label)
+    }
+
+    fn window_options(&self) -> WindowOptions {
+        WindowOptions {
+            interactive: self.interactive,
+            navigation: self.navigation.clone(),
+        }
+    }
+
+    fn page_hooks(&self) -> PageHooks {
+        PageHooks {
+            playing: Arc::clone(&self.playing),
+            audible: Arc::clone(&self.audible),
+            clock: Arc::clone(&self.clock),
+            bridge: self.bridge.clone(),
+            properties: Arc::clone(&self.properties),
+            effects: Arc::clone(&self.effects),
+        }
+    }
+
+    /// Tells the page the state of the target window if the bridge is enabled and it is changed.
+    pub fn update_bridge(&self) {
+        let Some(bridge) = &self.bridge else {
+            return;
+        };
+
+        let size = adjust_size(
+            self.scale_factor.unwrap_or(1.),
+            self.target_bounds.width,
+            self.target_bounds.height,
+        );
+        let state = TargetState {
+            width: size.width,
+            height: size.height,
+            focused: self.foreground,
+            visible: !self.hidden,
+            title: self.target_window.title().ok().flatten(),
+            application_name: self.application_name.clone(),
+        };
+
+        let mut current = bridge.lock().unwrap();
+        if *current == state {
+            return;
+        }
+        *current = state;
+
+        for overlay_window in self.windows() {
+            bridge::send(overlay_window, &current);
+        }
+    }
+
+    /// Sets the input regions declared by the page in the window with `label`.
+    /// Returns `false` if the window doesn't belong to this overlay.
+    pub fn set_input_regions(&mut self, label: &str, regions: Vec<InputRegion>) -> bool {
+        if !self.has_window(label) {
+            return false;
+        }
+
+        self.input_regions = Some((label.to_owned(), regions));
+        self.update_input_regions();
+
+        true
+    }
+
+    /// Lets the overlay window accept pointer events inside the input regions
+    /// while the target window is frontmost in interactive mode.
+    fn update_input_regions(&self) {
+        let Some(overlay_window) = &self.overlay_window else {
+            return;
+        };
+
+        let regions = self
+            .input_regions
+            .as_ref()
+            .filter(|(label, regions)| label == overlay_window.label() && !regions.is_empty())
+            .map(|(_, regions)| regions.clone());
+
+        if self.interactive && self.foreground && !self.hidden && regions.is_some() {
+            if let Err(e) = overlay_window.set_input_regions(regions) {
+                log::warn!(
+                    "Failed to set input regions, so the overlay stays click-through. Detail: {e}"
+                );
+                self.clear_input_regions(overlay_window);
+            }
+        } else if input_region::is_tracked(overlay_window) {
+            self.clear_input_regions(overlay_window);
+        }
+    }
+
+    /// Removes the input regions and restores the state that `set_foreground`
+    /// or `set_background` made, which lets pointer events through in the foreground.
+    fn clear_input_regions(&self, overlay_window: &WebviewWindow) {
+        if let Err(e) = overlay_window.set_input_regions(None) {
+            log::warn!("Failed to remove input regions. Detail: {e}");
+        }
+
+        if let Err(e) = overlay_window.merge_ignore_cursor_events(self.foreground) {
+            log::warn!("Failed to restore pointer events of overlay. Detail: {e}");
         }
     }
 
@@ -143,59 +888,255 @@ impl Overlay {
         event: Event,
     ) {
         match event {
-            Event::Showed => self.show(),
+            Event::Showed => {
+                self.show();
+
+                if self.is_suspended() {
+                    self.resume().await;
+                } else {
+                    self.sync_geometry(true, true);
+                }
+            }
             Event::Hidden => self.hide(),
             _ => {}
         }
 
         if !self.hidden {
             match event {
-                Event::Moved => self.move_(window.position().unwrap()),
-                Event::Resized => self.resize(window.size().unwrap()),
-                Event::Foregrounded => self.set_foreground().await,
+                Event::Moved => self.apply_geometry(Some(window.position().unwrap()), None),
+                Event::Resized => self.apply_geometry(None, Some(window.size().unwrap())),
+                Event::Foregrounded => {
+                    // The overlay may have been evicted by the limit of live overlays.
+                    self.resume().await;
+                    self.set_foreground().await;
+                }
                 Event::Backgrounded => self.set_background().await,
                 _ => {}
             }
         }
     }
 
+    /// Applies the latest geometry of the target window.
+    /// `Moved` and `Resized` events are coalesced by the overlay host, so this is
+    /// called at most once per frame for each overlay.
+    pub fn apply_geometry(
+        &mut self,
+        position: Option<window_observer::Position>,
+        size: Option<window_observer::Size>,
+    ) {
+        let moved = position.is_some();
+        let resized = size.is_some();
+
+        if let Some(position) = position {
+            self.target_bounds.x = position.x;
+            self.target_bounds.y = position.y;
+        }
+
+        if let Some(size) = size {
+            self.target_bounds.width = size.width;
+            self.target_bounds.height = size.height;
+        }
+
+        if !self.hidden {
+            self.sync_geometry(moved, resized);
+        }
+    }
+
+    /// Moves and resizes the overlay window to the last known bounds of the target window.
+    fn sync_geometry(&mut self, move_: bool, resize: bool) {
+        let Some(scale_factor) = self.target_scale_factor() else {
+            return;
+        };
+
+        // The logical size changes when the target window goes to the monitor
+        // that has another scale factor. So both of them need to be updated in that case.
+        let scale_factor_changed = self.scale_factor != Some(scale_factor);
+        self.scale_factor = Some(scale_factor);
+
+        if move_ || scale_factor_changed {
+            self.move_(scale_factor);
+        }
+
+        if resize || scale_factor_changed {
+            self.resize(scale_factor);
+            self.update_bridge();
+        }
+
+        // Window regions are in physical pixels on Windows.
+        if scale_factor_changed {
+            self.update_input_regions();
+        }
+    }
+
+    /// Returns the scale factor of the monitor that contains the target window.
+    fn target_scale_factor(&self) -> Option<f64> {
+        let overlay_window = self.overlay_window.as_ref()?;
+        let TargetBounds {
+            x,
+            y,
+            width,
+            height,
+        } = self.target_bounds;
+
+        let monitors = match overlay_window.available_monitors() {
+            Ok(monitors) => monitors.iter().map(MonitorArea::from_monitor).collect(),
+            Err(e) => {
+                log::warn!("Failed to get monitors. Detail: {e}");
+                Vec::new()
+            }
+        };
+
+        let scale_factor = scale_factor_for_bounds(&monitors, x, y, width, height)
+            .unwrap_or_else(|| overlay_window.scale_factor().unwrap());
+
+        Some(scale_factor)
+    }
+
+    /// Returns the refresh rate of the monitor that the overlay window is on.
+    pub fn monitor_refresh_rate(&self) -> Option<f64> {
+        let overlay_window = self.overlay_window.as_ref()?;
+
+        overlay_window
+            .monitor_refresh_rate()
+            .inspect_err(|e| log::debug!("Failed to get refresh rate of monitor. Detail: {e}"))
+            .ok()
+    }
+
     pub fn show(&mut self) {
-        #[cfg(not(target_os = "windows"))]
-        self.overlay_window.show().unwrap();
-        #[cfg(target_os = "windows")]
-        set_visible_window(&self.overlay_window, true).unwrap();
         self.hidden = false;
+        self.hidden_since = None;
+        self.last_used = Instant::now();
+        self.update_window_visibility();
+        self.update_playback();
+        self.update_bridge();
+        self.update_input_regions();
     }
 
     pub fn hide(&mut self) {
-        #[cfg(not(target_os = "windows"))]
-        self.overlay_window.hide().unwrap();
-        #[cfg(target_os = "windows")]
-        set_visible_window(&self.overlay_window, false).unwrap();
         self.hidden = true;
+        self.hidden_since.get_or_insert_with(Instant::now);
+        self.update_window_visibility();
+        self.update_playback();
+        self.update_bridge();
+        self.update_input_regions();
     }
 
-    pub fn move_(&self, position: window_observer::Position) {
-        let position = adjust_position(
-            self.overlay_window.scale_factor().unwrap(),
-            position.x,
-            position.y,
-        );
+    /// Pauses or resumes rendering of the overlay.
+    /// The overlay window is kept hidden while paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused == paused {
+            return;
+        }
+        self.paused = paused;
 
-        self.overlay_window
-            .set_position_with_adjustment(position.x, position.y)
-            .unwrap();
+        self.update_window_visibility();
+        self.update_playback();
+    }
+
+    /// Sets how the overlay saves power.
+    pub fn set_power_saving(&mut self, power_saving: PowerSaving) {
+        if self.power_saving == power_saving {
+            return;
+        }
+        self.power_saving = power_saving;
+
+        self.update_window_visibility();
+        self.update_playback();
     }
 
-    pub fn resize(&self, size: window_observer::Size) {
-        println!("{:?}", size);
+    fn update_window_visibility(&self) {
+        self.set_window_visible(self.is_visible());
+    }
+
+    /// Whether the wallpaper page should play animations under the current state.
+    fn should_play(&self) -> bool {
+        if self.paused || self.power_saving != PowerSaving::None {
+            return false;
+        }
+
+        match self.playback {
+            PlaybackPolicy::Always => true,
+            PlaybackPolicy::WhenVisible => !self.hidden,
+            PlaybackPolicy::WhenFocused => !self.hidden && self.foreground,
+        }
+    }
+
+    /// Tells the wallpaper page to pause or resume if the playback state is changed.
+    fn update_playback(&self) {
+        let playing = self.should_play();
+
+        if self.playing.swap(playing, atomic::Ordering::Relaxed) != playing {
+            log::debug!(
+                "Set playback of overlay to {playing}: target_window_id = {:?}",
+                self.target_window.id()
+            );
+
+            // Suspended overlays are told it on page load after they are resumed.
+            for overlay_window in self.windows() {
+                message::send_playback(overlay_window, playing);
+            }
+        }
+
+        self.update_audio();
+    }
+
+    /// Tells the wallpaper page whether it can play sound if it is changed.
+    fn update_audio(&self) {
+        let mute_when_unfocused = match self.latest_source() {
+            WallpaperSource::Video { options, .. } => options.mute_when_unfocused,
+            _ => false,
+        };
+        let audible = self.should_play() && (!mute_when_unfocused || self.foreground);
+
+        if self.audible.swap(audible, atomic::Ordering::Relaxed) != audible {
+            for overlay_window in self.windows() {
+                message::send_audio(overlay_window, audible);
+            }
+        }
+    }
+
+    /// Tells the wallpaper page the new state of the shared playback clock.
+    pub fn send_clock(&self, state: ClockState) {
+        for overlay_window in self.windows() {
+            message::send_clock(overlay_window, state);
+        }
+    }
+
+    fn set_window_visible(&self, visible: bool) {
+        for overlay_window in self.windows() {
+            #[cfg(not(target_os = "windows"))]
+            if visible {
+                overlay_window.show().unwrap();
+            } else {
+                overlay_window.hide().unwrap();
+            }
+            #[cfg(target_os = "windows")]
+            set_visible_window(overlay_window, visible).unwrap();
+        }
+    }
+
+    fn move_(&self, scale_factor: f64) {
+        let position = adjust_position(scale_factor, self.target_bounds.x, self.target_bounds.y);
+
+        for overlay_window in self.windows() {
+            overlay_window
+                .set_position_with_adjustment(position.x, position.y, scale_factor)
+                .unwrap();
+        }
+    }
+
+    fn resize(&self, scale_factor: f64) {
         let size = adjust_size(
-            self.overlay_window.scale_factor().unwrap(),
-            size.width,
-            size.height,
+            scale_factor,
+            self.target_bounds.width,
+            self.target_bounds.height,
         );
 
-        self.overlay_window.set_size(size).unwrap();
+        for overlay_window in self.windows() {
+            overlay_window
+                .set_size_with_adjustment(size.width, size.height, scale_factor)
+                .unwrap();
+        }
     }
 
     /// Sets up an intercept for the target window activation.
@@ -205,13 +1146,18 @@ impl Overlay {
     ///
     /// Otherwise, the target window will be activated and the target window goes frontmost
     /// and the overlay window will activated later. This causes flickering.
-    async fn setup_activate_intercept(&self) {
+    fn setup_activate_intercept(&self) {
+        let Some(overlay_window) = self.overlay_window.clone() else {
+            return;
+        };
         let target_window = self.target_window.clone();
-        let overlay_window = self.overlay_window.clone();
 
-        self.overlay_window.on_window_event(move |event| {
+        overlay_window.clone().on_window_event(move |event| {
             if matches!(event, tauri::WindowEvent::Focused(true)) {
-                overlay_window.merge_ignore_cursor_events(true).unwrap();
+                // In interactive mode, pointer events are toggled by the input regions.
+                if !input_region::is_tracked(&overlay_window) {
+                    overlay_window.merge_ignore_cursor_events(true).unwrap();
+                }
                 overlay_window.set_order_above(target_window.id()).unwrap();
                 overlay_window.merge_always_on_top(true).unwrap();
 
@@ -220,39 +1166,55 @@ impl Overlay {
         })
     }
 
-    pub async fn set_foreground(&self) {
-        self.overlay_window
-            .merge_ignore_cursor_events(true)
-            .unwrap();
+    pub async fn set_foreground(&mut self) {
+        self.last_used = Instant::now();
 
-        self.set_order().await;
-        self.overlay_window.merge_always_on_top(true).unwrap();
+        if let Some(overlay_window) = &self.overlay_window {
+            overlay_window.merge_ignore_cursor_events(true).unwrap();
+
+            self.set_order().await;
+            overlay_window.merge_always_on_top(true).unwrap();
+        }
+
+        self.foreground = true;
+        self.update_playback();
+        self.update_bridge();
+        self.update_input_regions();
     }
 
-    pub async fn set_background(&self) {
-        self.overlay_window.merge_always_on_top(false).unwrap();
-        self.set_order().await;
+    pub async fn set_background(&mut self) {
+        if let Some(overlay_window) = &self.overlay_window {
+            overlay_window.merge_always_on_top(false).unwrap();
+            self.set_order().await;
 
-        self.overlay_window
-            .merge_ignore_cursor_events(false)
-            .unwrap();
+            overlay_window.merge_ignore_cursor_events(false).unwrap();
+        }
+
+        self.foreground = false;
+        self.update_playback();
+        self.update_bridge();
+        self.update_input_regions();
     }
 
     pub async fn set_order(&self) {
+        let Some(overlay_window) = &self.overlay_window else {
+            return;
+        };
+
         #[cfg(target_os = "macos")]
         {
             // On macOS, we can't set the order above immediately.
             // So we need to wait a bit.
             // TODO: Find a better way to handle this problem.
 
-            self.overlay_window
+            overlay_window
                 .set_order_above(self.target_window.id())
                 .unwrap();
 
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         }
 
-        self.overlay_window
+        overlay_window
             .set_order_above(self.target_window.id())
             .unwrap();
     }
@@ -264,30 +1226,158 @@ impl Overlay {
             self.target_window.id()
         );
 
-        self.overlay_window.close().unwrap();
+        for overlay_window in self.windows() {
+            if let Err(e) = overlay_window.set_input_regions(None) {
+                log::warn!("Failed to remove input regions of closing overlay. Detail: {e}");
+            }
+            overlay_window.close().unwrap();
+        }
     }
 }
 
+/// Whether the sources show the same picture or video in different layouts.
+fn changes_only_layout(old: &WallpaperSource, new: &WallpaperSource) -> bool {
+    match (old, new) {
+        (
+            WallpaperSource::Picture { location: old, .. },
+            WallpaperSource::Picture { location: new, .. },
+        ) => old == new,
+        (
+            WallpaperSource::RemotePicture { location: old, .. },
+            WallpaperSource::RemotePicture { location: new, .. },
+        ) => old == new,
+        (
+            WallpaperSource::Video {
+                location: old,
+                options: old_options,
+                ..
+            },
+            WallpaperSource::Video {
+                location: new,
+                options: new_options,
+                ..
+            },
+        ) => old == new && old_options == new_options,
+        _ => false,
+    }
+}
+
+impl Drop for Overlay {
+    fn drop(&mut self) {
+        asset_protocol::forget_overlay(&self.app, self.wallpaper_id, self.target_window.id());
+    }
+}
+
+/// Returns the file which `shown` is filled with, if `source` is templated.
+/// Such files are not known by the scope of the asset protocol.
+fn templated_file(source: &WallpaperSource, shown: &WallpaperSource) -> Option<PathBuf> {
+    if !template::is_templated(source) {
+        return None;
+    }
+
+    match shown {
+        WallpaperSource::Picture { location, .. } | WallpaperSource::Video { location, .. } => {
+            Some(location.clone())
+        }
+        _ => None,
+    }
+}
+
+fn window_label(wallpaper_id: &Uuid, target_window: &Window, generation: u32) -> String {
+    format!(
+        "wallpaper-{}-{}-{}",
+        wallpaper_id,
+        target_window.id().as_u32(),
+        generation
+    )
+}
+
+/// Extracts the wallpaper ID from the label made by [`window_label`].
+pub fn wallpaper_id_from_label(label: &str) -> Option<Uuid> {
+    let rest = label.strip_prefix("wallpaper-")?;
+    Uuid::parse_str(rest.get(..36)?).ok()
+}
+
 pub fn create_window(
     app: &AppHandle,
-    wallpaper_id: &Uuid,
-    target_window: &Window,
+    label: &str,
     source: &WallpaperSource,
     opacity: f64,
+    options: WindowOptions,
+    hooks: PageHooks,
+    on_loaded: impl Fn() + Send + Sync + 'static,
 ) -> WebviewWindow {
-    let label = format!("wallpaper-{}-{}", wallpaper_id, target_window.id().as_u32());
     log::info!("Create overlay window with label `{label}`.");
 
-    let window = WebviewWindowBuilder::new(app, label, source::get_wallpaper_url(source))
+    // The label always starts with the wallpaper ID. See `window_label`.
+    let wallpaper_id = wallpaper_id_from_label(label).expect("Invalid overlay window label");
+    let url = source::get_wallpaper_url(wallpaper_id, source);
+    let mut builder = WebviewWindowBuilder::new(app, label, url.clone());
+    builder = sandbox::apply(builder, app, wallpaper_id, &url, &options.navigation);
+
+    if hooks.bridge.is_some() {
+        builder = builder.initialization_script(bridge::INIT_SCRIPT);
+    }
+
+    if let WallpaperSource::LocalWebPage { location } = source
+        && let Some(script) = wallpaper_engine::init_script(location)
+    {
+        builder = builder.initialization_script(script);
+    }
+
+    // The built-in pages of media tell when the media is loaded instead.
+    let reports_media_load = transition::reports_media_load(source);
+
+    let window = builder
+        .on_page_load(move |window, payload| {
+            if !matches!(payload.event(), PageLoadEvent::Finished) {
+                return;
+            }
+
+            // Join the shared playback at the current position.
+            message::send_clock(&window, hooks.clock.lock().unwrap().state());
+
+            // Pages start playing by default, so tell them only when they should be paused.
+            if !hooks.playing.load(atomic::Ordering::Relaxed) {
+                message::send_playback(&window, false);
+            }
+
+            // Pages start muted, so tell them only when they can play sound.
+            if hooks.audible.load(atomic::Ordering::Relaxed) {
+                message::send_audio(&window, true);
+            }
+
+            if let Some(state) = &hooks.bridge {
+                bridge::send(&window, &state.lock().unwrap());
+            }
+
+            message::send_properties(&window, &hooks.properties.lock().unwrap());
+
+            let effects = hooks.effects.lock().unwrap();
+            if !effects.is_empty() {
+                effects::apply(&window, &effects);
+            }
+
+            if !reports_media_load {
+                on_loaded();
+            }
+        })
         .decorations(false)
         .resizable(false)
         .transparent(true)
         .skip_taskbar(true)
         .focused(false)
+        // The overlay window never becomes key, so the first click must reach the page.
+        .accept_first_mouse(options.interactive)
         .build()
         .unwrap();
 
     window.setup_platform_specific().unwrap();
+    if let Err(e) = window.deny_permissions() {
+        log::warn!(
+            "Failed to deny permissions natively, so only the script denies them. Detail: {e}"
+        );
+    }
     window.merge_ignore_cursor_events(true).unwrap();
     window.set_opacity(opacity).unwrap();
 
@@ -307,72 +1397,208 @@ pub fn create_window(
     window
 }
 
+/// Messages to wallpaper pages.
+///
+/// They are delivered as `CustomEvent`s dispatched on `window` of the page,
+/// whose names are prefixed with `overkami:`.
+mod message {
+    use serde::Serialize;
+    use serde_json::{Map, Value};
+    use tauri::WebviewWindow;
+
+    use crate::{config::Layout, wallpaper::playback_clock::ClockState};
+
+    fn dispatch(window: &WebviewWindow, name: &str, detail: impl Serialize) {
+        let detail = serde_json::to_string(&detail).expect("Failed to serialize event detail");
+        let script = format!(
+            "window.dispatchEvent(new CustomEvent('overkami:{name}', {{ detail: {detail} }}));"
+        );
+
+        if let Err(e) = window.eval(script) {
+            log::warn!("Failed to send `{name}` event to overlay. Detail: {e}");
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Playback {
+        playing: bool,
+    }
+
+    /// Sends `overkami:playback` event.
+    pub fn send_playback(window: &WebviewWindow, playing: bool) {
+        dispatch(window, "playback", Playback { playing });
+    }
+
+    #[derive(Serialize)]
+    struct Audio {
+        audible: bool,
+    }
+
+    /// Sends `overkami:audio` event.
+    pub fn send_audio(window: &WebviewWindow, audible: bool) {
+        dispatch(window, "audio", Audio { audible });
+    }
+
+    /// Sends `overkami:clock` event.
+    pub fn send_clock(window: &WebviewWindow, state: ClockState) {
+        dispatch(window, "clock", state);
+    }
+
+    /// Sends `overkami:layout` event.
+    pub fn send_layout(window: &WebviewWindow, layout: &Layout) {
+        dispatch(window, "layout", layout);
+    }
+
+    /// Sends `overkami:properties` event.
+    pub fn send_properties(window: &WebviewWindow, properties: &Map<String, Value>) {
+        dispatch(window, "properties", properties);
+    }
+}
+
 pub mod source {
     use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
     use tauri::{Url, WebviewUrl};
+    use uuid::Uuid;
 
-    use crate::{config::WallpaperSource, utils::convert_file_src};
+    use serde::Serialize;
+
+    use crate::{config::WallpaperSource, wallpaper::protocol};
 
     /// ビルトインの壁紙を使う際に必要なデータを用意する。
     /// これはHTMLを指定する形式の壁紙には対応していない。それはカスタム壁紙であり、ビルトイン壁紙ではない。
-    pub fn get_wallpaper_url(source: &WallpaperSource) -> WebviewUrl {
+    pub fn get_wallpaper_url(wallpaper_id: Uuid, source: &WallpaperSource) -> WebviewUrl {
         match source {
-            WallpaperSource::Picture { location } => {
+            WallpaperSource::Picture { location, layout } => {
                 let location = utf8_percent_encode(
                     location.to_str().expect("Failed to read picture location"),
                     NON_ALPHANUMERIC,
                 );
+                let layout = encode_json(layout);
 
-                WebviewUrl::App(format!("?wallpaper=picture&location={location}").into())
+                WebviewUrl::App(
+                    format!("?wallpaper=picture&location={location}&layout={layout}").into(),
+                )
             }
-            WallpaperSource::Video { location } => {
+            WallpaperSource::Video {
+                location,
+                layout,
+                options,
+            } => {
                 let location = utf8_percent_encode(
                     location.to_str().expect("Failed to read video location"),
                     NON_ALPHANUMERIC,
                 );
+                let layout = encode_json(layout);
+                let options = encode_json(options);
 
-                WebviewUrl::App(format!("?wallpaper=video&location={location}").into())
+                WebviewUrl::App(
+                    format!(
+                        "?wallpaper=video&location={location}&layout={layout}&options={options}"
+                    )
+                    .into(),
+                )
             }
             WallpaperSource::LocalWebPage { location } => {
-                WebviewUrl::External(Url::parse(&convert_file_src(location).unwrap()).unwrap())
+                WebviewUrl::External(protocol::page_url(wallpaper_id, location))
             }
             WallpaperSource::RemoteWebPage { location } => {
                 WebviewUrl::External(Url::parse(location).unwrap())
             }
-            WallpaperSource::YouTube { location } => {
+            WallpaperSource::YouTube { location, options } => {
                 let location = utf8_percent_encode(location, NON_ALPHANUMERIC);
+                let options = encode_json(options);
+
+                WebviewUrl::App(
+                    format!("?wallpaper=youtube&location={location}&options={options}").into(),
+                )
+            }
+            WallpaperSource::Color { color } => {
+                let color = utf8_percent_encode(color, NON_ALPHANUMERIC);
 
-                WebviewUrl::App(format!("?wallpaper=youtube&location={location}").into())
+                WebviewUrl::App(format!("?wallpaper=color&color={color}").into())
             }
+            WallpaperSource::Gradient { .. } => {
+                let gradient = serde_json::to_string(source).expect("Failed to encode gradient");
+                let gradient = utf8_percent_encode(&gradient, NON_ALPHANUMERIC);
+
+                WebviewUrl::App(format!("?wallpaper=gradient&gradient={gradient}").into())
+            }
+            // Slideshows are shown as their items, so this is a slideshow without items.
+            WallpaperSource::Slideshow { .. } => WebviewUrl::App("?wallpaper=empty".into()),
+            // Remote pictures are shown from the cache, so this one has never been downloaded.
+            WallpaperSource::RemotePicture { .. } => WebviewUrl::App("?wallpaper=empty".into()),
         }
     }
+
+    fn encode_json(value: &impl Serialize) -> String {
+        let value = serde_json::to_string(value).expect("Failed to encode source options");
+        utf8_percent_encode(&value, NON_ALPHANUMERIC).to_string()
+    }
 }
 
 mod filter {
+    use regex::Regex;
+
     use crate::config::{Filter, StringFilterStrategy};
 
-    pub fn string_filter(
-        target: impl AsRef<str>,
-        search: impl AsRef<str>,
+    /// The filters of a wallpaper, with their regular expressions compiled when they are applied.
+    #[derive(Debug, Clone, Default)]
+    pub struct Filters(Vec<(Filter, Option<Regex>)>);
+
+    impl Filters {
+        pub fn new(filters: &[Filter]) -> Self {
+            let filters = filters
+                .iter()
+                .map(|filter| {
+                    let regex = match filter {
+                        Filter::WindowName {
+                            name,
+                            strategy: StringFilterStrategy::Regex,
+                        } => Regex::new(name)
+                            .inspect_err(|e| {
+                                log::warn!("Invalid regular expression in the filter. Detail: {e}")
+                            })
+                            .ok(),
+                        Filter::WindowName { .. } => None,
+                    };
+
+                    (filter.clone(), regex)
+                })
+                .collect();
+
+            Self(filters)
+        }
+
+        pub fn matches(&self, window_name: Option<&str>) -> bool {
+            self.0
+                .iter()
+                .all(|(filter, regex)| match (window_name, filter) {
+                    (Some(window_name), Filter::WindowName { name, strategy }) => {
+                        string_filter(window_name, name, strategy, regex.as_ref())
+                    }
+                    _ => false,
+                })
+        }
+
+        /// The compiled regular expressions, whose capture groups fill templated sources.
+        pub fn regexes(&self) -> impl Iterator<Item = &Regex> {
+            self.0.iter().filter_map(|(_, regex)| regex.as_ref())
+        }
+    }
+
+    fn string_filter(
+        target: &str,
+        search: &str,
         strategy: &StringFilterStrategy,
+        regex: Option<&Regex>,
     ) -> bool {
-        let target = target.as_ref();
-        let search = search.as_ref();
-
         match strategy {
             StringFilterStrategy::Prefix => target.starts_with(search),
             StringFilterStrategy::Suffix => target.ends_with(search),
             StringFilterStrategy::Contains => target.contains(search),
             StringFilterStrategy::Exact => target == search,
+            // Invalid expressions match nothing.
+            StringFilterStrategy::Regex => regex.is_some_and(|regex| regex.is_match(target)),
         }
     }
-
-    pub fn wallpaper_filter(window_name: Option<String>, filters: &[Filter]) -> bool {
-        filters.iter().all(|filter| match (&window_name, filter) {
-            (Some(window_name), Filter::WindowName { name, strategy }) => {
-                string_filter(window_name, name, strategy)
-            }
-            _ => false,
-        })
-    }
 }
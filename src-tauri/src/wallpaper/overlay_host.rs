@@ -7,9 +7,16 @@ use window_getter::WindowId;
 use window_observer::{WindowObserver, tokio::sync::mpsc};
 
 use crate::{
-    config::{Filter, Wallpaper, WallpaperSource},
-    os::windows::get_windows,
-    wallpaper::overlay::Overlay,
+    commands::sync::ApplyWallpaper,
+    config::Wallpaper,
+    os::{InputRegion, windows::get_windows},
+    wallpaper::{
+        overlay::{Overlay, OverlayContext},
+        playback_clock::{ClockState, SharedPlaybackClock},
+        power_saving::PowerSaving,
+        slideshow::SharedSlideshow,
+        transition::{self, TransitionEvent},
+    },
 };
 
 pub type Overlays = Arc<Mutex<HashMap<WindowId, Overlay>>>;
@@ -24,6 +31,7 @@ pub struct OverlayHost {
     pid: u32,
     observer: WindowObserver,
     overlays: Overlays,
+    context: OverlayContext,
 }
 
 impl OverlayHost {
@@ -32,6 +40,8 @@ impl OverlayHost {
         wallpaper_id: Uuid,
         pid: u32,
         config: &Wallpaper,
+        clock: SharedPlaybackClock,
+        slideshow: SharedSlideshow,
     ) -> anyhow::Result<Option<Self>> {
         log::info!(
             "Start new overlay host: \
@@ -48,12 +58,20 @@ impl OverlayHost {
         };
 
         let overlays: Overlays = Default::default();
+        let (transitions, transition_rx) = transition::channel();
+        let context = OverlayContext {
+            clock,
+            slideshow,
+            transitions,
+        };
         overlay_management::spawn_overlay_management_task(
             app.clone(),
             wallpaper_id,
             pid,
             Arc::clone(&overlays),
+            context.clone(),
             rx,
+            transition_rx,
         );
 
         let overlay_host = Self {
@@ -61,19 +79,18 @@ impl OverlayHost {
             pid,
             observer,
             overlays,
+            context,
             app: app.clone(),
         };
 
         // Initialize overlays for existing windows.
-        overlay_host
-            .create_windows(&config.filters, &config.source, config.opacity)
-            .await;
+        overlay_host.create_windows(config).await;
 
         Ok(Some(overlay_host))
     }
 
     /// Create the overlay windows for the windows opened by specific process.
-    async fn create_windows(&self, filters: &[Filter], source: &WallpaperSource, opacity: f64) {
+    async fn create_windows(&self, config: &Wallpaper) {
         let mut overlays = self.overlays.lock().await;
 
         for window in get_windows().await {
@@ -86,9 +103,8 @@ impl OverlayHost {
                 let Some(overlay) = Overlay::new(
                     self.wallpaper_id,
                     window.clone(),
-                    source,
-                    opacity,
-                    filters,
+                    config,
+                    self.context.clone(),
                     self.app.clone(),
                 )
                 .await
@@ -105,6 +121,10 @@ impl OverlayHost {
         self.pid
     }
 
+    pub fn overlays(&self) -> &Overlays {
+        &self.overlays
+    }
+
     /// Stop the overlay windows.
     pub async fn stop(self) {
         log::info!(
@@ -124,9 +144,81 @@ impl OverlayHost {
     }
 
     /// Apply wallpaper settings.
-    pub async fn apply_wallpaper(&self, opacity: Option<f64>, source: Option<WallpaperSource>) {
+    pub async fn apply_wallpaper(&self, payload: &ApplyWallpaper) {
+        for overlay in self.overlays.lock().await.values_mut() {
+            overlay.apply_wallpaper(payload);
+        }
+    }
+
+    /// Pause or resume rendering of all overlays.
+    pub async fn set_paused(&self, paused: bool) {
+        for overlay in self.overlays.lock().await.values_mut() {
+            overlay.set_paused(paused);
+        }
+    }
+
+    /// Set how all overlays save power.
+    pub async fn set_power_saving(&self, power_saving: PowerSaving) {
+        for overlay in self.overlays.lock().await.values_mut() {
+            overlay.set_power_saving(power_saving);
+        }
+    }
+
+    /// Set the input regions declared by the page in the overlay window with `label`.
+    /// Returns `false` if no overlay has the window.
+    pub async fn set_input_regions(&self, label: &str, regions: &[InputRegion]) -> bool {
+        self.overlays
+            .lock()
+            .await
+            .values_mut()
+            .any(|overlay| overlay.set_input_regions(label, regions.to_vec()))
+    }
+
+    /// Tells the overlay with the incoming window `label` whether the media of its page is loaded.
+    /// Returns `false` if no overlay has the window.
+    pub async fn report_media_load(&self, label: &str, result: Result<(), String>) -> bool {
+        let target_window_id = self
+            .overlays
+            .lock()
+            .await
+            .iter()
+            .find(|(_, overlay)| overlay.has_window(label))
+            .map(|(window_id, _)| *window_id);
+        let Some(target_window_id) = target_window_id else {
+            return false;
+        };
+
+        // It is ignored unless the window is still incoming.
+        let _ = self
+            .context
+            .transitions
+            .send(TransitionEvent {
+                target_window_id,
+                label: label.to_owned(),
+                result,
+            })
+            .await;
+
+        true
+    }
+
+    /// Send the new state of the shared playback clock to all overlays.
+    pub async fn send_clock(&self, state: ClockState) {
         for overlay in self.overlays.lock().await.values() {
-            overlay.apply_wallpaper(opacity, source.clone());
+            overlay.send_clock(state);
+        }
+    }
+
+    /// Synchronize the geometry of all overlays with the latest target windows.
+    pub async fn resync(&self) {
+        let mut windows: HashMap<_, _> = get_windows()
+            .await
+            .into_iter()
+            .map(|window| (window.id(), window))
+            .collect();
+
+        for (window_id, overlay) in self.overlays.lock().await.iter_mut() {
+            overlay.resync(windows.remove(window_id)).await;
         }
     }
 }
@@ -223,47 +315,175 @@ mod observer {
 /// Handle the window's events to achieve overlays movements
 /// that match the target windows.
 mod overlay_management {
-    use std::sync::Arc;
+    use std::{sync::Arc, time::Duration};
 
     use tauri::{AppHandle, Manager};
     use uuid::Uuid;
     use window_getter::{Window, WindowId};
     use window_observer::{Event, MaybeWindowAvailable};
 
-    use crate::{ConfigState, wallpaper::overlay::Overlay};
+    use crate::{
+        ConfigState,
+        wallpaper::{
+            overlay::{Overlay, OverlayContext},
+            transition::TransitionRx,
+        },
+    };
+
+    use super::{
+        Overlays,
+        geometry_updates::{FlushTimer, PendingGeometries, PendingGeometry},
+    };
 
-    use super::Overlays;
+    /// How often the titles of the target windows are read for templated sources and the bridge,
+    /// since there is no event for title changes.
+    const TITLE_INTERVAL: Duration = Duration::from_secs(1);
 
     pub fn spawn_overlay_management_task(
         app: AppHandle,
         wallpaper_id: Uuid,
         pid: u32,
         overlays: Overlays,
+        context: OverlayContext,
         mut rx: window_observer::EventRx,
+        mut transition_rx: TransitionRx,
     ) {
         tauri::async_runtime::spawn(async move {
-            while let Some(event) = rx.recv().await {
-                let Ok(event) = event else {
-                    log::warn!("Received invalid window event: {event:?}");
-                    continue;
-                };
+            let mut pending = PendingGeometries::default();
+            let mut flush_timer = FlushTimer::default();
+            let mut title_interval = tokio::time::interval(TITLE_INTERVAL);
+            title_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    event = rx.recv() => {
+                        let Some(event) = event else {
+                            break;
+                        };
+
+                        let Ok(event) = event else {
+                            log::warn!("Received invalid window event: {event:?}");
+                            continue;
+                        };
+
+                        log::debug!(
+                            "Received window event: \
+                            event = {event:?}, \
+                            wallpaper_id = {wallpaper_id}, \
+                            pid = {pid}"
+                        );
+
+                        // `Moved` and `Resized` events are stored and applied later
+                        // together with the other pending geometry updates.
+                        let event = match event {
+                            MaybeWindowAvailable::Available {
+                                window,
+                                event: event @ (Event::Moved | Event::Resized),
+                            } => {
+                                pending.record(window, event);
+                                continue;
+                            }
+                            event => event,
+                        };
+
+                        // Keep the order of events by applying geometry updates before others.
+                        if let Some(geometries) = pending.take_before_event() {
+                            let refresh_rate = apply_geometries(
+                                &app,
+                                wallpaper_id,
+                                &context,
+                                geometries,
+                                &overlays,
+                            )
+                            .await;
+                            flush_timer.follow(refresh_rate);
+                        }
+
+                        manage_overlay(
+                            app.clone(),
+                            wallpaper_id,
+                            &context,
+                            event,
+                            Arc::clone(&overlays),
+                        )
+                        .await;
+                    }
+                    _ = flush_timer.tick(), if !pending.is_empty() => {
+                        let geometries = pending.take();
+                        let refresh_rate =
+                            apply_geometries(&app, wallpaper_id, &context, geometries, &overlays)
+                                .await;
+                        flush_timer.follow(refresh_rate);
+                    }
+                    _ = title_interval.tick() => {
+                        for overlay in overlays.lock().await.values_mut() {
+                            overlay.update_template();
+                            overlay.update_bridge();
+                        }
+                    }
+                    Some(event) = transition_rx.recv() => {
+                        let mut overlays = overlays.lock().await;
+
+                        if let Some(overlay) = overlays.get_mut(&event.target_window_id) {
+                            overlay.handle_transition_event(event).await;
+                        }
+                    }
+                }
+            }
+        });
+    }
 
-                log::debug!(
-                    "Received window event: \
-                    event = {event:?}, \
-                    wallpaper_id = {wallpaper_id}, \
-                    pid = {pid}"
-                );
+    /// Applies coalesced geometry updates to overlays.
+    /// Returns the highest refresh rate of the monitors that the updated overlays are on.
+    async fn apply_geometries(
+        app: &AppHandle,
+        wallpaper_id: Uuid,
+        context: &OverlayContext,
+        geometries: Vec<(WindowId, PendingGeometry)>,
+        overlays: &Overlays,
+    ) -> Option<f64> {
+        let mut unknown_windows = Vec::new();
+        let mut refresh_rate = None;
+
+        {
+            let mut overlays = overlays.lock().await;
+
+            for (window_id, geometry) in geometries {
+                if let Some(overlay) = overlays.get_mut(&window_id) {
+                    overlay.apply_geometry(geometry.position, geometry.size);
 
-                manage_overlay(app.clone(), wallpaper_id, event, Arc::clone(&overlays)).await;
+                    if let Some(rate) = overlay.monitor_refresh_rate() {
+                        refresh_rate =
+                            Some(refresh_rate.map_or(rate, |highest: f64| highest.max(rate)));
+                    }
+                } else {
+                    unknown_windows.push(geometry.window);
+                }
             }
-        });
+        }
+
+        // If no overlay exists for the window, create a new one.
+        for window in unknown_windows {
+            if let Some(window) = window.create_window_getter_window().ok().flatten() {
+                handle_window_created(
+                    app.clone(),
+                    wallpaper_id,
+                    context,
+                    window,
+                    Arc::clone(overlays),
+                )
+                .await;
+            }
+        }
+
+        refresh_rate
     }
 
     /// Handles window events.
     async fn manage_overlay(
         app: AppHandle,
         wallpaper_id: Uuid,
+        context: &OverlayContext,
         event: MaybeWindowAvailable,
         overlays: Overlays,
     ) {
@@ -271,10 +491,12 @@ mod overlay_management {
             MaybeWindowAvailable::Available { window, event } => match event {
                 Event::Created => {
                     if let Some(window) = window.create_window_getter_window().ok().flatten() {
-                        handle_window_created(app, wallpaper_id, window, overlays).await;
+                        handle_window_created(app, wallpaper_id, context, window, overlays).await;
                     };
                 }
-                event => handle_general_event(app, wallpaper_id, window, event, overlays).await,
+                event => {
+                    handle_general_event(app, wallpaper_id, context, window, event, overlays).await
+                }
             },
             MaybeWindowAvailable::NotAvailable { event } => {
                 if let Event::Closed { window_id } = event {
@@ -288,6 +510,7 @@ mod overlay_management {
     async fn handle_window_created(
         app: AppHandle,
         wallpaper_id: Uuid,
+        context: &OverlayContext,
         window: Window,
         overlays: Overlays,
     ) {
@@ -309,9 +532,8 @@ mod overlay_management {
         let overlay = Overlay::new(
             wallpaper_id,
             window,
-            &wallpaper.source,
-            wallpaper.opacity,
-            &wallpaper.filters,
+            wallpaper,
+            context.clone(),
             app.clone(),
         )
         .await;
@@ -335,6 +557,7 @@ mod overlay_management {
     async fn handle_general_event(
         app: AppHandle,
         wallpaper_id: Uuid,
+        context: &OverlayContext,
         window: window_observer::Window,
         event: Event,
         overlays: Overlays,
@@ -360,7 +583,336 @@ mod overlay_management {
 
         // If no overlay exists for the window, create a new one.
         if let Some(window) = window.create_window_getter_window().ok().flatten() {
-            handle_window_created(app, wallpaper_id, window, overlays).await;
+            handle_window_created(app, wallpaper_id, context, window, overlays).await;
+        }
+    }
+}
+
+/// Coalescing of high-frequency geometry events.
+///
+/// While the target window is dragged or resized, `Moved` and `Resized` events arrive
+/// much faster than overlays can follow. So we only keep the latest position and size
+/// for each window and apply them once per frame of the monitor.
+mod geometry_updates {
+    use std::{collections::HashMap, time::Duration};
+
+    use tokio::time::{Interval, MissedTickBehavior};
+    use window_getter::WindowId;
+    use window_observer::{Event, Position, Size};
+
+    /// The refresh rate assumed until the one of the monitor is known.
+    const DEFAULT_REFRESH_RATE: f64 = 60.;
+    /// Refresh rates out of this range are treated as wrong values.
+    const REFRESH_RATE_RANGE: std::ops::RangeInclusive<f64> = 24.0..=500.;
+
+    /// Returns the length of a frame at the refresh rate in Hz.
+    pub fn frame_duration(refresh_rate: f64) -> Duration {
+        let refresh_rate = if REFRESH_RATE_RANGE.contains(&refresh_rate) {
+            refresh_rate
+        } else {
+            DEFAULT_REFRESH_RATE
+        };
+
+        Duration::from_secs_f64(1. / refresh_rate)
+    }
+
+    /// Ticks once per frame of the monitor that the moved overlays are on.
+    pub struct FlushTimer {
+        interval: Interval,
+    }
+
+    impl Default for FlushTimer {
+        fn default() -> Self {
+            Self::new(frame_duration(DEFAULT_REFRESH_RATE))
+        }
+    }
+
+    impl FlushTimer {
+        fn new(period: Duration) -> Self {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            Self { interval }
+        }
+
+        pub async fn tick(&mut self) {
+            self.interval.tick().await;
+        }
+
+        pub fn period(&self) -> Duration {
+            self.interval.period()
+        }
+
+        /// Follows the refresh rate of the monitor. `None` keeps the current one.
+        pub fn follow(&mut self, refresh_rate: Option<f64>) {
+            let Some(refresh_rate) = refresh_rate else {
+                return;
+            };
+
+            let period = frame_duration(refresh_rate);
+            if period != self.period() {
+                log::debug!("Flush geometry updates at {refresh_rate}Hz.");
+                *self = Self::new(period);
+            }
+        }
+    }
+
+    pub struct PendingGeometry<W = window_observer::Window> {
+        /// The latest observed window. This is used to create an overlay if it doesn't exist yet.
+        pub window: W,
+        pub position: Option<Position>,
+        pub size: Option<Size>,
+    }
+
+    /// Pending geometry updates for each target window.
+    pub struct PendingGeometries<W = window_observer::Window> {
+        geometries: HashMap<WindowId, PendingGeometry<W>>,
+    }
+
+    impl<W> Default for PendingGeometries<W> {
+        fn default() -> Self {
+            Self {
+                geometries: HashMap::new(),
+            }
+        }
+    }
+
+    impl PendingGeometries {
+        /// Records the latest geometry of the window for `Moved` or `Resized` event.
+        pub fn record(&mut self, window: window_observer::Window, event: Event) {
+            let Ok(window_id) = window.id() else {
+                return;
+            };
+
+            let (position, size) = match event {
+                Event::Moved => (window.position().ok(), None),
+                Event::Resized => (None, window.size().ok()),
+                _ => return,
+            };
+
+            self.record_geometry(window_id, window, position, size);
+        }
+    }
+
+    impl<W> PendingGeometries<W> {
+        /// Records the geometry of the window. `None` keeps the pending position or size.
+        pub fn record_geometry(
+            &mut self,
+            window_id: WindowId,
+            window: W,
+            position: Option<Position>,
+            size: Option<Size>,
+        ) {
+            match self.geometries.get_mut(&window_id) {
+                Some(pending) => {
+                    pending.window = window;
+                    pending.position = position.or(pending.position.take());
+                    pending.size = size.or(pending.size.take());
+                }
+                None => {
+                    self.geometries.insert(
+                        window_id,
+                        PendingGeometry {
+                            window,
+                            position,
+                            size,
+                        },
+                    );
+                }
+            }
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.geometries.is_empty()
+        }
+
+        /// Takes all pending geometry updates.
+        pub fn take(&mut self) -> Vec<(WindowId, PendingGeometry<W>)> {
+            self.geometries.drain().collect()
+        }
+
+        /// Takes the pending geometry updates which must be applied before another event
+        /// to keep the order of events.
+        pub fn take_before_event(&mut self) -> Option<Vec<(WindowId, PendingGeometry<W>)>> {
+            (!self.is_empty()).then(|| self.take())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::time::Instant;
+
+        use super::*;
+
+        fn position(x: f64, y: f64) -> Option<Position> {
+            Some(Position { x, y })
+        }
+
+        fn size(width: f64, height: f64) -> Option<Size> {
+            Some(Size { width, height })
+        }
+
+        #[test]
+        fn record_keeps_latest_position_and_size() {
+            let mut pending = PendingGeometries::default();
+            let id = WindowId::from(1);
+
+            pending.record_geometry(id, 0, position(1., 1.), None);
+            pending.record_geometry(id, 1, None, size(100., 100.));
+            pending.record_geometry(id, 2, position(2., 2.), None);
+            pending.record_geometry(id, 3, position(3., 3.), None);
+
+            let geometries = pending.take();
+            assert_eq!(geometries.len(), 1);
+
+            let (window_id, geometry) = &geometries[0];
+            assert_eq!(*window_id, id);
+            assert_eq!(geometry.window, 3);
+            assert_eq!(geometry.position, position(3., 3.));
+            assert_eq!(geometry.size, size(100., 100.));
+        }
+
+        #[test]
+        fn record_keeps_windows_apart() {
+            let mut pending = PendingGeometries::default();
+
+            pending.record_geometry(WindowId::from(1), (), position(1., 1.), None);
+            pending.record_geometry(WindowId::from(2), (), None, size(2., 2.));
+
+            let mut geometries = pending.take();
+            geometries.sort_by_key(|(window_id, _)| window_id.as_u32());
+
+            assert_eq!(geometries[0].1.position, position(1., 1.));
+            assert_eq!(geometries[0].1.size, None);
+            assert_eq!(geometries[1].1.position, None);
+            assert_eq!(geometries[1].1.size, size(2., 2.));
+        }
+
+        #[test]
+        fn take_leaves_nothing_pending() {
+            let mut pending = PendingGeometries::default();
+            pending.record_geometry(WindowId::from(1), (), position(1., 1.), None);
+
+            assert_eq!(pending.take().len(), 1);
+            assert!(pending.is_empty());
+            assert!(pending.take().is_empty());
+        }
+
+        #[test]
+        fn pending_updates_are_taken_before_other_events() {
+            let mut pending = PendingGeometries::default();
+            assert!(pending.take_before_event().is_none());
+
+            pending.record_geometry(WindowId::from(1), (), position(1., 1.), None);
+            pending.record_geometry(WindowId::from(1), (), None, size(2., 2.));
+
+            let geometries = pending.take_before_event().unwrap();
+            assert_eq!(geometries.len(), 1);
+            assert_eq!(geometries[0].1.position, position(1., 1.));
+            assert_eq!(geometries[0].1.size, size(2., 2.));
+
+            // The next event must not apply the same updates again.
+            assert!(pending.take_before_event().is_none());
+        }
+
+        #[test]
+        fn frame_duration_follows_refresh_rate() {
+            assert_eq!(frame_duration(60.), Duration::from_secs_f64(1. / 60.));
+            assert_eq!(frame_duration(144.), Duration::from_secs_f64(1. / 144.));
+            assert!(frame_duration(240.) < frame_duration(120.));
+        }
+
+        #[test]
+        fn frame_duration_ignores_wrong_refresh_rates() {
+            let default = frame_duration(DEFAULT_REFRESH_RATE);
+
+            assert_eq!(frame_duration(0.), default);
+            assert_eq!(frame_duration(1.), default);
+            assert_eq!(frame_duration(f64::NAN), default);
+            assert_eq!(frame_duration(10_000.), default);
+        }
+
+        #[test]
+        fn flush_timer_keeps_period_without_refresh_rate() {
+            tauri::async_runtime::block_on(async {
+                let mut timer = FlushTimer::default();
+                timer.follow(Some(120.));
+                assert_eq!(timer.period(), frame_duration(120.));
+
+                timer.follow(None);
+                assert_eq!(timer.period(), frame_duration(120.));
+            });
+        }
+
+        /// Replays bursts of `Moved` and `Resized` events through the same select loop as
+        /// the overlay management task, and prints the latency from each event to the flush
+        /// which applies it. Moving the overlay windows themselves is not included.
+        ///
+        /// Run with `cargo test --release -- --ignored --nocapture replay_bursts`.
+        #[test]
+        #[ignore = "benchmark"]
+        fn replay_bursts() {
+            use window_observer::tokio::sync::mpsc;
+
+            const WINDOWS: u32 = 4;
+            const EVENTS: u32 = 4_000;
+            /// About the rate of events while a window is dragged with a 1000Hz mouse.
+            const EVENT_INTERVAL: Duration = Duration::from_millis(1);
+
+            for refresh_rate in [60., 144.] {
+                let mut latencies = tauri::async_runtime::block_on(async move {
+                    let (tx, mut rx) = mpsc::unbounded_channel();
+                    tauri::async_runtime::spawn(async move {
+                        for event in 0..EVENTS {
+                            let value = f64::from(event);
+                            let geometry = if event % 2 == 0 {
+                                (position(value, value), None)
+                            } else {
+                                (None, size(value, value))
+                            };
+                            let _ = tx.send((WindowId::from(event % WINDOWS + 1), geometry));
+                            tokio::time::sleep(EVENT_INTERVAL).await;
+                        }
+                    });
+
+                    let mut pending = PendingGeometries::default();
+                    let mut flush_timer = FlushTimer::default();
+                    flush_timer.follow(Some(refresh_rate));
+                    // When each event not flushed yet was received.
+                    let mut received = Vec::new();
+                    let mut latencies = Vec::new();
+
+                    loop {
+                        tokio::select! {
+                            event = rx.recv() => {
+                                let Some((window_id, (position, size))) = event else {
+                                    break;
+                                };
+                                pending.record_geometry(window_id, (), position, size);
+                                received.push(Instant::now());
+                            }
+                            _ = flush_timer.tick(), if !pending.is_empty() => {
+                                pending.take();
+                                let flushed = Instant::now();
+                                latencies.extend(received.drain(..).map(|at| flushed - at));
+                            }
+                        }
+                    }
+
+                    latencies
+                });
+
+                latencies.sort();
+                let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+                println!(
+                    "{refresh_rate}Hz: {} events, latency p50 = {:?}, p99 = {:?}, max = {:?}",
+                    latencies.len(),
+                    percentile(50),
+                    percentile(99),
+                    latencies.last().unwrap(),
+                );
+                assert!(percentile(99) <= frame_duration(refresh_rate) * 2);
+            }
         }
     }
 }
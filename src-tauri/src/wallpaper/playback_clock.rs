@@ -0,0 +1,196 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The playback clock shared by all overlays of a wallpaper.
+///
+/// Positions are seconds from the start of the media. Pages take the remainder by
+/// the duration of the media, so all copies loop together.
+#[derive(Debug)]
+pub struct PlaybackClock {
+    /// When `position` was taken.
+    origin: Instant,
+    position: f64,
+    rate: f64,
+    paused: bool,
+}
+
+pub type SharedPlaybackClock = Arc<Mutex<PlaybackClock>>;
+
+/// The snapshot of the clock sent to wallpaper pages.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockState {
+    pub position: f64,
+    pub rate: f64,
+    pub paused: bool,
+}
+
+/// Operations on the playback clock.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PlaybackControl {
+    Pause,
+    Resume,
+    Seek { position: f64 },
+    SetRate { rate: f64 },
+}
+
+impl Default for PlaybackClock {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+            position: 0.,
+            rate: 1.,
+            paused: false,
+        }
+    }
+}
+
+impl PlaybackClock {
+    pub fn state(&self) -> ClockState {
+        self.state_at(Instant::now())
+    }
+
+    pub fn apply(&mut self, control: PlaybackControl) {
+        self.apply_at(control, Instant::now());
+    }
+
+    /// Restarts from the beginning for new media. The rate and the pause are kept.
+    pub fn reset(&mut self) {
+        self.position = 0.;
+        self.origin = Instant::now();
+    }
+
+    fn state_at(&self, now: Instant) -> ClockState {
+        ClockState {
+            position: self.position_at(now),
+            rate: self.rate,
+            paused: self.paused,
+        }
+    }
+
+    fn apply_at(&mut self, control: PlaybackControl, now: Instant) {
+        // Fix the current position so that the change applies from now on.
+        self.position = self.position_at(now);
+        self.origin = now;
+
+        match control {
+            PlaybackControl::Pause => self.paused = true,
+            PlaybackControl::Resume => self.paused = false,
+            PlaybackControl::Seek { position } => self.position = position.max(0.),
+            PlaybackControl::SetRate { rate } => self.rate = rate.max(0.),
+        }
+    }
+
+    fn position_at(&self, now: Instant) -> f64 {
+        if self.paused {
+            self.position
+        } else {
+            self.position + now.duration_since(self.origin).as_secs_f64() * self.rate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A clock started at `start`, and a function to get instants seconds after it.
+    fn clock() -> (PlaybackClock, impl Fn(u64) -> Instant) {
+        let start = Instant::now();
+        let clock = PlaybackClock {
+            origin: start,
+            ..Default::default()
+        };
+
+        (clock, move |seconds| start + Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn runs_from_start() {
+        let (clock, at) = clock();
+
+        assert_eq!(clock.position_at(at(0)), 0.);
+        assert_eq!(clock.position_at(at(2)), 2.);
+    }
+
+    #[test]
+    fn pause_stops_position() {
+        let (mut clock, at) = clock();
+
+        clock.apply_at(PlaybackControl::Pause, at(2));
+
+        let state = clock.state_at(at(5));
+        assert!(state.paused);
+        assert_eq!(state.position, 2.);
+    }
+
+    #[test]
+    fn resume_continues_from_paused_position() {
+        let (mut clock, at) = clock();
+
+        clock.apply_at(PlaybackControl::Pause, at(2));
+        clock.apply_at(PlaybackControl::Resume, at(5));
+
+        let state = clock.state_at(at(6));
+        assert!(!state.paused);
+        assert_eq!(state.position, 3.);
+    }
+
+    #[test]
+    fn seek_keeps_playing() {
+        let (mut clock, at) = clock();
+
+        clock.apply_at(PlaybackControl::Seek { position: 10. }, at(1));
+        assert_eq!(clock.position_at(at(3)), 12.);
+
+        clock.apply_at(PlaybackControl::Seek { position: -1. }, at(3));
+        assert_eq!(clock.position_at(at(3)), 0.);
+    }
+
+    #[test]
+    fn seek_keeps_pause() {
+        let (mut clock, at) = clock();
+
+        clock.apply_at(PlaybackControl::Pause, at(1));
+        clock.apply_at(PlaybackControl::Seek { position: 10. }, at(2));
+
+        let state = clock.state_at(at(5));
+        assert!(state.paused);
+        assert_eq!(state.position, 10.);
+    }
+
+    #[test]
+    fn set_rate_applies_from_now() {
+        let (mut clock, at) = clock();
+
+        clock.apply_at(PlaybackControl::SetRate { rate: 2. }, at(2));
+
+        let state = clock.state_at(at(3));
+        assert_eq!(state.rate, 2.);
+        assert_eq!(state.position, 4.);
+
+        clock.apply_at(PlaybackControl::SetRate { rate: -1. }, at(3));
+        assert_eq!(clock.position_at(at(5)), 4.);
+    }
+
+    #[test]
+    fn reset_keeps_rate_and_pause() {
+        let (mut clock, at) = clock();
+
+        clock.apply_at(PlaybackControl::SetRate { rate: 2. }, at(1));
+        clock.apply_at(PlaybackControl::Pause, at(2));
+        clock.reset();
+
+        let state = clock.state();
+        assert_eq!(state.position, 0.);
+        assert_eq!(state.rate, 2.);
+        assert!(state.paused);
+    }
+}
@@ -0,0 +1,31 @@
+use crate::config::{BatteryBehavior, Wallpaper, WallpaperSource};
+
+/// How an overlay saves power under the current power source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PowerSaving {
+    #[default]
+    None,
+    PauseAnimation,
+    Disable,
+}
+
+impl PowerSaving {
+    pub fn new(behavior: &BatteryBehavior, on_battery: bool) -> Self {
+        match (behavior, on_battery) {
+            (BatteryBehavior::PauseAnimation, true) => Self::PauseAnimation,
+            (BatteryBehavior::Disable, true) => Self::Disable,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Returns the source to show under the current power source.
+pub fn effective_source(wallpaper: &Wallpaper, on_battery: bool) -> WallpaperSource {
+    match (&wallpaper.on_battery, on_battery) {
+        (BatteryBehavior::StaticFallback { location }, true) => WallpaperSource::Picture {
+            location: location.clone(),
+            layout: wallpaper.source.layout().copied().unwrap_or_default(),
+        },
+        _ => wallpaper.source.clone(),
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    io::{Read as _, Seek as _, SeekFrom},
+    path::{Component, Path, PathBuf},
+};
+
+use percent_encoding::{NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use tauri::{
+    AppHandle, Manager, UriSchemeContext, UriSchemeResponder, Url,
+    http::{Request, Response, StatusCode, header},
+};
+use uuid::Uuid;
+
+use crate::{ConfigState, config::WallpaperSource, wallpaper::overlay::wallpaper_id_from_label};
+
+/// The scheme of the protocol serving the directories of local web page wallpapers.
+pub const SCHEME: &str = "wallpaper";
+
+const INDEX_FILE_NAME: &str = "index.html";
+
+/// Returns the URL of the page of a local web page wallpaper.
+///
+/// It is `wallpaper://<wallpaper-id>/<file>`, but custom protocols are served as
+/// `http://<scheme>.localhost` on Windows, so the ID is put in the path there.
+pub fn page_url(wallpaper_id: Uuid, page: &Path) -> Url {
+    let file_name = page
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let file_name = utf8_percent_encode(&file_name, NON_ALPHANUMERIC);
+
+    #[cfg(any(windows, target_os = "android"))]
+    let url = format!("http://{SCHEME}.localhost/{wallpaper_id}/{file_name}");
+    #[cfg(not(any(windows, target_os = "android")))]
+    let url = format!("{SCHEME}://{wallpaper_id}/{file_name}");
+
+    Url::parse(&url).expect("Failed to make wallpaper page URL")
+}
+
+/// Handles requests to the protocol.
+///
+/// Only the directory of the page of the wallpaper is served,
+/// and only to the overlay windows of the wallpaper.
+pub fn handle(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    let caller = wallpaper_id_from_label(ctx.webview_label());
+
+    tauri::async_runtime::spawn(async move {
+        let response = match resolve(&app, caller, request.uri().to_string()).await {
+            Ok(path) => serve_file(path, &request).await,
+            Err(code) => status(code),
+        };
+
+        responder.respond(response);
+    });
+}
+
+/// Resolves the file requested by the URI in the directory of the wallpaper.
+async fn resolve(
+    app: &AppHandle,
+    caller: Option<Uuid>,
+    uri: String,
+) -> Result<PathBuf, StatusCode> {
+    let url = Url::parse(&uri).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let mut segments: Vec<_> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+
+    let localhost = format!("{SCHEME}.localhost");
+    let id = if url.host_str() == Some(localhost.as_str()) {
+        if segments.is_empty() {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        segments.remove(0)
+    } else {
+        url.host_str().ok_or(StatusCode::BAD_REQUEST)?
+    };
+    let id = Uuid::parse_str(id).map_err(|_| StatusCode::NOT_FOUND)?;
+
+    if caller != Some(id) {
+        log::warn!("Blocked request to another wallpaper: {uri}");
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let dir = {
+        let config = app.state::<ConfigState>();
+        let config = config.lock().await;
+
+        match config
+            .wallpapers
+            .get(&id)
+            .map(|wallpaper| &wallpaper.source)
+        {
+            Some(WallpaperSource::LocalWebPage { location }) => location
+                .parent()
+                .map(Path::to_path_buf)
+                .ok_or(StatusCode::NOT_FOUND)?,
+            _ => return Err(StatusCode::NOT_FOUND),
+        }
+    };
+
+    resolve_in(&dir, &segments).inspect_err(|code| {
+        if *code == StatusCode::FORBIDDEN {
+            log::warn!("Blocked request to the outside of the wallpaper directory: {uri}");
+        }
+    })
+}
+
+/// Resolves the file at the path segments of a URL in `dir`.
+/// The file must be inside `dir`, even after symbolic links are followed.
+fn resolve_in(dir: &Path, segments: &[&str]) -> Result<PathBuf, StatusCode> {
+    let mut path = dir.to_path_buf();
+    for segment in segments {
+        let segment = percent_decode_str(segment)
+            .decode_utf8()
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        // Only plain names are allowed, so that the path can't go out of the directory.
+        let mut components = Path::new(segment.as_ref()).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(name)), None) => path.push(name),
+            _ => return Err(StatusCode::FORBIDDEN),
+        }
+    }
+
+    if path.is_dir() {
+        path.push(INDEX_FILE_NAME);
+    }
+
+    // Symbolic links may still point to the outside.
+    let canonical = dunce::canonicalize(&path).map_err(|_| StatusCode::NOT_FOUND)?;
+    let dir = dunce::canonicalize(dir).map_err(|_| StatusCode::NOT_FOUND)?;
+    if !canonical.starts_with(&dir) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(canonical)
+}
+
+pub(super) fn status(code: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(code)
+        .body(Vec::new())
+        .expect("Failed to make response")
+}
+
+/// Responds the file, or a part of it if the request has `Range` header.
+pub(super) async fn serve_file(path: PathBuf, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned);
+
+    tauri::async_runtime::spawn_blocking(move || read_file(&path, range.as_deref()))
+        .await
+        .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+/// Reads the file, or a part of it if `range` is like `bytes=0-1023`.
+fn read_file(path: &Path, range: Option<&str>) -> Response<Vec<u8>> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return status(StatusCode::NOT_FOUND),
+    };
+    let Ok(len) = file.metadata().map(|metadata| metadata.len()) else {
+        return status(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type(path))
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let Some(range) = range else {
+        let mut body = Vec::with_capacity(len as usize);
+        if file.read_to_end(&mut body).is_err() {
+            return status(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+
+        return builder
+            .status(StatusCode::OK)
+            .body(body)
+            .expect("Failed to make response");
+    };
+
+    let Some((start, end)) = parse_range(range, len) else {
+        return Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+            .body(Vec::new())
+            .expect("Failed to make response");
+    };
+
+    let mut body = vec![0; (end - start + 1) as usize];
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_exact(&mut body).is_err() {
+        return status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    builder
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+        .body(body)
+        .expect("Failed to make response")
+}
+
+/// The largest part of a file returned at once for open-ended ranges like `bytes=100-`.
+const MAX_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// Parses a single range of bytes into inclusive offsets.
+fn parse_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    if len == 0 || range.contains(',') {
+        return None;
+    }
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        // The last `end` bytes.
+        ("", end) => {
+            let suffix = end.parse::<u64>().ok()?.min(len);
+            (len - suffix, len - 1)
+        }
+        (start, "") => {
+            let start = start.parse::<u64>().ok()?;
+            if start >= len {
+                return None;
+            }
+            (start, start.saturating_add(MAX_RANGE_LEN - 1).min(len - 1))
+        }
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len - 1)),
+    };
+
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "js" | "mjs" => "text/javascript",
+        "css" => "text/css",
+        "json" | "map" => "application/json",
+        "wasm" => "application/wasm",
+        "txt" | "glsl" | "frag" | "vert" => "text/plain",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn parse_range_bounded() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=900-2000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=100-99", 1000), None);
+        assert_eq!(parse_range("bytes=1000-1001", 1000), None);
+    }
+
+    #[test]
+    fn parse_range_open_ended() {
+        assert_eq!(parse_range("bytes=100-", 1000), Some((100, 999)));
+        assert_eq!(
+            parse_range("bytes=0-", 2 * MAX_RANGE_LEN),
+            Some((0, MAX_RANGE_LEN - 1))
+        );
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        // It used to overflow.
+        assert_eq!(parse_range(&format!("bytes={}-", u64::MAX), 1000), None);
+    }
+
+    #[test]
+    fn parse_range_suffix() {
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-2000", 1000), Some((0, 999)));
+    }
+
+    #[test]
+    fn parse_range_invalid() {
+        assert_eq!(parse_range("bytes=0-99", 0), None);
+        assert_eq!(parse_range("bytes=0-9,20-29", 1000), None);
+        assert_eq!(parse_range("items=0-99", 1000), None);
+        assert_eq!(parse_range("bytes=a-b", 1000), None);
+    }
+
+    #[test]
+    fn resolve_files_and_index() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(INDEX_FILE_NAME), "").unwrap();
+        fs::create_dir(dir.path().join("assets")).unwrap();
+        fs::write(dir.path().join("assets").join("main file.js"), "").unwrap();
+
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        assert_eq!(resolve_in(dir.path(), &[]), Ok(root.join(INDEX_FILE_NAME)));
+        assert_eq!(
+            resolve_in(dir.path(), &["assets", "main%20file.js"]),
+            Ok(root.join("assets").join("main file.js"))
+        );
+        assert_eq!(
+            resolve_in(dir.path(), &["missing.js"]),
+            Err(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn resolve_rejects_parent_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("page")).unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        let page = dir.path().join("page");
+        assert_eq!(
+            resolve_in(&page, &["%2E%2E", "secret.txt"]),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            resolve_in(&page, &["..%2Fsecret.txt"]),
+            Err(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_rejects_symlink_escape() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("page")).unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        let page = dir.path().join("page");
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), page.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), page.join("parent")).unwrap();
+
+        assert_eq!(resolve_in(&page, &["link.txt"]), Err(StatusCode::FORBIDDEN));
+        assert_eq!(
+            resolve_in(&page, &["parent", "secret.txt"]),
+            Err(StatusCode::FORBIDDEN)
+        );
+    }
+}
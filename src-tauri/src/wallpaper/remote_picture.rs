@@ -0,0 +1,548 @@
+//! Remote pictures, which are downloaded by the backend and shown from a cache on disk.
+//!
+//! The cache is in the app cache directory and shared by the wallpapers showing the same URL.
+//! It is revalidated with `ETag` and `Last-Modified` at the refresh interval of the wallpaper,
+//! and the last downloaded picture keeps being shown while the URL is unreachable.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context as _, bail};
+use reqwest::{StatusCode, header};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, async_runtime};
+use uuid::Uuid;
+
+use crate::{
+    ConfigState,
+    config::{Layout, WallpaperSource},
+    wallpaper::{
+        asset_protocol::update_scope, overlay_host::OverlayHost, system_state,
+        wallpaper_host::SharedWallpaperConfig,
+    },
+};
+
+const TICK: Duration = Duration::from_secs(1);
+/// The shortest refresh interval, so that servers are not asked too often.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// How soon the URL is tried again after downloading failed.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// The largest picture that is downloaded, so that a URL can't fill the disk or the memory.
+const MAX_PICTURE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// What is known about the cached picture of a URL. This is saved next to the picture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    /// The name of the picture file in the cache directory.
+    file: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// The cached remote pictures, shared by all wallpapers.
+pub struct RemotePictures {
+    /// The cache directory. `None` if it is unavailable, in which case nothing is shown.
+    dir: Option<PathBuf>,
+    client: reqwest::Client,
+    /// The largest picture in bytes that is downloaded.
+    max_size: u64,
+    /// The entries read from the cache. `None` if the URL has never been downloaded.
+    entries: Mutex<HashMap<String, Option<Entry>>>,
+    /// When each URL is checked for updates next time.
+    next_checks: Mutex<HashMap<String, Instant>>,
+}
+
+impl RemotePictures {
+    pub fn new(app: &AppHandle) -> Self {
+        let dir = app
+            .path()
+            .app_cache_dir()
+            .map(|dir| dir.join("remote-pictures"))
+            .inspect_err(|e| log::warn!("Remote pictures will not be shown. Detail: {e}"))
+            .ok();
+
+        Self::with_dir(dir)
+    }
+
+    fn with_dir(dir: Option<PathBuf>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("overkami/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            dir,
+            client,
+            max_size: MAX_PICTURE_SIZE,
+            entries: Default::default(),
+            next_checks: Default::default(),
+        }
+    }
+
+    /// Returns the cached picture of the URL, or `None` if it has never been downloaded.
+    pub fn file(&self, url: &str) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        let entry = self.entry(url)?;
+
+        Some(dir.join(entry.file))
+    }
+
+    /// Returns the source showing the cached picture of the URL.
+    pub fn current(&self, url: &str, layout: &Layout) -> Option<WallpaperSource> {
+        Some(WallpaperSource::Picture {
+            location: self.file(url)?,
+            layout: *layout,
+        })
+    }
+
+    fn entry(&self, url: &str) -> Option<Entry> {
+        let dir = self.dir.as_ref()?;
+
+        self.entries
+            .lock()
+            .unwrap()
+            .entry(url.to_owned())
+            .or_insert_with(|| load_entry(dir, url))
+            .clone()
+    }
+
+    /// Whether the URL should be checked for updates now.
+    fn is_due(&self, url: &str) -> bool {
+        self.next_checks
+            .lock()
+            .unwrap()
+            .get(url)
+            .is_none_or(|at| *at <= Instant::now())
+    }
+
+    /// Downloads the picture of the URL if it is updated.
+    /// Returns whether the cached picture is changed.
+    ///
+    /// The cached picture is kept if the URL is unreachable or does not return a picture.
+    pub async fn refresh(&self, url: &str, interval: Duration) -> bool {
+        let Some(dir) = &self.dir else {
+            return false;
+        };
+
+        // Other wallpapers showing the same URL wait for this check.
+        self.schedule(url, RETRY_INTERVAL);
+
+        let cached = self.entry(url);
+        let entry = match download(&self.client, dir, url, cached.as_ref(), self.max_size).await {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!(
+                    "Failed to download the remote picture `{url}`. \
+                    The cached one is shown instead. Detail: {e:#}"
+                );
+                return false;
+            }
+        };
+        self.schedule(url, interval.max(MIN_REFRESH_INTERVAL));
+
+        let Some(entry) = entry else {
+            return false;
+        };
+        let changed = cached
+            .as_ref()
+            .is_none_or(|cached| cached.file != entry.file);
+
+        if changed
+            && let Some(cached) = cached
+            && let Err(e) = async_fs::remove_file(dir.join(&cached.file)).await
+        {
+            log::warn!("Failed to remove the old remote picture. Detail: {e}");
+        }
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(url.to_owned(), Some(entry));
+
+        changed
+    }
+
+    fn schedule(&self, url: &str, after: Duration) {
+        self.next_checks
+            .lock()
+            .unwrap()
+            .insert(url.to_owned(), Instant::now() + after);
+    }
+}
+
+/// Downloads the picture unless the cached one is still fresh, and saves it to the cache.
+/// Returns the new entry, or `None` if the cached one is not modified.
+async fn download(
+    client: &reqwest::Client,
+    dir: &Path,
+    url: &str,
+    cached: Option<&Entry>,
+    max_size: u64,
+) -> anyhow::Result<Option<Entry>> {
+    let mut request = client.get(url);
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request.send().await.context("Failed to send request")?;
+    if response.status() == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(None);
+    }
+    let mut response = response.error_for_status()?;
+
+    let header_value = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+    };
+    let content_type = header_value(header::CONTENT_TYPE).unwrap_or_default();
+    let extension = picture_extension(&content_type)
+        .with_context(|| format!("The response is not a picture but `{content_type}`"))?;
+    let etag = header_value(header::ETAG);
+    let last_modified = header_value(header::LAST_MODIFIED);
+
+    if let Some(length) = response.content_length()
+        && length > max_size
+    {
+        bail!("The picture is too large. It is {length} bytes");
+    }
+    // The length may be missing or wrong, so it is checked while reading too.
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.context("Failed to read response")? {
+        if (body.len() + chunk.len()) as u64 > max_size {
+            bail!("The picture is too large. It is over {max_size} bytes");
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    // Servers without validators return the same picture every time.
+    let unchanged = match cached {
+        Some(cached) => async_fs::read(dir.join(&cached.file))
+            .await
+            .is_ok_and(|cached| cached == body),
+        None => false,
+    };
+    // The file name changes with the picture so that webviews do not show their cache.
+    let file = match cached {
+        Some(cached) if unchanged => cached.file.clone(),
+        _ => format!("{}-{}.{extension}", cache_key(url), Uuid::new_v4().simple()),
+    };
+
+    async_fs::create_dir_all(dir)
+        .await
+        .context("Failed to create cache directory")?;
+    if !unchanged {
+        async_fs::write(dir.join(&file), &body)
+            .await
+            .context("Failed to save picture")?;
+    }
+
+    let entry = Entry {
+        url: url.to_owned(),
+        file,
+        etag,
+        last_modified,
+    };
+    async_fs::write(entry_file(dir, url), serde_json::to_vec(&entry)?)
+        .await
+        .context("Failed to save cache entry")?;
+
+    Ok(Some(entry))
+}
+
+fn load_entry(dir: &Path, url: &str) -> Option<Entry> {
+    let raw = std::fs::read(entry_file(dir, url)).ok()?;
+    let entry: Entry = serde_json::from_slice(&raw)
+        .inspect_err(|e| log::warn!("Failed to read the cached remote picture. Detail: {e}"))
+        .ok()?;
+
+    // The key may collide with another URL.
+    (entry.url == url && dir.join(&entry.file).exists()).then_some(entry)
+}
+
+fn entry_file(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{}.json", cache_key(url)))
+}
+
+/// A file name for the URL which is the same across runs. This is FNV-1a.
+fn cache_key(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+/// SVG is not accepted, since it can run scripts and load other resources in the page.
+fn picture_extension(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+
+    Some(match mime.as_str() {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        "image/bmp" => "bmp",
+        _ => return None,
+    })
+}
+
+/// Checks the remote picture of the wallpaper for updates at its refresh interval,
+/// and lets the overlays show the new one.
+pub fn spawn_refresh_task(
+    app: AppHandle,
+    config: SharedWallpaperConfig,
+    overlay_hosts: Weak<async_runtime::Mutex<Vec<OverlayHost>>>,
+) {
+    async_runtime::spawn(async move {
+        let pictures = app.state::<RemotePictures>();
+        // The URL and the picture which the overlays were given.
+        let mut shown: Option<(String, Option<PathBuf>)> = None;
+
+        loop {
+            tokio::time::sleep(TICK).await;
+
+            // The wallpaper host is dropped.
+            let Some(overlay_hosts) = overlay_hosts.upgrade() else {
+                break;
+            };
+            let source = config.lock().await.source.clone();
+            let WallpaperSource::RemotePicture {
+                location,
+                refresh_interval,
+                ..
+            } = source
+            else {
+                shown = None;
+                continue;
+            };
+
+            // The overlays are given the cached picture when the source is applied.
+            let (shown_location, shown_file) =
+                shown.get_or_insert_with(|| (location.clone(), pictures.file(&location)));
+            if *shown_location != location {
+                *shown_location = location.clone();
+                *shown_file = pictures.file(&location);
+            }
+
+            if system_state::is_paused() || !pictures.is_due(&location) {
+                continue;
+            }
+            pictures
+                .refresh(&location, Duration::from_secs(refresh_interval))
+                .await;
+
+            let file = pictures.file(&location);
+            if *shown_file == file {
+                continue;
+            }
+            *shown_file = file;
+
+            // The asset protocol serves only the files known by the scope.
+            {
+                let config = app.state::<ConfigState>();
+                update_scope(&app, &*config.lock().await);
+            }
+
+            for overlay_host in overlay_hosts.lock().await.iter() {
+                for overlay in overlay_host.overlays().lock().await.values_mut() {
+                    overlay.show_slide();
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Serves the responses in order on a local HTTP server, and returns the URL and the
+    /// headers of the requests it received.
+    fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/picture", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = String::new();
+
+                    let mut reader = BufReader::new(&stream);
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                        request.push_str(&line.to_ascii_lowercase());
+                    }
+
+                    stream.write_all(response.as_bytes()).unwrap();
+                    request
+                })
+                .collect()
+        });
+
+        (url, server)
+    }
+
+    fn picture(etag: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nETag: \"{etag}\"\r\n\
+            Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    /// A picture without `Content-Length`, whose end is told by closing the connection.
+    fn picture_without_length(body: &str) -> String {
+        format!("HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nConnection: close\r\n\r\n{body}")
+    }
+
+    fn not_modified() -> String {
+        "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_owned()
+    }
+
+    /// The cache directory is created by [`RemotePictures`], so it doesn't exist yet.
+    fn cache_dir(temp: &TempDir) -> PathBuf {
+        temp.path().join("cache")
+    }
+
+    #[test]
+    fn revalidates_cached_picture() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = cache_dir(&temp);
+        let (url, server) = serve(vec![
+            picture("1", "first"),
+            not_modified(),
+            picture("2", "second"),
+        ]);
+        let pictures = RemotePictures::with_dir(Some(dir.clone()));
+
+        async_runtime::block_on(async {
+            assert!(pictures.refresh(&url, INTERVAL).await);
+            let first = pictures.file(&url).unwrap();
+            assert_eq!(first.extension().unwrap(), "png");
+            assert_eq!(std::fs::read_to_string(&first).unwrap(), "first");
+
+            assert!(!pictures.refresh(&url, INTERVAL).await);
+            assert_eq!(pictures.file(&url).unwrap(), first);
+
+            assert!(pictures.refresh(&url, INTERVAL).await);
+            let second = pictures.file(&url).unwrap();
+            assert_eq!(std::fs::read_to_string(&second).unwrap(), "second");
+            assert!(!first.exists());
+        });
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"1\""));
+    }
+
+    #[test]
+    fn keeps_cached_picture_while_offline() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = cache_dir(&temp);
+        let (url, server) = serve(vec![picture("1", "first")]);
+        let pictures = RemotePictures::with_dir(Some(dir.clone()));
+
+        async_runtime::block_on(async {
+            assert!(pictures.refresh(&url, INTERVAL).await);
+            // The server is stopped.
+            server.join().unwrap();
+
+            assert!(!pictures.refresh(&url, INTERVAL).await);
+        });
+
+        // The cache is also read after restart.
+        let pictures = RemotePictures::with_dir(Some(dir.clone()));
+        let file = pictures.file(&url).unwrap();
+        assert_eq!(std::fs::read_to_string(file).unwrap(), "first");
+    }
+
+    #[test]
+    fn rejects_responses_other_than_pictures() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = cache_dir(&temp);
+        let page = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\
+            Content-Length: 2\r\nConnection: close\r\n\r\nhi"
+            .to_owned();
+        let (url, server) = serve(vec![page]);
+        let pictures = RemotePictures::with_dir(Some(dir.clone()));
+
+        assert!(!async_runtime::block_on(pictures.refresh(&url, INTERVAL)));
+        assert!(pictures.file(&url).is_none());
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn rejects_svg() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = cache_dir(&temp);
+        let svg = "HTTP/1.1 200 OK\r\nContent-Type: image/svg+xml\r\n\
+            Content-Length: 6\r\nConnection: close\r\n\r\n<svg/>"
+            .to_owned();
+        let (url, server) = serve(vec![svg]);
+        let pictures = RemotePictures::with_dir(Some(dir.clone()));
+
+        assert!(!async_runtime::block_on(pictures.refresh(&url, INTERVAL)));
+        assert!(pictures.file(&url).is_none());
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn rejects_too_large_pictures() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = cache_dir(&temp);
+        let (url, server) = serve(vec![
+            picture("1", "too large"),
+            picture_without_length("too large"),
+            picture_without_length("small"),
+        ]);
+        let mut pictures = RemotePictures::with_dir(Some(dir.clone()));
+        pictures.max_size = 5;
+
+        async_runtime::block_on(async {
+            // Told by `Content-Length`.
+            assert!(!pictures.refresh(&url, INTERVAL).await);
+            // Found while reading.
+            assert!(!pictures.refresh(&url, INTERVAL).await);
+            assert!(pictures.file(&url).is_none());
+
+            assert!(pictures.refresh(&url, INTERVAL).await);
+            let file = pictures.file(&url).unwrap();
+            assert_eq!(std::fs::read_to_string(file).unwrap(), "small");
+        });
+
+        server.join().unwrap();
+    }
+}
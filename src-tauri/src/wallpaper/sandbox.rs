@@ -0,0 +1,348 @@
+use tauri::{AppHandle, Url, WebviewUrl, WebviewWindowBuilder, Wry, webview::NewWindowResponse};
+use uuid::Uuid;
+
+use crate::config::NavigationPolicy;
+
+/// The script denying permissions which would show prompts, injected into every frame of overlays.
+///
+/// Overlay windows can't be focused, so nobody can answer the prompts anyway.
+/// The webviews deny them natively as well, which covers the APIs the script misses.
+const DENY_PERMISSIONS_SCRIPT: &str = r#"
+(() => {
+  const denied = () =>
+    Promise.reject(new DOMException("Permission denied", "NotAllowedError"));
+
+  if (window.MediaDevices) {
+    MediaDevices.prototype.getUserMedia = denied;
+    MediaDevices.prototype.getDisplayMedia = denied;
+  }
+  for (const name of ["getUserMedia", "webkitGetUserMedia"]) {
+    if (name in navigator) {
+      navigator[name] = (_, __, onError) =>
+        onError?.(new DOMException("Permission denied", "NotAllowedError"));
+    }
+  }
+
+  if (window.Notification) {
+    Object.defineProperty(Notification, "permission", { get: () => "denied" });
+    Notification.requestPermission = (callback) => {
+      callback?.("denied");
+      return Promise.resolve("denied");
+    };
+  }
+
+  if (window.Geolocation) {
+    const fail = (_, onError) =>
+      onError?.({ code: 1, message: "User denied Geolocation" });
+    Geolocation.prototype.getCurrentPosition = fail;
+    Geolocation.prototype.watchPosition = (...args) => {
+      fail(...args);
+      return 0;
+    };
+  }
+
+  if (window.Permissions) {
+    const query = Permissions.prototype.query;
+    const prompting = ["camera", "microphone", "notifications", "geolocation"];
+    Permissions.prototype.query = function (descriptor) {
+      return prompting.includes(descriptor?.name)
+        ? Promise.resolve({ name: descriptor.name, state: "denied", onchange: null })
+        : query.call(this, descriptor);
+    };
+  }
+})();
+"#;
+
+/// Restricts what the page in an overlay window can do.
+///
+/// Popups and permissions are always denied. Web pages are also kept in the navigation policy
+/// and given their own storage per wallpaper, so that cookies and storages are not shared with
+/// other wallpapers or the app.
+pub fn apply<'a>(
+    builder: WebviewWindowBuilder<'a, Wry, AppHandle>,
+    app: &AppHandle,
+    wallpaper_id: Uuid,
+    url: &WebviewUrl,
+    policy: &NavigationPolicy,
+) -> WebviewWindowBuilder<'a, Wry, AppHandle> {
+    let builder = builder
+        .initialization_script_for_all_frames(DENY_PERMISSIONS_SCRIPT)
+        .on_new_window(move |url, _| {
+            log::warn!("Blocked new window to `{url}` from wallpaper `{wallpaper_id}`.");
+            NewWindowResponse::Deny
+        });
+
+    // Built-in wallpapers are pages of the app itself.
+    let WebviewUrl::External(start) = url else {
+        return builder;
+    };
+
+    let start = start.clone();
+    let policy = policy.clone();
+    let builder = builder.on_navigation(move |url| {
+        let allowed = is_navigation_allowed(&policy, &start, url);
+        if !allowed {
+            log::warn!("Blocked navigation to `{url}` from wallpaper `{wallpaper_id}`.");
+        }
+
+        allowed
+    });
+
+    isolate_data(builder, app, wallpaper_id)
+}
+
+/// Gives the webview its own cookies and storages per wallpaper.
+#[cfg(target_os = "macos")]
+fn isolate_data<'a>(
+    builder: WebviewWindowBuilder<'a, Wry, AppHandle>,
+    _app: &AppHandle,
+    wallpaper_id: Uuid,
+) -> WebviewWindowBuilder<'a, Wry, AppHandle> {
+    // WKWebView has no data directory, but data stores identified by UUIDs instead.
+    builder.data_store_identifier(*wallpaper_id.as_bytes())
+}
+
+/// Gives the webview its own cookies and storages per wallpaper.
+#[cfg(not(target_os = "macos"))]
+fn isolate_data<'a>(
+    builder: WebviewWindowBuilder<'a, Wry, AppHandle>,
+    app: &AppHandle,
+    wallpaper_id: Uuid,
+) -> WebviewWindowBuilder<'a, Wry, AppHandle> {
+    match data_directory(app, wallpaper_id) {
+        Ok(dir) => builder.data_directory(dir),
+        Err(e) => {
+            log::warn!("Failed to isolate webview data of wallpaper. Detail: {e:#}");
+            builder
+        }
+    }
+}
+
+/// Removes the webview data of a wallpaper which is no longer used.
+pub async fn remove_data(app: &AppHandle, wallpaper_id: Uuid) {
+    #[cfg(target_os = "macos")]
+    let result = app
+        .remove_data_store(*wallpaper_id.as_bytes())
+        .await
+        .map_err(anyhow::Error::from);
+    #[cfg(not(target_os = "macos"))]
+    let result = match data_directory(app, wallpaper_id) {
+        Ok(dir) if dir.exists() => async_fs::remove_dir_all(dir)
+            .await
+            .map_err(anyhow::Error::from),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        log::warn!("Failed to remove webview data of wallpaper `{wallpaper_id}`. Detail: {e:#}");
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn data_directory(app: &AppHandle, wallpaper_id: Uuid) -> anyhow::Result<std::path::PathBuf> {
+    use anyhow::Context as _;
+    use tauri::Manager as _;
+
+    let dir = app
+        .path()
+        .app_local_data_dir()
+        .context("Failed to get app local data directory")?;
+
+    Ok(dir.join("webview").join(wallpaper_id.to_string()))
+}
+
+/// Whether the page which started at `start` can navigate to `url`.
+fn is_navigation_allowed(policy: &NavigationPolicy, start: &Url, url: &Url) -> bool {
+    match policy {
+        NavigationPolicy::SameOrigin => is_same_origin(start, url),
+        NavigationPolicy::Allowlist { origins } => {
+            is_same_origin(start, url) || origins.iter().any(|origin| matches_origin(origin, url))
+        }
+        NavigationPolicy::Block => {
+            let mut start = start.clone();
+            let mut url = url.clone();
+            start.set_fragment(None);
+            url.set_fragment(None);
+
+            start == url
+        }
+    }
+}
+
+/// Compares the scheme, host and port.
+///
+/// [`Url::origin`] is not used because it is opaque for custom protocols like `wallpaper://`.
+fn is_same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Checks `url` against an origin like `https://example.com` or `https://*.example.com:8080`.
+fn matches_origin(origin: &str, url: &Url) -> bool {
+    let Some((scheme, authority)) = origin.trim().trim_end_matches('/').split_once("://") else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    if !scheme.eq_ignore_ascii_case(url.scheme()) {
+        return false;
+    }
+
+    let (pattern, port) = match authority.rsplit_once(':') {
+        Some((pattern, port)) => match port.parse::<u16>() {
+            Ok(port) => (pattern, Some(port)),
+            Err(_) => return false,
+        },
+        None => (authority, None),
+    };
+    let port_matches = match port {
+        Some(port) => url.port_or_known_default() == Some(port),
+        None => url.port().is_none(),
+    };
+    if !port_matches {
+        return false;
+    }
+
+    let pattern = pattern.to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_only() {
+        let origin = "https://*.example.com";
+
+        assert!(matches_origin(origin, &url("https://www.example.com/")));
+        assert!(matches_origin(origin, &url("https://a.b.example.com/page")));
+        assert!(!matches_origin(origin, &url("https://example.com/")));
+        assert!(!matches_origin(origin, &url("http://www.example.com/")));
+    }
+
+    #[test]
+    fn lookalike_hosts_do_not_match() {
+        assert!(!matches_origin(
+            "https://*.example.com",
+            &url("https://evil-example.com/")
+        ));
+        assert!(!matches_origin(
+            "https://example.com",
+            &url("https://evil-example.com/")
+        ));
+        assert!(!matches_origin(
+            "https://example.com",
+            &url("https://example.com.evil.org/")
+        ));
+    }
+
+    #[test]
+    fn ports_must_match() {
+        assert!(matches_origin(
+            "https://example.com:8443",
+            &url("https://example.com:8443/")
+        ));
+        assert!(matches_origin(
+            "https://example.com:443",
+            &url("https://example.com/")
+        ));
+        assert!(!matches_origin(
+            "https://example.com",
+            &url("https://example.com:8443/")
+        ));
+        assert!(!matches_origin(
+            "https://example.com:8443",
+            &url("https://example.com/")
+        ));
+        assert!(!matches_origin(
+            "https://example.com:port",
+            &url("https://example.com/")
+        ));
+    }
+
+    #[test]
+    fn same_origin_policy() {
+        let start = url("https://example.com/index.html");
+        let policy = NavigationPolicy::SameOrigin;
+
+        assert!(is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com/other.html")
+        ));
+        assert!(!is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com:8443/")
+        ));
+        assert!(!is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://www.example.com/")
+        ));
+    }
+
+    #[test]
+    fn allowlist_policy() {
+        let start = url("https://example.com/");
+        let policy = NavigationPolicy::Allowlist {
+            origins: vec!["https://*.example.org".to_owned()],
+        };
+
+        assert!(is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com/other")
+        ));
+        assert!(is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://cdn.example.org/")
+        ));
+        assert!(!is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://evil-example.org/")
+        ));
+    }
+
+    #[test]
+    fn block_policy_allows_fragments_only() {
+        let start = url("https://example.com/index.html#intro");
+        let policy = NavigationPolicy::Block;
+
+        assert!(is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com/index.html#settings")
+        ));
+        assert!(is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com/index.html")
+        ));
+        assert!(!is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com/index.html?page=2")
+        ));
+        assert!(!is_navigation_allowed(
+            &policy,
+            &start,
+            &url("https://example.com/other.html")
+        ));
+    }
+}
@@ -0,0 +1,924 @@
+//! Slideshows, which show the pictures and videos in a folder or a playlist in turn.
+//!
+//! Overlays keep the slideshow as their source, and show its current item when their
+//! windows are created. The rotation task moves to the next item and lets the overlays
+//! switch to it through the usual transitions. It also watches the files of the slideshow
+//! and lists the items again when they are changed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash, Hasher, RandomState},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Manager,
+    async_runtime::{self, Receiver, Sender},
+};
+use uuid::Uuid;
+use window_getter::WindowId;
+
+use crate::{
+    config::{Layout, SlideshowOptions, SlideshowOrder, VideoOptions, WallpaperSource},
+    wallpaper::{
+        asset_protocol, overlay_host::OverlayHost, playback_clock::SharedPlaybackClock,
+        system_state, wallpaper_host::SharedWallpaperConfig,
+    },
+};
+
+pub const PICTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "avif", "bmp"];
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm", "avi", "mov", "flv"];
+
+const TICK: Duration = Duration::from_secs(1);
+/// How often the folder or the playlist is read again if it can't be watched.
+const SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Returns the source showing the file, or `None` if it is neither a picture nor a video.
+pub fn item_source(path: &Path) -> Option<WallpaperSource> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let location = path.to_path_buf();
+
+    if PICTURE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(WallpaperSource::Picture {
+            location,
+            layout: Layout::default(),
+        })
+    } else if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        Some(WallpaperSource::Video {
+            location,
+            layout: Layout::default(),
+            options: VideoOptions::default(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Reads the paths listed one per line in the playlist.
+/// Relative paths are resolved from the folder of the playlist, and lines starting with `#`
+/// are ignored, so M3U playlists can be used too.
+pub fn read_playlist(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read playlist `{}`", path.display()))?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| dir.join(line))
+        .collect())
+}
+
+/// Lists the pictures and videos of the slideshow in the configured order without shuffling.
+pub fn list_items(location: &Path, options: &SlideshowOptions) -> anyhow::Result<Vec<PathBuf>> {
+    let mut items = if location.is_dir() {
+        let mut items = Vec::new();
+        scan_dir(location, options.recursive, &mut items)?;
+        items.sort();
+        items
+    } else {
+        read_playlist(location)?
+    };
+    items.retain(|item| item_source(item).is_some() && item.is_file());
+
+    match options.order {
+        SlideshowOrder::Listed => {}
+        SlideshowOrder::Name => items.sort_by(|a, b| a.file_name().cmp(&b.file_name())),
+        SlideshowOrder::Modified => items.sort_by_cached_key(|item| {
+            std::fs::metadata(item)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        }),
+    }
+
+    Ok(items)
+}
+
+fn scan_dir(dir: &Path, recursive: bool, items: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read folder `{}`", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry?;
+
+        // Linked folders are skipped since they may make loops.
+        if entry.file_type()?.is_dir() {
+            if recursive {
+                scan_dir(&entry.path(), true, items)?;
+            }
+        } else {
+            items.push(entry.path());
+        }
+    }
+
+    Ok(())
+}
+
+fn shuffle(items: &mut [PathBuf]) {
+    // A xorshift seeded by the random keys of the standard library is enough for this.
+    let mut seed = RandomState::new().build_hasher().finish() | 1;
+
+    for i in (1..items.len()).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        items.swap(i, (seed % (i as u64 + 1)) as usize);
+    }
+}
+
+/// Where all windows, or a window rotating independently, are in the slideshow.
+#[derive(Debug, Clone, Copy)]
+struct Rotation {
+    position: usize,
+    /// When to move to the next item.
+    next: Instant,
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self {
+            position: 0,
+            next: Instant::now(),
+        }
+    }
+}
+
+/// The state of the slideshow. Windows are identified by `W`, which tests replace.
+struct State<W = WindowId> {
+    /// The location and options that `items` are listed with.
+    settings: Option<(PathBuf, SlideshowOptions)>,
+    /// The items in the order to show.
+    items: Vec<PathBuf>,
+    /// The rotation of all windows. New windows rotating independently start next to it.
+    shared: Rotation,
+    windows: HashMap<W, Rotation>,
+}
+
+impl<W> Default for State<W> {
+    fn default() -> Self {
+        Self {
+            settings: None,
+            items: Vec::new(),
+            shared: Rotation::default(),
+            windows: HashMap::new(),
+        }
+    }
+}
+
+impl<W: Copy + Eq + Hash> State<W> {
+    fn interval(&self) -> Duration {
+        let interval = self
+            .settings
+            .as_ref()
+            .map_or(0, |(_, options)| options.interval);
+        Duration::from_secs(interval.max(1))
+    }
+
+    fn is_independent(&self) -> bool {
+        self.settings
+            .as_ref()
+            .is_some_and(|(_, options)| options.independent)
+    }
+
+    fn is_shuffled(&self) -> bool {
+        self.settings
+            .as_ref()
+            .is_some_and(|(_, options)| options.shuffle)
+    }
+
+    /// The items shown by all windows and by each window rotating independently.
+    fn shown(&self) -> Vec<Option<&PathBuf>> {
+        std::iter::once(&self.shared)
+            .chain(self.windows.values())
+            .map(|rotation| self.items.get(rotation.position))
+            .collect()
+    }
+
+    /// Starts over with the new settings. The saved order and position are used if they are
+    /// for the same location.
+    fn reset(
+        &mut self,
+        settings: (PathBuf, SlideshowOptions),
+        items: Vec<PathBuf>,
+        saved: Option<Saved>,
+    ) {
+        let shuffled = settings.1.shuffle;
+        let saved = saved.filter(|saved| saved.location == settings.0);
+
+        self.settings = Some(settings);
+        self.windows.clear();
+        self.shared = Rotation {
+            position: 0,
+            next: Instant::now() + self.interval(),
+        };
+
+        match saved {
+            Some(saved) => {
+                self.items = saved.items;
+                self.shared.position = saved.position;
+                self.merge(items);
+            }
+            None => {
+                self.items = items;
+                if shuffled {
+                    shuffle(&mut self.items);
+                }
+            }
+        }
+    }
+
+    /// Replaces the items with the ones listed again, keeping the shown items if they remain.
+    fn merge(&mut self, items: Vec<PathBuf>) -> Reload {
+        let present: HashSet<_> = items.iter().cloned().collect();
+        if self.items.len() == items.len() && self.items.iter().all(|item| present.contains(item)) {
+            // The shuffled order is kept if the items are the same.
+            if self.is_shuffled() || self.items == items {
+                return Reload::default();
+            }
+        }
+
+        let before: Vec<_> = self.shown().into_iter().map(|item| item.cloned()).collect();
+        let old = std::mem::take(&mut self.items);
+
+        self.items = if self.is_shuffled() {
+            // New items are added to the end of the shuffled order.
+            let known: HashSet<_> = old.iter().collect();
+            let mut merged: Vec<_> = old
+                .iter()
+                .filter(|item| present.contains(*item))
+                .cloned()
+                .collect();
+            merged.extend(items.into_iter().filter(|item| !known.contains(item)));
+            merged
+        } else {
+            items
+        };
+
+        let index: HashMap<_, _> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(position, item)| (item, position))
+            .collect();
+        // The item at the position, or the next one remaining if it is removed.
+        let remap = |position: usize| {
+            old.iter()
+                .skip(position)
+                .find_map(|item| index.get(item).copied())
+                .unwrap_or(0)
+        };
+
+        self.shared.position = remap(self.shared.position);
+        for rotation in self.windows.values_mut() {
+            rotation.position = remap(rotation.position);
+        }
+
+        let after: Vec<_> = self.shown().into_iter().map(|item| item.cloned()).collect();
+        Reload {
+            items_changed: old != self.items,
+            shown_changed: before != after,
+        }
+    }
+
+    /// Moves all windows to the next item if it is time. Returns whether they moved.
+    fn advance(&mut self, now: Instant) -> bool {
+        if now < self.shared.next || self.items.is_empty() {
+            return false;
+        }
+        self.shared.next = now + self.interval();
+        self.shared.position += 1;
+
+        if self.shared.position >= self.items.len() {
+            self.shared.position = 0;
+
+            if self.is_shuffled() {
+                let last = self.items.last().cloned();
+                shuffle(&mut self.items);
+
+                // Avoid showing the same item twice in a row over the rounds.
+                if self.items.len() > 1 && self.items.first() == last.as_ref() {
+                    let end = self.items.len() - 1;
+                    self.items.swap(0, end);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the rotation of the window rotating independently, starting one for it
+    /// if it is new.
+    fn window(&mut self, window: W, now: Instant) -> &mut Rotation {
+        let position = match self.items.len() {
+            0 => 0,
+            len => (self.shared.position + self.windows.len()) % len,
+        };
+        let next = now + self.interval();
+
+        self.windows
+            .entry(window)
+            .or_insert(Rotation { position, next })
+    }
+
+    /// Moves the window to the next item if it is time. Returns whether it moved.
+    fn advance_window(&mut self, window: W, now: Instant) -> bool {
+        let len = self.items.len();
+        let interval = self.interval();
+        let rotation = self.window(window, now);
+        if now < rotation.next || len == 0 {
+            return false;
+        }
+
+        rotation.next = now + interval;
+        rotation.position = (rotation.position + 1) % len;
+        let position = rotation.position;
+
+        // Windows opened later and the next launch start from here.
+        self.shared.position = position;
+
+        true
+    }
+}
+
+/// What is changed by listing the items of the slideshow again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reload {
+    /// Whether the items are added, removed or reordered.
+    pub items_changed: bool,
+    /// Whether the item shown by any window is changed.
+    pub shown_changed: bool,
+}
+
+/// The order and position saved to resume the slideshow after restart.
+#[derive(Debug, Serialize, Deserialize)]
+struct Saved {
+    location: PathBuf,
+    items: Vec<PathBuf>,
+    position: usize,
+}
+
+/// The slideshow of a wallpaper, shared by its overlays.
+pub struct Slideshow {
+    app: AppHandle,
+    wallpaper_id: Uuid,
+    /// Where the order and position are saved. `None` if the directory is unavailable.
+    file: Option<PathBuf>,
+    state: Mutex<State>,
+}
+
+pub type SharedSlideshow = Arc<Slideshow>;
+
+impl Slideshow {
+    pub fn new(app: &AppHandle, wallpaper_id: Uuid) -> Self {
+        let file = state_file(app, wallpaper_id)
+            .inspect_err(|e| log::warn!("Slideshow position will not be saved. Detail: {e:#}"))
+            .ok();
+
+        Self {
+            app: app.clone(),
+            wallpaper_id,
+            file,
+            state: Default::default(),
+        }
+    }
+
+    /// Returns the item to show in the window, or `None` if there is nothing to show.
+    pub fn current(&self, window: WindowId) -> Option<WallpaperSource> {
+        let mut state = self.state.lock().unwrap();
+
+        let position = if state.is_independent() {
+            state.window(window, Instant::now()).position
+        } else {
+            state.shared.position
+        };
+
+        state.items.get(position).and_then(|item| item_source(item))
+    }
+
+    /// Lists the items of the slideshow again. This must be called when the source is changed.
+    ///
+    /// The asset protocol scope is updated with the items when they are changed.
+    pub async fn reload(&self, source: &WallpaperSource) -> Reload {
+        let WallpaperSource::Slideshow { location, options } = source else {
+            self.clear();
+            return Reload::default();
+        };
+        let settings = (location.clone(), options.clone());
+
+        let items = {
+            let (location, options) = settings.clone();
+            async_runtime::spawn_blocking(move || list_items(&location, &options))
+                .await
+                .expect("Failed to list slideshow items")
+        };
+        let items = match items {
+            Ok(items) => items,
+            Err(e) => {
+                // The items are kept, since the folder may be unavailable for a moment.
+                log::warn!("Failed to list the items of the slideshow. Detail: {e:#}");
+                return Reload::default();
+            }
+        };
+
+        let (reload, items) = {
+            let mut state = self.state.lock().unwrap();
+
+            let reload = if state.settings.as_ref() == Some(&settings) {
+                state.merge(items)
+            } else {
+                // The saved position is only for resuming after restart.
+                let saved = state.settings.is_none().then(|| self.load()).flatten();
+                state.reset(settings, items, saved);
+                Reload {
+                    items_changed: true,
+                    shown_changed: true,
+                }
+            };
+
+            (reload, reload.items_changed.then(|| state.items.clone()))
+        };
+
+        // The asset protocol serves only the files known by the scope.
+        if let Some(items) = items {
+            asset_protocol::set_slideshow_files(&self.app, self.wallpaper_id, items);
+        }
+        if reload.items_changed || reload.shown_changed {
+            self.save();
+        }
+
+        reload
+    }
+
+    /// Forgets the items, and removes them from the asset protocol scope.
+    /// This must be called when the wallpaper is stopped.
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = State::default();
+        asset_protocol::set_slideshow_files(&self.app, self.wallpaper_id, Vec::new());
+    }
+
+    fn load(&self) -> Option<Saved> {
+        let raw = std::fs::read(self.file.as_ref()?).ok()?;
+
+        serde_json::from_slice(&raw)
+            .inspect_err(|e| log::warn!("Failed to read the saved slideshow. Detail: {e}"))
+            .ok()
+    }
+
+    fn save(&self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let saved = {
+            let state = self.state.lock().unwrap();
+            let Some((location, _)) = &state.settings else {
+                return;
+            };
+
+            Saved {
+                location: location.clone(),
+                items: state.items.clone(),
+                position: state.shared.position,
+            }
+        };
+
+        if let Err(e) = write_saved(file, &saved) {
+            log::warn!("Failed to save the slideshow position. Detail: {e:#}");
+        }
+    }
+}
+
+fn write_saved(file: &Path, saved: &Saved) -> anyhow::Result<()> {
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(file, serde_json::to_vec(saved)?)?;
+
+    Ok(())
+}
+
+fn state_file(app: &AppHandle, wallpaper_id: Uuid) -> anyhow::Result<PathBuf> {
+    let dir = app
+        .path()
+        .app_local_data_dir()
+        .context("Failed to get app local data directory")?;
+
+    Ok(dir.join("slideshows").join(format!("{wallpaper_id}.json")))
+}
+
+/// Removes the saved position of the slideshow of the wallpaper, if any.
+pub async fn remove_state(app: &AppHandle, wallpaper_id: Uuid) {
+    let Ok(file) = state_file(app, wallpaper_id) else {
+        return;
+    };
+
+    if file.exists()
+        && let Err(e) = async_fs::remove_file(file).await
+    {
+        log::warn!("Failed to remove the slideshow of wallpaper `{wallpaper_id}`. Detail: {e}");
+    }
+}
+
+/// Watches the files of the slideshow, so that its items are listed again when they change.
+struct ItemWatcher {
+    settings: (PathBuf, SlideshowOptions),
+    /// `None` if the location can't be watched. It is scanned periodically instead.
+    watcher: Option<RecommendedWatcher>,
+    scanned_at: Instant,
+}
+
+impl ItemWatcher {
+    fn new(location: &Path, options: &SlideshowOptions, tx: Sender<()>) -> Self {
+        let watcher = watch(location, options, tx)
+            .inspect_err(|e| {
+                log::warn!(
+                    "Failed to watch the slideshow `{}`, so it is scanned every {} seconds. \
+                    Detail: {e:#}",
+                    location.display(),
+                    SCAN_INTERVAL.as_secs()
+                )
+            })
+            .ok();
+
+        Self {
+            settings: (location.to_path_buf(), options.clone()),
+            watcher,
+            scanned_at: Instant::now(),
+        }
+    }
+
+    fn is_for(&self, location: &Path, options: &SlideshowOptions) -> bool {
+        self.settings.0 == location && self.settings.1 == *options
+    }
+
+    /// Whether the items should be listed again. `rx` receives the changes from the watcher.
+    fn needs_scan(&mut self, rx: &mut Receiver<()>) -> bool {
+        let changed = rx.try_recv().is_ok();
+
+        let due = self.watcher.is_none() && self.scanned_at.elapsed() >= SCAN_INTERVAL;
+        if changed || due {
+            self.scanned_at = Instant::now();
+        }
+
+        changed || due
+    }
+}
+
+fn watch(
+    location: &Path,
+    options: &SlideshowOptions,
+    tx: Sender<()>,
+) -> anyhow::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        // Listing the items reads the files too, which must not trigger another scan.
+        // A full channel means that a scan is already pending.
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            let _ = tx.try_send(());
+        }
+    })
+    .context("Failed to create file watcher")?;
+
+    if location.is_dir() {
+        let mode = if options.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(location, mode)
+            .with_context(|| format!("Failed to watch folder `{}`", location.display()))?;
+    } else {
+        // Editors often replace files, so the folders of the playlist and its items are watched
+        // instead of the files themselves.
+        let mut dirs: HashSet<_> = read_playlist(location)?
+            .iter()
+            .filter_map(|item| item.parent().map(Path::to_path_buf))
+            .collect();
+        dirs.extend(location.parent().map(Path::to_path_buf));
+
+        for dir in dirs {
+            if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                log::debug!("Failed to watch folder `{}`. Detail: {e}", dir.display());
+            }
+        }
+    }
+
+    Ok(watcher)
+}
+
+/// Moves the slideshow of the wallpaper to the next item at the interval, and lists its items
+/// again when its files are changed.
+pub fn spawn_rotation_task(
+    config: SharedWallpaperConfig,
+    overlay_hosts: Weak<async_runtime::Mutex<Vec<OverlayHost>>>,
+    clock: SharedPlaybackClock,
+    slideshow: SharedSlideshow,
+) {
+    async_runtime::spawn(async move {
+        let (tx, mut rx) = async_runtime::channel(1);
+        let mut watched: Option<ItemWatcher> = None;
+
+        loop {
+            tokio::time::sleep(TICK).await;
+
+            // The wallpaper host is dropped.
+            let Some(overlay_hosts) = overlay_hosts.upgrade() else {
+                break;
+            };
+            let source = config.lock().await.source.clone();
+            let WallpaperSource::Slideshow { location, options } = &source else {
+                watched = None;
+                continue;
+            };
+
+            let watcher = match watched.take() {
+                Some(watcher) if watcher.is_for(location, options) => watched.insert(watcher),
+                _ => watched.insert(ItemWatcher::new(location, options, tx.clone())),
+            };
+
+            let mut changed = false;
+            if watcher.needs_scan(&mut rx) {
+                let reload = slideshow.reload(&source).await;
+                changed = reload.shown_changed;
+
+                // The playlist may list items in other folders now.
+                if reload.items_changed && !location.is_dir() {
+                    *watcher = ItemWatcher::new(location, options, tx.clone());
+                }
+            }
+
+            if system_state::is_paused() && !changed {
+                continue;
+            }
+
+            let now = Instant::now();
+            let moved = !options.independent && slideshow.state.lock().unwrap().advance(now);
+            // The next item starts from the beginning. Items of independent slideshows share
+            // the clock with the other windows, so they join at its current position instead.
+            if changed || moved {
+                clock.lock().unwrap().reset();
+            }
+
+            let mut advanced_any = false;
+            let mut windows = Vec::new();
+
+            for overlay_host in overlay_hosts.lock().await.iter() {
+                for (window_id, overlay) in overlay_host.overlays().lock().await.iter_mut() {
+                    windows.push(*window_id);
+
+                    let advanced = options.independent
+                        && slideshow
+                            .state
+                            .lock()
+                            .unwrap()
+                            .advance_window(*window_id, now);
+                    if changed || moved || advanced {
+                        overlay.show_slide();
+                    }
+                    advanced_any |= advanced;
+                }
+            }
+
+            // Forget the windows which are closed.
+            slideshow
+                .state
+                .lock()
+                .unwrap()
+                .windows
+                .retain(|window_id, _| windows.contains(window_id));
+
+            if moved || advanced_any {
+                slideshow.save();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    fn settings(options: SlideshowOptions) -> (PathBuf, SlideshowOptions) {
+        (PathBuf::from("slides"), options)
+    }
+
+    fn state(names: &[&str], options: SlideshowOptions) -> State<u32> {
+        let mut state = State::default();
+        state.reset(settings(options), paths(names), None);
+        state
+    }
+
+    fn shuffled() -> SlideshowOptions {
+        SlideshowOptions {
+            shuffle: true,
+            ..Default::default()
+        }
+    }
+
+    fn shown(state: &State<u32>) -> &Path {
+        &state.items[state.shared.position]
+    }
+
+    #[test]
+    fn merge_keeps_shown_item_when_others_are_removed() {
+        let mut state = state(&["a", "b", "c", "d"], SlideshowOptions::default());
+        state.shared.position = 2;
+
+        let reload = state.merge(paths(&["a", "c", "d"]));
+
+        assert_eq!(
+            reload,
+            Reload {
+                items_changed: true,
+                shown_changed: false
+            }
+        );
+        assert_eq!(shown(&state), Path::new("c"));
+    }
+
+    #[test]
+    fn merge_moves_to_next_item_when_shown_one_is_removed() {
+        let mut state = state(&["a", "b", "c", "d"], SlideshowOptions::default());
+        state.shared.position = 1;
+
+        let reload = state.merge(paths(&["a", "c", "d"]));
+
+        assert!(reload.items_changed && reload.shown_changed);
+        assert_eq!(shown(&state), Path::new("c"));
+    }
+
+    #[test]
+    fn merge_moves_to_first_item_when_last_one_is_removed() {
+        let mut state = state(&["a", "b", "c"], SlideshowOptions::default());
+        state.shared.position = 2;
+
+        state.merge(paths(&["a", "b"]));
+
+        assert_eq!(shown(&state), Path::new("a"));
+    }
+
+    #[test]
+    fn merge_keeps_shown_item_when_items_are_added() {
+        let mut state = state(&["b", "d"], SlideshowOptions::default());
+        state.shared.position = 1;
+
+        let reload = state.merge(paths(&["a", "b", "c", "d"]));
+
+        assert_eq!(
+            reload,
+            Reload {
+                items_changed: true,
+                shown_changed: false
+            }
+        );
+        assert_eq!(shown(&state), Path::new("d"));
+        assert_eq!(state.items, paths(&["a", "b", "c", "d"]));
+    }
+
+    #[test]
+    fn merge_remaps_independent_windows() {
+        let mut state = state(&["a", "b", "c", "d"], SlideshowOptions::default());
+        let now = Instant::now();
+        state.window(1, now).position = 3;
+        state.window(2, now).position = 1;
+
+        state.merge(paths(&["a", "c", "d"]));
+
+        assert_eq!(state.items[state.windows[&1].position], Path::new("d"));
+        assert_eq!(state.items[state.windows[&2].position], Path::new("c"));
+    }
+
+    #[test]
+    fn merge_keeps_shuffled_order() {
+        let names = ["a", "b", "c", "d", "e", "f"];
+        let mut state = state(&names, shuffled());
+        let order = state.items.clone();
+
+        // The listed order doesn't matter while the items are the same.
+        assert_eq!(state.merge(paths(&names)), Reload::default());
+        assert_eq!(state.items, order);
+
+        // New items are added to the end, and removed ones are dropped from the order.
+        state.merge(paths(&["a", "c", "d", "e", "f", "g"]));
+        let expected: Vec<_> = order
+            .iter()
+            .filter(|item| *item != Path::new("b"))
+            .cloned()
+            .chain([PathBuf::from("g")])
+            .collect();
+        assert_eq!(state.items, expected);
+    }
+
+    #[test]
+    fn advance_wraps_around() {
+        let options = SlideshowOptions {
+            interval: 10,
+            ..Default::default()
+        };
+        let mut state = state(&["a", "b", "c"], options);
+        let mut now = state.shared.next;
+
+        // Not before the interval passes.
+        assert!(!state.advance(now - Duration::from_secs(1)));
+
+        let mut positions = Vec::new();
+        for _ in 0..4 {
+            assert!(state.advance(now));
+            positions.push(state.shared.position);
+            now += Duration::from_secs(10);
+        }
+
+        assert_eq!(positions, [1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn advance_does_not_repeat_item_over_shuffled_rounds() {
+        let mut state = state(&["a", "b", "c"], shuffled());
+        let mut now = state.shared.next;
+        let mut previous = shown(&state).to_path_buf();
+
+        for _ in 0..30 {
+            assert!(state.advance(now));
+            assert_ne!(shown(&state), previous);
+
+            previous = shown(&state).to_path_buf();
+            now += state.interval();
+        }
+    }
+
+    #[test]
+    fn advance_window_wraps_around_and_moves_shared_position() {
+        let options = SlideshowOptions {
+            interval: 10,
+            independent: true,
+            ..Default::default()
+        };
+        let mut state = state(&["a", "b", "c"], options);
+        let start = Instant::now();
+
+        // New windows start next to the ones already rotating.
+        assert_eq!(state.window(1, start).position, 0);
+        assert_eq!(state.window(2, start).position, 1);
+
+        let mut now = start;
+        assert!(!state.advance_window(2, now));
+
+        let mut positions = Vec::new();
+        for _ in 0..3 {
+            now += Duration::from_secs(10);
+            assert!(state.advance_window(2, now));
+            positions.push(state.windows[&2].position);
+        }
+
+        assert_eq!(positions, [2, 0, 1]);
+        assert_eq!(state.shared.position, 1);
+        assert_eq!(state.windows[&1].position, 0);
+    }
+
+    #[test]
+    fn reset_resumes_from_saved_order_and_position() {
+        let saved = Saved {
+            location: PathBuf::from("slides"),
+            items: paths(&["c", "a", "b"]),
+            position: 1,
+        };
+        let mut state = State::<u32>::default();
+
+        state.reset(
+            settings(shuffled()),
+            paths(&["a", "b", "c", "d"]),
+            Some(saved),
+        );
+
+        assert_eq!(state.items, paths(&["c", "a", "b", "d"]));
+        assert_eq!(shown(&state), Path::new("a"));
+    }
+
+    #[test]
+    fn reset_ignores_saved_state_of_another_location() {
+        let saved = Saved {
+            location: PathBuf::from("other"),
+            items: paths(&["c", "a", "b"]),
+            position: 2,
+        };
+        let mut state = State::<u32>::default();
+
+        state.reset(
+            settings(SlideshowOptions::default()),
+            paths(&["a", "b", "c"]),
+            Some(saved),
+        );
+
+        assert_eq!(state.items, paths(&["a", "b", "c"]));
+        assert_eq!(state.shared.position, 0);
+    }
+}
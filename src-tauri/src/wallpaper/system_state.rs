@@ -0,0 +1,82 @@
+use std::sync::atomic;
+
+use tauri::{Manager, async_runtime};
+
+use crate::{
+    os::{
+        power::PowerSource,
+        system_state::{self, SystemEvent},
+    },
+    wallpaper::WallpaperHostsState,
+};
+
+static PAUSED: atomic::AtomicBool = atomic::AtomicBool::new(false);
+static ON_BATTERY: atomic::AtomicBool = atomic::AtomicBool::new(false);
+
+/// Whether rendering of wallpapers is paused due to sleep or screen lock.
+pub fn is_paused() -> bool {
+    PAUSED.load(atomic::Ordering::Relaxed)
+}
+
+/// Whether the computer is running on battery.
+pub fn is_on_battery() -> bool {
+    ON_BATTERY.load(atomic::Ordering::Relaxed)
+}
+
+/// Starts reflecting system states to wallpapers.
+///
+/// Overlays are paused while the system is asleep or locked,
+/// and their geometry is resynchronized on resume or display changes.
+pub fn setup_system_state_sync(app: &tauri::App) {
+    log::info!("Starting system state observation...");
+
+    let (tx, mut rx) = async_runtime::channel(10);
+    if let Err(e) = system_state::start(app.handle().clone(), tx) {
+        log::warn!("Failed to start system state observation. Detail: {e}");
+    }
+
+    let app = app.handle().clone();
+    async_runtime::spawn(async move {
+        let mut asleep = false;
+        let mut locked = false;
+
+        while let Some(event) = rx.recv().await {
+            log::debug!("Received system event: {event:?}");
+
+            if let SystemEvent::PowerSourceChanged(source) = event {
+                let on_battery = source == PowerSource::Battery;
+                ON_BATTERY.store(on_battery, atomic::Ordering::Relaxed);
+
+                let hosts = app.state::<WallpaperHostsState>();
+                for host in hosts.lock().await.values() {
+                    host.set_on_battery(on_battery).await;
+                }
+
+                continue;
+            }
+
+            match event {
+                SystemEvent::Suspended => asleep = true,
+                SystemEvent::Resumed => asleep = false,
+                SystemEvent::Locked => locked = true,
+                SystemEvent::Unlocked => locked = false,
+                SystemEvent::DisplaysChanged | SystemEvent::PowerSourceChanged(_) => {}
+            }
+
+            let paused = asleep || locked;
+            let was_paused = PAUSED.swap(paused, atomic::Ordering::Relaxed);
+            let resync = !paused && event != SystemEvent::Suspended && event != SystemEvent::Locked;
+
+            let hosts = app.state::<WallpaperHostsState>();
+            for host in hosts.lock().await.values() {
+                if paused != was_paused {
+                    host.set_paused(paused).await;
+                }
+
+                if resync {
+                    host.resync().await;
+                }
+            }
+        }
+    });
+}
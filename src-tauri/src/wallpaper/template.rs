@@ -0,0 +1,321 @@
+//! Templated sources, whose locations are filled in for each target window.
+//!
+//! Locations can contain `{title}` for the title of the target window, `{app}` for the name of
+//! its application, and `{1}`, `{2}`, ... or `{<name>}` for the capture groups of the window
+//! name filters using regular expressions. Placeholders without values are left as they are.
+//!
+//! Only pictures, videos, remote web pages and YouTube videos are templated. Slideshows and
+//! remote pictures are shared by the overlays of a wallpaper, and local web pages are served
+//! from the directory of the configured page.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use regex::{Captures, Regex};
+
+use crate::config::WallpaperSource;
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{(\w+)\}").expect("Failed to compile placeholder pattern"));
+
+pub type TemplateValues = HashMap<String, String>;
+
+/// Whether the location of the source has placeholders.
+pub fn is_templated(source: &WallpaperSource) -> bool {
+    match source {
+        WallpaperSource::Picture { location, .. } | WallpaperSource::Video { location, .. } => {
+            PLACEHOLDER.is_match(&location.to_string_lossy())
+        }
+        WallpaperSource::RemoteWebPage { location } | WallpaperSource::YouTube { location, .. } => {
+            PLACEHOLDER.is_match(location)
+        }
+        _ => false,
+    }
+}
+
+/// Collects the values of the placeholders for the target window.
+/// `regexes` are the compiled window name filters using regular expressions.
+pub fn values<'a>(
+    title: Option<&str>,
+    application_name: Option<&str>,
+    regexes: impl IntoIterator<Item = &'a Regex>,
+) -> TemplateValues {
+    let mut values = TemplateValues::new();
+
+    if let Some(application_name) = application_name {
+        values.insert("app".to_owned(), application_name.to_owned());
+    }
+
+    let Some(title) = title else {
+        return values;
+    };
+    values.insert("title".to_owned(), title.to_owned());
+
+    for regex in regexes {
+        let Some(captures) = regex.captures(title) else {
+            continue;
+        };
+
+        for (index, group_name) in regex.capture_names().enumerate().skip(1) {
+            let Some(group) = captures.get(index) else {
+                continue;
+            };
+
+            values.insert(index.to_string(), group.as_str().to_owned());
+            if let Some(group_name) = group_name {
+                values.insert(group_name.to_owned(), group.as_str().to_owned());
+            }
+        }
+    }
+
+    values
+}
+
+/// Fills the placeholders in the location of the source.
+/// If the filled file does not exist and the fallback picture is set, the fallback is returned.
+pub fn resolve(
+    source: &WallpaperSource,
+    values: &TemplateValues,
+    fallback: Option<&Path>,
+) -> WallpaperSource {
+    if !is_templated(source) {
+        return source.clone();
+    }
+
+    let resolved = match source {
+        WallpaperSource::Picture { location, layout } => WallpaperSource::Picture {
+            location: fill_path(location, values),
+            layout: *layout,
+        },
+        WallpaperSource::Video {
+            location,
+            layout,
+            options,
+        } => WallpaperSource::Video {
+            location: fill_path(location, values),
+            layout: *layout,
+            options: *options,
+        },
+        WallpaperSource::RemoteWebPage { location } => WallpaperSource::RemoteWebPage {
+            location: fill(location, values, url_component),
+        },
+        WallpaperSource::YouTube { location, options } => WallpaperSource::YouTube {
+            location: fill(location, values, url_component),
+            options: options.clone(),
+        },
+        _ => return source.clone(),
+    };
+
+    match (&resolved, fallback) {
+        (
+            WallpaperSource::Picture { location, layout }
+            | WallpaperSource::Video {
+                location, layout, ..
+            },
+            Some(fallback),
+        ) if !location.exists() => {
+            log::debug!("Show the fallback picture instead of missing {location:?}");
+
+            WallpaperSource::Picture {
+                location: fallback.to_path_buf(),
+                layout: *layout,
+            }
+        }
+        _ => resolved,
+    }
+}
+
+fn fill(template: &str, values: &TemplateValues, escape: fn(&str) -> String) -> String {
+    PLACEHOLDER
+        .replace_all(template, |captures: &Captures| {
+            match values.get(&captures[1]) {
+                Some(value) => escape(value),
+                None => captures[0].to_owned(),
+            }
+        })
+        .into_owned()
+}
+
+fn fill_path(template: &Path, values: &TemplateValues) -> PathBuf {
+    PathBuf::from(fill(&template.to_string_lossy(), values, file_name))
+}
+
+/// Makes the value a part of a file name, so that window titles cannot point to files
+/// outside the directories written in the template.
+fn file_name(value: &str) -> String {
+    let name: String = value
+        .chars()
+        .map(|c| {
+            if std::path::is_separator(c) || c == ':' || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    match name.as_str() {
+        "." | ".." => "_".to_owned(),
+        _ => name,
+    }
+}
+
+fn url_component(value: &str) -> String {
+    utf8_percent_encode(value, NON_ALPHANUMERIC).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Layout, VideoOptions};
+
+    fn picture(location: impl Into<PathBuf>) -> WallpaperSource {
+        WallpaperSource::Picture {
+            location: location.into(),
+            layout: Layout::default(),
+        }
+    }
+
+    fn title_values(title: &str) -> TemplateValues {
+        values(Some(title), None, &[] as &[Regex])
+    }
+
+    #[test]
+    fn file_names_replace_separators() {
+        assert_eq!(file_name("a/b"), "a_b");
+        assert_eq!(file_name("C:/Users"), "C__Users");
+        assert_eq!(file_name("line\nbreak"), "line_break");
+        assert_eq!(file_name("Report - Draft"), "Report - Draft");
+    }
+
+    #[test]
+    fn file_names_replace_parent_and_current_directories() {
+        assert_eq!(file_name(".."), "_");
+        assert_eq!(file_name("."), "_");
+        // Only the whole name means a directory.
+        assert_eq!(file_name("a..b"), "a..b");
+    }
+
+    #[test]
+    fn fill_path_stays_in_template_directory() {
+        let values = title_values("../../secret");
+
+        let filled = fill_path(Path::new("pictures/{title}.png"), &values);
+
+        assert_eq!(filled, PathBuf::from("pictures/.._.._secret.png"));
+        assert_eq!(filled.parent(), Some(Path::new("pictures")));
+    }
+
+    #[test]
+    fn values_have_title_app_and_groups() {
+        let regexes = [
+            Regex::new(r"^(?<project>\S+) - (\w+)$").unwrap(),
+            // Not matching filters give no groups.
+            Regex::new(r"^Untitled (\d+)$").unwrap(),
+        ];
+
+        let values = values(Some("overkami - Code"), Some("Editor"), &regexes);
+
+        assert_eq!(values["title"], "overkami - Code");
+        assert_eq!(values["app"], "Editor");
+        assert_eq!(values["project"], "overkami");
+        assert_eq!(values["1"], "overkami");
+        assert_eq!(values["2"], "Code");
+        assert_eq!(values.len(), 5);
+    }
+
+    #[test]
+    fn values_skip_unmatched_optional_groups() {
+        let regexes = [Regex::new(r"^(\w+)(?: \[(\w+)\])?$").unwrap()];
+
+        let values = values(Some("Notes"), None, &regexes);
+
+        assert_eq!(values["1"], "Notes");
+        assert!(!values.contains_key("2"));
+    }
+
+    #[test]
+    fn values_without_title_have_only_app() {
+        let regexes = [Regex::new(r"(\w+)").unwrap()];
+
+        let values = values(None, Some("Editor"), &regexes);
+
+        assert_eq!(
+            values,
+            TemplateValues::from([("app".to_owned(), "Editor".to_owned())])
+        );
+    }
+
+    #[test]
+    fn resolve_fills_path_and_url() {
+        let regexes = [Regex::new(r"^(?<project>\S+) - (\w+)$").unwrap()];
+        let values = values(Some("my:project - Code"), None, &regexes);
+
+        assert_eq!(
+            resolve(
+                &picture("pictures/{project}/{2}-{missing}.png"),
+                &values,
+                None
+            ),
+            picture("pictures/my_project/Code-{missing}.png")
+        );
+        assert_eq!(
+            resolve(
+                &WallpaperSource::RemoteWebPage {
+                    location: "https://example.com/?q={title}".to_owned()
+                },
+                &values,
+                None
+            ),
+            WallpaperSource::RemoteWebPage {
+                location: "https://example.com/?q=my%3Aproject%20%2D%20Code".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_shows_fallback_for_missing_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("Code.png"), "").unwrap();
+        let fallback = dir.join("fallback.png");
+        let template = picture(dir.join("{title}.png"));
+
+        // The filled file exists.
+        assert_eq!(
+            resolve(&template, &title_values("Code"), Some(&fallback)),
+            picture(dir.join("Code.png"))
+        );
+        // The filled file is missing.
+        assert_eq!(
+            resolve(&template, &title_values("Mail"), Some(&fallback)),
+            picture(&fallback)
+        );
+        // Videos fall back to the picture too.
+        let layout = Layout {
+            scale: 2.,
+            ..Default::default()
+        };
+        let video = WallpaperSource::Video {
+            location: dir.join("{title}.mp4"),
+            layout,
+            options: VideoOptions::default(),
+        };
+        assert_eq!(
+            resolve(&video, &title_values("Code"), Some(&fallback)),
+            WallpaperSource::Picture {
+                location: fallback.clone(),
+                layout,
+            }
+        );
+        // Without the fallback, the missing file is shown as it is.
+        assert_eq!(
+            resolve(&template, &title_values("Mail"), None),
+            picture(dir.join("Mail.png"))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use std::{sync::LazyLock, time::Duration};
+
+use anyhow::Context as _;
+use tauri::{
+    AppHandle, Emitter, WebviewWindow,
+    async_runtime::{self, Receiver, Sender},
+};
+use uuid::Uuid;
+use window_getter::WindowId;
+
+use crate::{
+    config::{Transition, TransitionKind, WallpaperSource},
+    os::WebviewWindowPlatformExt,
+};
+
+/// The preloaded source is given up if it is not ready within this,
+/// and the current overlay window is kept.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The interval of updating the opacity of overlay windows while transitions.
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// The result of preloading a new source in an incoming overlay window.
+#[derive(Debug)]
+pub struct TransitionEvent {
+    pub target_window_id: WindowId,
+    /// The label of the incoming overlay window.
+    pub label: String,
+    pub result: Result<(), String>,
+}
+
+pub type TransitionTx = Sender<TransitionEvent>;
+pub type TransitionRx = Receiver<TransitionEvent>;
+
+pub fn channel() -> (TransitionTx, TransitionRx) {
+    async_runtime::channel(100)
+}
+
+/// Checks whether the source can be loaded, as far as it can be known before loading.
+///
+/// Only local files are checked here. Remote pages are checked by [`spawn_remote_check`].
+pub fn check_source(source: &WallpaperSource) -> anyhow::Result<()> {
+    match source {
+        WallpaperSource::LocalWebPage { location }
+        | WallpaperSource::Picture { location, .. }
+        | WallpaperSource::Video { location, .. }
+        | WallpaperSource::Slideshow { location, .. } => {
+            std::fs::metadata(location)
+                .with_context(|| format!("Failed to open `{}`", location.display()))?;
+        }
+        WallpaperSource::RemoteWebPage { .. }
+        | WallpaperSource::RemotePicture { .. }
+        | WallpaperSource::YouTube { .. }
+        | WallpaperSource::Color { .. }
+        | WallpaperSource::Gradient { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// Whether the built-in page of the source reports the load of its media by itself.
+/// The pages of these sources finish loading even if the media fails to load.
+pub fn reports_media_load(source: &WallpaperSource) -> bool {
+    matches!(
+        source,
+        WallpaperSource::Picture { .. } | WallpaperSource::Video { .. }
+    )
+}
+
+/// The number of signals needed before the incoming overlay window of the source is ready.
+///
+/// Every source needs its page or media to be loaded, and remote pages also need
+/// the result of [`spawn_remote_check`].
+pub fn required_signals(source: &WallpaperSource) -> u8 {
+    match source {
+        WallpaperSource::RemoteWebPage { .. } => 2,
+        _ => 1,
+    }
+}
+
+/// Requests the remote page, and sends a failure if it is unreachable or returns an error status.
+/// Webviews finish loading even for error pages, so the status must be checked separately.
+pub fn spawn_remote_check(
+    tx: TransitionTx,
+    target_window_id: WindowId,
+    label: String,
+    location: String,
+) {
+    static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+        reqwest::Client::builder()
+            .timeout(LOAD_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client")
+    });
+
+    async_runtime::spawn(async move {
+        let result = match CLIENT.get(&location).send().await {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(format!("`{location}` returned HTTP {}.", response.status())),
+            Err(e) => Err(format!("Failed to reach `{location}`. Detail: {e}")),
+        };
+
+        let _ = tx
+            .send(TransitionEvent {
+                target_window_id,
+                label,
+                result,
+            })
+            .await;
+    });
+}
+
+/// Sends a failure of the incoming overlay window if it is not ready within [`LOAD_TIMEOUT`].
+pub fn spawn_timeout(tx: TransitionTx, target_window_id: WindowId, label: String) {
+    async_runtime::spawn(async move {
+        tokio::time::sleep(LOAD_TIMEOUT).await;
+
+        // It is ignored if the window is already ready.
+        let _ = tx
+            .send(TransitionEvent {
+                target_window_id,
+                label,
+                result: Err(format!(
+                    "The source did not finish loading in {} seconds.",
+                    LOAD_TIMEOUT.as_secs()
+                )),
+            })
+            .await;
+    });
+}
+
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceError {
+    wallpaper_id: Uuid,
+    detail: String,
+}
+
+/// Tells the main window that the new source of the wallpaper failed to load.
+pub fn report_error(app: &AppHandle, wallpaper_id: Uuid, detail: String) {
+    log::error!("Failed to load the new source of wallpaper `{wallpaper_id}`. Detail: {detail}");
+
+    if let Err(e) = app.emit(
+        "wallpaper-source-error",
+        SourceError {
+            wallpaper_id,
+            detail,
+        },
+    ) {
+        log::warn!("Failed to report the source error. Detail: {e}");
+    }
+}
+
+/// Fades from the outgoing overlay window to the incoming one, and then destroys the outgoing one.
+///
+/// The windows may be closed during the transition, so errors are ignored.
+pub fn spawn_animation(
+    outgoing: WebviewWindow,
+    incoming: WebviewWindow,
+    transition: Transition,
+    opacity: f64,
+) {
+    async_runtime::spawn(async move {
+        let duration = Duration::from_millis(transition.duration);
+
+        match transition.kind {
+            TransitionKind::Cut => {}
+            TransitionKind::Fade => {
+                animate(duration / 2, |progress| {
+                    set_opacity(&outgoing, opacity * (1. - progress));
+                })
+                .await;
+                animate(duration / 2, |progress| {
+                    set_opacity(&incoming, opacity * progress);
+                })
+                .await;
+            }
+            TransitionKind::Crossfade => {
+                animate(duration, |progress| {
+                    set_opacity(&incoming, opacity * progress);
+                    set_opacity(&outgoing, opacity * (1. - progress));
+                })
+                .await;
+            }
+        }
+
+        set_opacity(&incoming, opacity);
+        let _ = outgoing.destroy();
+    });
+}
+
+fn set_opacity(window: &WebviewWindow, opacity: f64) {
+    // Skip closed windows, whose native handles are already gone.
+    if window.is_visible().is_ok() {
+        let _ = window.set_opacity(opacity);
+    }
+}
+
+/// Calls `step` with the progress from 0 to 1 every frame over `duration`.
+async fn animate(duration: Duration, mut step: impl FnMut(f64)) {
+    let mut interval = tokio::time::interval(FRAME_INTERVAL);
+    let start = tokio::time::Instant::now();
+
+    loop {
+        interval.tick().await;
+
+        let progress = start.elapsed().as_secs_f64() / duration.as_secs_f64().max(f64::EPSILON);
+        step(progress.min(1.));
+
+        if progress >= 1. {
+            break;
+        }
+    }
+}
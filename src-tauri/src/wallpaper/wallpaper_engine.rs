@@ -0,0 +1,408 @@
+//! Import of Wallpaper Engine projects, which are directories with `project.json`.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    config::{Layout, VideoOptions, WallpaperSource},
+    wallpaper::manifest::{Property, PropertyKind, SelectOption, WallpaperManifest},
+};
+
+pub const PROJECT_FILE_NAME: &str = "project.json";
+
+/// The wallpaper made from a project. The rest of the wallpaper is configured by users.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedProject {
+    pub name: String,
+    pub source: WallpaperSource,
+    pub preview: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// The type of the project which overkami can't show, such as `scene` and `application`.
+    Unsupported(String),
+    Invalid(anyhow::Error),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(kind) => write!(f, "Unsupported project type `{kind}`"),
+            Self::Invalid(e) => write!(f, "{e:#}"),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ImportError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Invalid(e)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Project {
+    #[serde(default)]
+    title: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    file: Option<PathBuf>,
+    #[serde(default)]
+    preview: Option<PathBuf>,
+    #[serde(default)]
+    general: General,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct General {
+    #[serde(default)]
+    properties: HashMap<String, ProjectProperty>,
+}
+
+/// A user property of a project. Only the fields used by overkami are read.
+#[derive(Debug, Deserialize)]
+struct ProjectProperty {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    order: f64,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    value: Value,
+    #[serde(default)]
+    min: Option<f64>,
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    step: Option<f64>,
+    #[serde(default)]
+    fraction: bool,
+    #[serde(default)]
+    precision: Option<i32>,
+    #[serde(default)]
+    options: Vec<ProjectOption>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectOption {
+    label: String,
+    value: Value,
+}
+
+fn read_project(dir: &Path) -> anyhow::Result<Option<Project>> {
+    let path = dir.join(PROJECT_FILE_NAME);
+
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).context("Failed to read Wallpaper Engine project"),
+    };
+
+    let project = serde_json::from_slice(&data).with_context(|| {
+        format!(
+            "Failed to parse Wallpaper Engine project `{}`",
+            path.display()
+        )
+    })?;
+
+    Ok(Some(project))
+}
+
+/// Makes a wallpaper from the project in `dir`.
+pub fn import_project(dir: &Path) -> Result<ImportedProject, ImportError> {
+    let project = read_project(dir)?
+        .with_context(|| format!("`{PROJECT_FILE_NAME}` is not found in `{}`", dir.display()))?;
+
+    let file = || {
+        project
+            .file
+            .as_ref()
+            .map(|file| dir.join(file))
+            .context("The project has no file")
+    };
+    let source = match project.kind.to_ascii_lowercase().as_str() {
+        "web" => WallpaperSource::LocalWebPage { location: file()? },
+        "video" => WallpaperSource::Video {
+            location: file()?,
+            layout: Layout::default(),
+            options: VideoOptions::default(),
+        },
+        _ => return Err(ImportError::Unsupported(project.kind)),
+    };
+
+    let name = if project.title.is_empty() {
+        dir.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    } else {
+        project.title
+    };
+
+    Ok(ImportedProject {
+        name,
+        source,
+        preview: project.preview.map(|preview| dir.join(preview)),
+    })
+}
+
+/// Reads the user properties of the project in `dir` as a manifest.
+/// Returns `None` if `dir` is not a project.
+pub fn read_manifest(dir: &Path) -> anyhow::Result<Option<WallpaperManifest>> {
+    let Some(project) = read_project(dir)? else {
+        return Ok(None);
+    };
+
+    let mut properties: Vec<_> = project.general.properties.into_iter().collect();
+    properties.sort_by(|(a_key, a), (b_key, b)| a.order.total_cmp(&b.order).then(a_key.cmp(b_key)));
+
+    let properties = properties
+        .into_iter()
+        .filter_map(|(key, property)| {
+            // Texts starting with `ui_` are keys of the localization of Wallpaper Engine.
+            let label = (!property.text.is_empty() && !property.text.starts_with("ui_"))
+                .then(|| property.text.clone());
+            let kind = property.into_kind()?;
+
+            Some(Property { key, label, kind })
+        })
+        .collect();
+
+    Ok(Some(WallpaperManifest { properties }))
+}
+
+impl ProjectProperty {
+    /// Converts the property to the one of the manifest. Returns `None` if it is not supported.
+    fn into_kind(self) -> Option<PropertyKind> {
+        let kind = match self.kind.as_str() {
+            "color" => PropertyKind::Color {
+                default: self
+                    .value
+                    .as_str()
+                    .and_then(color_to_hex)
+                    .unwrap_or_else(|| "#000000".to_owned()),
+            },
+            "slider" => PropertyKind::Number {
+                default: as_f64(&self.value).unwrap_or_default(),
+                min: self.min,
+                max: self.max,
+                step: Some(self.step.unwrap_or(if self.fraction {
+                    10f64.powi(-self.precision.unwrap_or(2))
+                } else {
+                    1.
+                })),
+            },
+            "bool" => PropertyKind::Bool {
+                default: self.value.as_bool().unwrap_or_default(),
+            },
+            "textinput" => PropertyKind::Text {
+                default: as_string(&self.value),
+            },
+            "combo" => PropertyKind::Select {
+                default: as_string(&self.value),
+                options: self
+                    .options
+                    .into_iter()
+                    .map(|option| SelectOption {
+                        label: option.label,
+                        value: as_string(&option.value),
+                    })
+                    .collect(),
+            },
+            "file" => PropertyKind::File {
+                default: None,
+                extensions: Vec::new(),
+            },
+            kind => {
+                log::debug!("Skip Wallpaper Engine property of unsupported type `{kind}`");
+                return None;
+            }
+        };
+
+        Some(kind)
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+}
+
+fn as_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Converts a color like `0.5 0.25 1` to `#8040ff`.
+fn color_to_hex(color: &str) -> Option<String> {
+    let channels: Vec<f64> = color
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let [r, g, b] = channels[..] else {
+        return None;
+    };
+
+    // Old projects use `0` to `255` instead of `0` to `1`.
+    let scale = if [r, g, b].iter().any(|channel| *channel > 1.) {
+        1.
+    } else {
+        255.
+    };
+    let [r, g, b] = [r, g, b].map(|channel| (channel * scale).round().clamp(0., 255.) as u8);
+
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+/// Returns the script which passes the properties to `window.wallpaperPropertyListener`
+/// of the page, if the page is a part of a project.
+///
+/// Wallpaper Engine passes colors as `r g b` in `0` to `1`, so they are converted back.
+pub fn init_script(page: &Path) -> Option<String> {
+    let manifest = match read_manifest(page.parent()?) {
+        Ok(manifest) => manifest?,
+        Err(e) => {
+            log::warn!("{e:#}");
+            return None;
+        }
+    };
+
+    let colors: Vec<_> = manifest
+        .properties
+        .iter()
+        .filter(|property| matches!(property.kind, PropertyKind::Color { .. }))
+        .map(|property| property.key.as_str())
+        .collect();
+    let colors = serde_json::to_string(&colors).expect("Failed to serialize color keys");
+
+    Some(format!(
+        r#"
+(() => {{
+  const colors = new Set({colors});
+  const toColor = (hex) =>
+    [1, 3, 5]
+      .map((i) => (parseInt(hex.slice(i, i + 2), 16) / 255).toFixed(4))
+      .join(" ");
+
+  window.addEventListener("overkami:properties", (event) => {{
+    const listener = window.wallpaperPropertyListener;
+    if (typeof listener?.applyUserProperties !== "function") return;
+
+    const properties = {{}};
+    for (const [key, value] of Object.entries(event.detail)) {{
+      properties[key] = {{
+        value: colors.has(key) && typeof value === "string" ? toColor(value) : value,
+      }};
+    }}
+    listener.applyUserProperties(properties);
+  }});
+}})();
+"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/wallpaper_engine")
+            .join(name)
+    }
+
+    #[test]
+    fn imports_web_project() {
+        let dir = fixture("web");
+        let project = import_project(&dir).unwrap();
+
+        assert_eq!(project.name, "Web Wallpaper");
+        assert_eq!(project.preview, Some(dir.join("preview.jpg")));
+        assert!(matches!(
+            project.source,
+            WallpaperSource::LocalWebPage { location } if location == dir.join("index.html")
+        ));
+    }
+
+    #[test]
+    fn imports_video_project() {
+        let dir = fixture("video");
+        let project = import_project(&dir).unwrap();
+
+        assert_eq!(project.name, "Video Wallpaper");
+        assert!(matches!(
+            project.source,
+            WallpaperSource::Video { location, .. } if location == dir.join("video.mp4")
+        ));
+    }
+
+    #[test]
+    fn rejects_scene_project() {
+        assert!(matches!(
+            import_project(&fixture("scene")),
+            Err(ImportError::Unsupported(kind)) if kind == "scene"
+        ));
+    }
+
+    #[test]
+    fn rejects_application_project() {
+        assert!(matches!(
+            import_project(&fixture("application")),
+            Err(ImportError::Unsupported(kind)) if kind == "application"
+        ));
+    }
+
+    #[test]
+    fn reads_properties_of_web_project() {
+        let manifest = read_manifest(&fixture("web")).unwrap().unwrap();
+        let keys: Vec<_> = manifest
+            .properties
+            .iter()
+            .map(|property| property.key.as_str())
+            .collect();
+
+        // `label` is only a text in Wallpaper Engine, so it is skipped.
+        assert_eq!(
+            keys,
+            ["schemecolor", "speed", "showclock", "message", "mode"]
+        );
+        assert!(matches!(
+            &manifest.properties[0].kind,
+            PropertyKind::Color { default } if default == "#ff8000"
+        ));
+        assert!(matches!(
+            manifest.properties[1].kind,
+            PropertyKind::Number {
+                default: 5.,
+                min: Some(0.),
+                max: Some(10.),
+                step: Some(0.1)
+            }
+        ));
+        assert!(matches!(
+            &manifest.properties[4].kind,
+            PropertyKind::Select { default, options } if default == "b" && options.len() == 2
+        ));
+    }
+
+    #[test]
+    fn converts_colors() {
+        assert_eq!(color_to_hex("1 0.5 0").as_deref(), Some("#ff8000"));
+        assert_eq!(color_to_hex("255 128 0").as_deref(), Some("#ff8000"));
+        assert_eq!(color_to_hex("1 0.5"), None);
+    }
+}
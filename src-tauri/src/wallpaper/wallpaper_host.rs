@@ -4,21 +4,41 @@ use tauri::{AppHandle, async_runtime::Mutex};
 use uuid::Uuid;
 
 use crate::{
-    commands::sync::ApplyWallpaper,
-    config::Wallpaper,
-    os::application_observer::{listen_application, unlisten_application},
-    wallpaper::overlay_host::OverlayHost,
+    commands::sync::{ApplyWallpaper, update_wallpaper_config},
+    config::{BatteryBehavior, Wallpaper},
+    os::{
+        InputRegion,
+        application_observer::{listen_application, unlisten_application},
+    },
+    wallpaper::{
+        overlay_host::OverlayHost,
+        playback_clock::{PlaybackControl, SharedPlaybackClock},
+        power_saving::{PowerSaving, effective_source},
+        remote_picture,
+        slideshow::{self, SharedSlideshow, Slideshow},
+        system_state,
+    },
 };
 
 pub type OverlayHosts = Arc<Mutex<Vec<OverlayHost>>>;
 pub type SharedWallpaperConfig = Arc<Mutex<Wallpaper>>;
 
+/// The number of overlays whose webviews are alive or suspended.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OverlayCounts {
+    pub live: usize,
+    pub suspended: usize,
+}
+
 /// The host of wallpaper for the application.
 /// It tracks app launches and if the wallpaper application is need, apply it to the app.
 pub struct WallpaperHost {
     id: Uuid,
     config: SharedWallpaperConfig,
     overlay_hosts: OverlayHosts,
+    /// The playback clock that all overlays of the wallpaper follow.
+    clock: SharedPlaybackClock,
+    slideshow: SharedSlideshow,
 }
 
 impl WallpaperHost {
@@ -27,20 +47,44 @@ impl WallpaperHost {
 
         let overlay_hosts: OverlayHosts = Default::default();
         let config = Arc::new(Mutex::new(config));
+        let clock = SharedPlaybackClock::default();
+
+        // The items of the slideshow are needed before overlays are created.
+        let slideshow = Arc::new(Slideshow::new(&app, id));
+        slideshow.reload(&config.lock().await.source).await;
 
         // Listen for application process changes and set up overlay hosts.
         application_updates::setup_event_listener(
             id,
             Arc::clone(&config),
             Arc::clone(&overlay_hosts),
+            Arc::clone(&clock),
+            Arc::clone(&slideshow),
             app.clone(),
         )
         .await;
 
+        // Suspend overlays which are not needed for a while.
+        overlay_suspension::spawn_sweep_task(Arc::clone(&config), Arc::downgrade(&overlay_hosts));
+
+        slideshow::spawn_rotation_task(
+            Arc::clone(&config),
+            Arc::downgrade(&overlay_hosts),
+            Arc::clone(&clock),
+            Arc::clone(&slideshow),
+        );
+        remote_picture::spawn_refresh_task(
+            app,
+            Arc::clone(&config),
+            Arc::downgrade(&overlay_hosts),
+        );
+
         Self {
             id,
             config,
             overlay_hosts,
+            clock,
+            slideshow,
         }
     }
 
@@ -52,22 +96,138 @@ impl WallpaperHost {
         for overlay in self.overlay_hosts.lock().await.drain(..) {
             overlay.stop().await;
         }
+
+        self.slideshow.clear();
     }
 
     /// Apply new wallpaper settings.
     pub async fn apply_wallpaper(&self, old_wallpaper: Wallpaper, mut payload: ApplyWallpaper) {
+        let power_saving = {
+            let mut config = self.config.lock().await;
+            update_wallpaper_config(&mut config, payload.clone()).await;
+
+            if payload.source.is_some() {
+                self.slideshow.reload(&config.source).await;
+            }
+
+            // While on battery, the source to show may be the fallback one.
+            let on_battery = system_state::is_on_battery();
+            if on_battery && (payload.source.is_some() || payload.on_battery.is_some()) {
+                payload.source = Some(effective_source(&config, on_battery));
+            }
+
+            // New media starts from the beginning.
+            if effective_source(&config, on_battery) != effective_source(&old_wallpaper, on_battery)
+            {
+                self.clock.lock().unwrap().reset();
+            }
+
+            PowerSaving::new(&config.on_battery, on_battery)
+        };
+
         if let Some(new_app_name) = payload.application_name.take() {
             self.change_application(old_wallpaper.application_name, new_app_name)
                 .await;
         }
 
         for overlay_host in self.overlay_hosts.lock().await.iter() {
-            overlay_host
-                .apply_wallpaper(payload.opacity, payload.source.clone())
-                .await;
+            overlay_host.apply_wallpaper(&payload).await;
+
+            if payload.on_battery.is_some() {
+                overlay_host.set_power_saving(power_saving).await;
+            }
         }
     }
 
+    /// Apply the battery behavior of the wallpaper for the new power source.
+    pub async fn set_on_battery(&self, on_battery: bool) {
+        let config = self.config.lock().await;
+
+        // The fallback source is applied in the same way as source changes by users.
+        let payload = ApplyWallpaper {
+            source: matches!(config.on_battery, BatteryBehavior::StaticFallback { .. })
+                .then(|| effective_source(&config, on_battery)),
+            ..Default::default()
+        };
+        let power_saving = PowerSaving::new(&config.on_battery, on_battery);
+
+        // The fallback picture or the source back from it starts from the beginning.
+        if payload.source.is_some() {
+            self.clock.lock().unwrap().reset();
+        }
+
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            overlay_host.apply_wallpaper(&payload).await;
+            overlay_host.set_power_saving(power_saving).await;
+        }
+    }
+
+    /// Pause or resume rendering of the wallpaper.
+    pub async fn set_paused(&self, paused: bool) {
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            overlay_host.set_paused(paused).await;
+        }
+    }
+
+    /// Seek, pause or change the rate of the playback of all overlays at once.
+    pub async fn control_playback(&self, control: PlaybackControl) {
+        let state = {
+            let mut clock = self.clock.lock().unwrap();
+            clock.apply(control);
+            clock.state()
+        };
+
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            overlay_host.send_clock(state).await;
+        }
+    }
+
+    /// Set the input regions declared by the page in the overlay window with `label`.
+    pub async fn set_input_regions(&self, label: &str, regions: Vec<InputRegion>) -> bool {
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            if overlay_host.set_input_regions(label, &regions).await {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Tell the overlay with the incoming window `label` whether the media of its page is loaded.
+    pub async fn report_media_load(&self, label: &str, result: Result<(), String>) -> bool {
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            if overlay_host.report_media_load(label, result.clone()).await {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Synchronize the geometry of overlays with their target windows.
+    pub async fn resync(&self) {
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            overlay_host.resync().await;
+        }
+    }
+
+    /// Count the overlays of the wallpaper.
+    pub async fn overlay_counts(&self) -> OverlayCounts {
+        let mut counts = OverlayCounts::default();
+
+        for overlay_host in self.overlay_hosts.lock().await.iter() {
+            for overlay in overlay_host.overlays().lock().await.values() {
+                if overlay.is_suspended() {
+                    counts.suspended += 1;
+                } else {
+                    counts.live += 1;
+                }
+            }
+        }
+
+        counts
+    }
+
     /// Change the target application of wallpaper.
     async fn change_application(&self, old_app_name: String, new_app_name: String) {
         // When the application is updated, we need to clear the overlay hosts.
@@ -87,12 +247,17 @@ impl WallpaperHost {
 
 /// Handle application rise and fall events to make or stop wallpaper instances.
 mod application_updates {
+    use std::sync::Arc;
+
     use tauri::{AppHandle, async_runtime};
     use uuid::Uuid;
 
     use crate::{
         os::application_observer::{ApplicationEvent, listen_application},
-        wallpaper::{overlay_host::OverlayHost, wallpaper_host::SharedWallpaperConfig},
+        wallpaper::{
+            overlay_host::OverlayHost, playback_clock::SharedPlaybackClock,
+            slideshow::SharedSlideshow, wallpaper_host::SharedWallpaperConfig,
+        },
     };
 
     use super::OverlayHosts;
@@ -101,6 +266,8 @@ mod application_updates {
         wallpaper_id: uuid::Uuid,
         config: SharedWallpaperConfig,
         overlay_hosts: OverlayHosts,
+        clock: SharedPlaybackClock,
+        slideshow: SharedSlideshow,
         app: tauri::AppHandle,
     ) {
         let (tx, mut rx) = async_runtime::channel(100);
@@ -118,7 +285,16 @@ mod application_updates {
                     wallpaper_id = {wallpaper_id}"
                 );
 
-                on_application_event(wallpaper_id, &config, event, &overlay_hosts, &app).await;
+                on_application_event(
+                    wallpaper_id,
+                    &config,
+                    &clock,
+                    &slideshow,
+                    event,
+                    &overlay_hosts,
+                    &app,
+                )
+                .await;
             }
         });
     }
@@ -126,6 +302,8 @@ mod application_updates {
     async fn on_application_event(
         wallpaper_id: Uuid,
         config: &SharedWallpaperConfig,
+        clock: &SharedPlaybackClock,
+        slideshow: &SharedSlideshow,
         event: ApplicationEvent,
         overlay_hosts: &OverlayHosts,
         app: &AppHandle,
@@ -134,9 +312,16 @@ mod application_updates {
             ApplicationEvent::Added(pid) => {
                 let config = config.lock().await;
 
-                let overlay_host = OverlayHost::start(app.clone(), wallpaper_id, pid, &config)
-                    .await
-                    .unwrap();
+                let overlay_host = OverlayHost::start(
+                    app.clone(),
+                    wallpaper_id,
+                    pid,
+                    &config,
+                    Arc::clone(clock),
+                    Arc::clone(slideshow),
+                )
+                .await
+                .unwrap();
 
                 if let Some(overlay_host) = overlay_host {
                     overlay_hosts.lock().await.push(overlay_host);
@@ -156,3 +341,247 @@ mod application_updates {
         }
     }
 }
+
+/// Suspension of overlays to reclaim the memory of their webviews.
+///
+/// Overlays hidden longer than `suspend_after` of the wallpaper are suspended, and if the number
+/// of live overlays exceeds `max_live_overlays` of the wallpaper, the least recently used
+/// hidden ones are suspended. Suspended overlays are resumed only when their target windows are
+/// shown or focused, so overlays of shown target windows are never suspended, even while they
+/// are paused or disabled on battery. The limit may be exceeded while many target windows are
+/// shown.
+mod overlay_suspension {
+    use std::{
+        sync::Weak,
+        time::{Duration, Instant},
+    };
+
+    use tauri::async_runtime::{self, Mutex};
+
+    use crate::wallpaper::{overlay::Overlay, overlay_host::OverlayHost};
+
+    use super::SharedWallpaperConfig;
+
+    const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// What the sweep needs from an overlay.
+    trait Suspendable {
+        fn is_hidden(&self) -> bool;
+        fn hidden_duration(&self) -> Duration;
+        fn last_used(&self) -> Instant;
+        fn suspend(&mut self);
+    }
+
+    impl Suspendable for Overlay {
+        fn is_hidden(&self) -> bool {
+            Overlay::is_hidden(self)
+        }
+
+        fn hidden_duration(&self) -> Duration {
+            Overlay::hidden_duration(self)
+        }
+
+        fn last_used(&self) -> Instant {
+            Overlay::last_used(self)
+        }
+
+        fn suspend(&mut self) {
+            Overlay::suspend(self);
+        }
+    }
+
+    pub fn spawn_sweep_task(
+        config: SharedWallpaperConfig,
+        overlay_hosts: Weak<Mutex<Vec<OverlayHost>>>,
+    ) {
+        async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(SWEEP_INTERVAL).await;
+
+                // The wallpaper host is dropped.
+                let Some(overlay_hosts) = overlay_hosts.upgrade() else {
+                    break;
+                };
+                let (max_live_overlays, suspend_after) = {
+                    let config = config.lock().await;
+                    let suspend_after = (config.suspend_after > 0)
+                        .then(|| Duration::from_secs(config.suspend_after));
+                    (config.max_live_overlays, suspend_after)
+                };
+
+                let overlay_hosts = overlay_hosts.lock().await;
+                let mut overlays = Vec::with_capacity(overlay_hosts.len());
+                for overlay_host in overlay_hosts.iter() {
+                    overlays.push(overlay_host.overlays().lock().await);
+                }
+
+                let live = overlays
+                    .iter_mut()
+                    .flat_map(|overlays| overlays.values_mut())
+                    .filter(|overlay| !overlay.is_suspended())
+                    .collect();
+
+                sweep(live, max_live_overlays, suspend_after);
+            }
+        });
+    }
+
+    fn sweep<T: Suspendable>(
+        mut live: Vec<&mut T>,
+        max_live_overlays: usize,
+        suspend_after: Option<Duration>,
+    ) {
+        live.retain_mut(|overlay| {
+            if overlay.is_hidden()
+                && suspend_after.is_some_and(|after| overlay.hidden_duration() > after)
+            {
+                overlay.suspend();
+                return false;
+            }
+
+            true
+        });
+
+        if max_live_overlays == 0 || live.len() <= max_live_overlays {
+            return;
+        }
+        let excess = live.len() - max_live_overlays;
+
+        // Suspending overlays of shown target windows would blank them.
+        let mut hidden: Vec<_> = live
+            .into_iter()
+            .filter(|overlay| overlay.is_hidden())
+            .collect();
+        hidden.sort_by_key(|overlay| overlay.last_used());
+
+        for overlay in hidden.into_iter().take(excess) {
+            overlay.suspend();
+        }
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        struct FakeOverlay {
+            hidden: bool,
+            /// Pausing hides the overlay window but not the target window.
+            paused: bool,
+            hidden_duration: Duration,
+            last_used: Instant,
+            suspended: bool,
+        }
+
+        impl FakeOverlay {
+            /// Same as [`Overlay::is_visible`].
+            fn is_visible(&self) -> bool {
+                !self.hidden && !self.paused
+            }
+        }
+
+        impl Suspendable for FakeOverlay {
+            fn is_hidden(&self) -> bool {
+                self.hidden
+            }
+
+            fn hidden_duration(&self) -> Duration {
+                self.hidden_duration
+            }
+
+            fn last_used(&self) -> Instant {
+                self.last_used
+            }
+
+            fn suspend(&mut self) {
+                self.suspended = true;
+            }
+        }
+
+        /// Overlays last used the given seconds after `base`.
+        fn overlays(base: Instant, hidden: &[bool], used_at: &[u64]) -> Vec<FakeOverlay> {
+            hidden
+                .iter()
+                .zip(used_at)
+                .map(|(&hidden, &used_at)| FakeOverlay {
+                    hidden,
+                    paused: false,
+                    hidden_duration: if hidden {
+                        Duration::from_secs(1)
+                    } else {
+                        Duration::ZERO
+                    },
+                    last_used: base + Duration::from_secs(used_at),
+                    suspended: false,
+                })
+                .collect()
+        }
+
+        fn suspended(overlays: &[FakeOverlay]) -> Vec<bool> {
+            overlays.iter().map(|overlay| overlay.suspended).collect()
+        }
+
+        #[test]
+        fn cap_suspends_least_recently_used_first() {
+            let mut overlays = overlays(Instant::now(), &[true; 5], &[3, 1, 4, 0, 2]);
+
+            sweep(overlays.iter_mut().collect(), 2, None);
+
+            assert_eq!(suspended(&overlays), [false, true, false, true, true]);
+        }
+
+        #[test]
+        fn cap_never_suspends_shown_overlays() {
+            let mut overlays = overlays(Instant::now(), &[false, true, false, true], &[0, 1, 2, 3]);
+
+            sweep(overlays.iter_mut().collect(), 1, None);
+
+            // The cap is exceeded while the target windows are shown.
+            assert_eq!(suspended(&overlays), [false, true, false, true]);
+        }
+
+        #[test]
+        fn cap_skips_shown_overlays_in_eviction_order() {
+            let mut overlays = overlays(Instant::now(), &[false, true, true, true], &[0, 1, 2, 3]);
+
+            sweep(overlays.iter_mut().collect(), 3, None);
+
+            assert_eq!(suspended(&overlays), [false, true, false, false]);
+        }
+
+        #[test]
+        fn cap_counts_overlays_suspended_for_being_hidden() {
+            let mut overlays = overlays(Instant::now(), &[true; 3], &[2, 0, 1]);
+            overlays[0].hidden_duration = Duration::from_secs(120);
+
+            sweep(
+                overlays.iter_mut().collect(),
+                2,
+                Some(Duration::from_secs(60)),
+            );
+
+            assert_eq!(suspended(&overlays), [true, false, false]);
+        }
+
+        #[test]
+        fn zero_cap_is_unlimited() {
+            let mut overlays = overlays(Instant::now(), &[true; 3], &[0, 1, 2]);
+
+            sweep(overlays.iter_mut().collect(), 0, None);
+
+            assert_eq!(suspended(&overlays), [false; 3]);
+        }
+
+        #[test]
+        fn cap_never_suspends_paused_overlays() {
+            let mut overlays = overlays(Instant::now(), &[false, false, true], &[0, 1, 2]);
+            for overlay in &mut overlays {
+                overlay.paused = true;
+            }
+            assert!(overlays.iter().all(|overlay| !overlay.is_visible()));
+
+            sweep(overlays.iter_mut().collect(), 1, Some(Duration::ZERO));
+
+            // Nothing resumes the overlays of shown target windows when they are unpaused.
+            assert_eq!(suspended(&overlays), [false, false, true]);
+        }
+    }
+}
@@ -1,13 +1,38 @@
-import { Show } from "solid-js";
+import { onCleanup, Show } from "solid-js";
 import "./App.css";
 import { HomeView } from "./components/home-view/HomeView";
 import { default as WallpaperView } from "./components/wallpaper-view/WallpaperView";
-import { GlobalStateProvider, useView } from "./GlobalState";
+import { GlobalStateProvider, useView, useWallpapers } from "./GlobalState";
+import { listenWallpaperSourceError } from "./lib/binding/event_wallpaper";
+import { errorMessage } from "./lib/binding/utils";
 import { cl } from "./lib/utils";
 import SofumeLogo from "./sofume_logo.png";
 
+/** Shows an error when the new source of a wallpaper failed to load. */
+function useSourceErrorMessage() {
+  const [wallpapers] = useWallpapers();
+  // Every overlay of the wallpaper reports the same error, so show only one of them.
+  let showing = false;
+
+  const unlisten = listenWallpaperSourceError(async (error) => {
+    if (showing) return;
+    showing = true;
+
+    const name = wallpapers()[error.wallpaperId]?.name ?? error.wallpaperId;
+    await errorMessage({
+      message: `壁紙「${name}」の読み込みに失敗しました。前の壁紙をそのまま表示します。`,
+      detail: error.detail,
+    });
+
+    showing = false;
+  });
+
+  onCleanup(() => unlisten.then((unlisten) => unlisten()));
+}
+
 export function Main() {
   const [view] = useView();
+  useSourceErrorMessage();
 
   return (
     <main style="height: calc(100vh - 48px);">
@@ -29,6 +54,7 @@ export function Main() {
               <WallpaperView
                 id={wallpaperView.id}
                 wallpaper={wallpaperView.wallpaper}
+                template={wallpaperView.template}
               />
             </div>
           );
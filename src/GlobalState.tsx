@@ -7,12 +7,18 @@ import {
   useContext,
 } from "solid-js";
 import { getConfig } from "./lib/binding/command_config";
-import type { Config, Wallpapers } from "./lib/binding/payload_config";
+import type {
+  Config,
+  SourceLibrary,
+  Wallpapers,
+} from "./lib/binding/payload_config";
 import type { View } from "./lib/view";
 
 export interface GlobalState {
   wallpapers: () => Wallpapers;
   setWallpapers: (update: (prev: Wallpapers) => void) => void;
+  sources: () => SourceLibrary;
+  setSources: (update: (prev: SourceLibrary) => void) => void;
   view: () => View;
   setView: (view: View) => void;
   config: () => Config;
@@ -27,14 +33,19 @@ export function GlobalStateProvider(props: ParentProps) {
     initialConfig.wallpapers,
     { equals: false },
   );
+  const [sources, setSources] = createSignal<SourceLibrary>(
+    initialConfig.sources,
+    { equals: false },
+  );
   const [view, setView] = createSignal<View>({ type: "home" });
 
   createEffect(
-    on(wallpapers, (wallpapers) => {
+    on([wallpapers, sources], ([wallpapers, sources]) => {
       setConfig({
         version: config().version,
         open_window_on_startup: config().open_window_on_startup,
         wallpapers: wallpapers,
+        sources: sources,
       });
     }),
   );
@@ -42,6 +53,8 @@ export function GlobalStateProvider(props: ParentProps) {
   const state: GlobalState = {
     wallpapers,
     setWallpapers,
+    sources,
+    setSources,
     view,
     setView,
     config,
@@ -69,6 +82,11 @@ export function useWallpapers() {
   return [state.wallpapers, state.setWallpapers] as const;
 }
 
+export function useSources() {
+  const state = useGlobalState();
+  return [state.sources, state.setSources] as const;
+}
+
 export function useView() {
   const state = useGlobalState();
   return [state.view, state.setView] as const;
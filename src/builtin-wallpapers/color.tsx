@@ -0,0 +1,5 @@
+export default function App(props: { background: string }) {
+  return (
+    <div class="w-screen h-screen" style={{ background: props.background }} />
+  );
+}
@@ -1,14 +1,61 @@
 import { convertFileSrc } from "@tauri-apps/api/core";
+import { createSignal, onCleanup, Show } from "solid-js";
+import { reportMediaLoad } from "../lib/binding/command_wallpaper";
+import type { Layout } from "../lib/binding/payload_config";
+import { mediaStyle, tileOffset } from "../lib/layout";
+import { listenLayout } from "../lib/wallpaper_event";
 
-export default function App(props: { path: string }) {
+export default function App(props: { path: string; layout: Layout }) {
   const url = convertFileSrc(props.path);
+  const [layout, setLayout] = createSignal(props.layout);
+  const [size, setSize] = createSignal({ width: 0, height: 0 });
+
+  onCleanup(listenLayout(setLayout));
+
+  const tileStyle = () => {
+    const tile = {
+      width: size().width * layout().scale,
+      height: size().height * layout().scale,
+    };
+    const area = { width: window.innerWidth, height: window.innerHeight };
+    const { x, y } = tileOffset(layout(), area, tile);
+
+    return {
+      "background-image": `url("${url}")`,
+      "background-size": `${tile.width}px ${tile.height}px`,
+      "background-position": `${x}px ${y}px`,
+    };
+  };
 
   return (
-    <div class="w-screen h-screen">
+    <div class="w-screen h-screen overflow-hidden">
+      <Show
+        when={layout().fit === "Tile"}
+        fallback={
+          <img
+            class="w-full h-full"
+            style={mediaStyle(layout())}
+            src={url}
+            alt="画像のロードに失敗しました。"
+          />
+        }
+      >
+        <div class="w-full h-full bg-repeat" style={tileStyle()} />
+      </Show>
+
+      {/* Tiles need the original size of the picture. */}
       <img
-        class="w-full h-full object-cover"
         src={url}
-        alt="画像のロードに失敗しました。"
+        alt=""
+        hidden
+        onLoad={(event) => {
+          setSize({
+            width: event.currentTarget.naturalWidth,
+            height: event.currentTarget.naturalHeight,
+          });
+          reportMediaLoad();
+        }}
+        onError={() => reportMediaLoad(`Failed to load \`${props.path}\`.`)}
       />
     </div>
   );
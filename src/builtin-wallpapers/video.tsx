@@ -1,18 +1,90 @@
 import { convertFileSrc } from "@tauri-apps/api/core";
+import { createSignal, onCleanup, onMount, Show } from "solid-js";
+import { reportMediaLoad } from "../lib/binding/command_wallpaper";
+import type { Layout, VideoOptions } from "../lib/binding/payload_config";
+import { mediaStyle, tileOffset } from "../lib/layout";
+import { followPlaybackClock, hasLoopPoints } from "../lib/playback_clock";
+import { listenAudio, listenLayout } from "../lib/wallpaper_event";
 
-export default function App(props: { path: string }) {
+export default function App(props: {
+  path: string;
+  layout: Layout;
+  options: VideoOptions;
+}) {
   const url = convertFileSrc(props.path);
+  const [layout, setLayout] = createSignal(props.layout);
+  const [audible, setAudible] = createSignal(false);
+  let video!: HTMLVideoElement;
+
+  onMount(() => onCleanup(followPlaybackClock(video, props.options)));
+  onCleanup(listenLayout(setLayout));
+  onCleanup(listenAudio(({ audible }) => setAudible(audible)));
 
   return (
-    <div class="w-screen h-screen">
+    <div class="w-screen h-screen overflow-hidden">
       <video
+        ref={video}
         autoplay
-        loop
-        muted
+        loop={!hasLoopPoints(props.options)}
+        muted={!audible() || props.options.volume <= 0}
+        prop:volume={Math.min(props.options.volume, 100) / 100}
         preload="auto"
         src={url}
-        class="w-full h-full object-cover"
+        class="w-full h-full"
+        style={mediaStyle(layout())}
+        hidden={layout().fit === "Tile"}
+        onLoadedData={() => reportMediaLoad()}
+        onError={() =>
+          reportMediaLoad(
+            `Failed to load \`${props.path}\`: ${video.error?.message ?? ""}`,
+          )
+        }
       />
+
+      <Show when={layout().fit === "Tile"}>
+        <VideoTiles video={video} layout={layout()} />
+      </Show>
     </div>
   );
 }
+
+/** Repeats the frames of the video on a canvas, since CSS cannot tile videos. */
+function VideoTiles(props: { video: HTMLVideoElement; layout: Layout }) {
+  let canvas!: HTMLCanvasElement;
+  let frame = 0;
+
+  const draw = () => {
+    frame = requestAnimationFrame(draw);
+
+    const { video, layout } = props;
+    const context = canvas.getContext("2d");
+    if (context === null || video.videoWidth === 0) return;
+
+    const ratio = window.devicePixelRatio;
+    const area = { width: window.innerWidth, height: window.innerHeight };
+    if (canvas.width !== area.width * ratio) canvas.width = area.width * ratio;
+    if (canvas.height !== area.height * ratio) {
+      canvas.height = area.height * ratio;
+    }
+
+    const tile = {
+      width: video.videoWidth * layout.scale,
+      height: video.videoHeight * layout.scale,
+    };
+    const { x, y } = tileOffset(layout, area, tile);
+    const pattern = context.createPattern(video, "repeat");
+    if (pattern === null) return;
+
+    pattern.setTransform(new DOMMatrix().translate(x, y).scale(layout.scale));
+    context.setTransform(ratio, 0, 0, ratio, 0, 0);
+    context.fillStyle = pattern;
+    context.fillRect(0, 0, area.width, area.height);
+  };
+
+  onMount(() => {
+    frame = requestAnimationFrame(draw);
+  });
+  onCleanup(() => cancelAnimationFrame(frame));
+
+  return <canvas ref={canvas} class="w-full h-full" />;
+}
@@ -1,5 +1,18 @@
+import { createSignal, onCleanup } from "solid-js";
 import YouTubeEmbed from "../components/wallpaper/YouTubeEmbed";
+import type { YouTubeOptions } from "../lib/binding/payload_config";
+import { listenPlayback } from "../lib/wallpaper_event";
 
-export default function App(props: { url: string }) {
-  return <YouTubeEmbed url={props.url} />;
+export default function App(props: { url: string; options: YouTubeOptions }) {
+  const [playing, setPlaying] = createSignal(true);
+
+  onCleanup(listenPlayback(({ playing }) => setPlaying(playing)));
+
+  return (
+    <YouTubeEmbed
+      url={props.url}
+      options={props.options}
+      playing={playing()}
+    />
+  );
 }
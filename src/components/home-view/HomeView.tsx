@@ -1,9 +1,22 @@
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { basename } from "@tauri-apps/api/path";
+import { open } from "@tauri-apps/plugin-dialog";
+import FileInput from "lucide-solid/icons/file-input";
+import FolderInput from "lucide-solid/icons/folder-input";
 import Plus from "lucide-solid/icons/plus";
-import { createResource, For } from "solid-js";
-import { useView, useWallpapers } from "../../GlobalState";
-import type { Wallpaper } from "../../lib/binding/payload_config";
+import { createResource, For, Show } from "solid-js";
+import { useConfig, useView, useWallpapers } from "../../GlobalState";
+import { backgroundOf } from "../../lib/background";
+import { saveConfig } from "../../lib/binding/command_config";
+import {
+  BUNDLE_EXTENSION,
+  importWallpaperBundle,
+} from "../../lib/binding/command_bundle";
+import { importWallpaperEngineProject } from "../../lib/binding/command_import";
+import {
+  GRADIENT_KINDS,
+  type Wallpaper,
+} from "../../lib/binding/payload_config";
 import { cl } from "../../lib/utils";
 import { textMutedClass } from "../ui";
 import YouTubeEmbed from "../wallpaper/YouTubeEmbed";
@@ -24,6 +37,8 @@ export function HomeView() {
       </For>
 
       <AddWallpaperCard />
+      <ImportBundleCard />
+      <ImportWallpaperCard />
     </div>
   );
 }
@@ -55,6 +70,86 @@ export function AddWallpaperCard() {
   );
 }
 
+/** Adds the wallpaper in a bundle exported by overkami. */
+export function ImportBundleCard() {
+  const [, setWallpapers] = useWallpapers();
+  const [config] = useConfig();
+
+  const onClick = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: "overkamiの壁紙", extensions: [BUNDLE_EXTENSION] }],
+    });
+    if (path === null) return;
+
+    const imported = await importWallpaperBundle(path);
+    if (imported === null) return;
+
+    setWallpapers((wallpapers) => {
+      wallpapers[imported.id] = imported.wallpaper;
+      return wallpapers;
+    });
+    saveConfig(config());
+  };
+
+  return (
+    <button
+      type="button"
+      onClick={onClick}
+      title="書き出された壁紙を読み込む"
+      class={cl(
+        "flex flex-col justify-center items-center gap-2 h-44",
+        "rounded-lg border-4 border-dashed",
+        "border-dark dark:border-light opacity-60",
+        "cursor-pointer active:scale-95 transition",
+      )}
+    >
+      <FileInput class="stroke-dark dark:stroke-light size-1/4" />
+      <span>ファイルから読み込む</span>
+    </button>
+  );
+}
+
+/** Makes a new wallpaper from a Wallpaper Engine project. */
+export function ImportWallpaperCard() {
+  const [, setView] = useView();
+
+  const onClick = async () => {
+    const path = await open({
+      directory: true,
+      title: "Wallpaper Engineの壁紙のフォルダを選択",
+    });
+    if (path === null) return;
+
+    const project = await importWallpaperEngineProject(path);
+    if (project === null) return;
+
+    setView({
+      type: "wallpaper",
+      id: crypto.randomUUID(),
+      wallpaper: undefined,
+      template: project,
+    });
+  };
+
+  return (
+    <button
+      type="button"
+      onClick={onClick}
+      title="Wallpaper Engineの壁紙を読み込む"
+      class={cl(
+        "flex flex-col justify-center items-center gap-2 h-44",
+        "rounded-lg border-4 border-dashed",
+        "border-dark dark:border-light opacity-60",
+        "cursor-pointer active:scale-95 transition",
+      )}
+    >
+      <FolderInput class="stroke-dark dark:stroke-light size-1/4" />
+      <span>Wallpaper Engineから読み込む</span>
+    </button>
+  );
+}
+
 export function WallpaperCard(props: { id: string; wallpaper: Wallpaper }) {
   const { wallpaper, id } = props;
   const [, setView] = useView();
@@ -64,9 +159,18 @@ export function WallpaperCard(props: { id: string; wallpaper: Wallpaper }) {
   };
 
   const [sourceDisplay] = createResource(async () => {
-    if (wallpaper.source.type === "RemoteWebPage") {
+    if (
+      wallpaper.source.type === "RemoteWebPage" ||
+      wallpaper.source.type === "RemotePicture"
+    ) {
       return new URL(wallpaper.source.location).pathname.split("/").pop();
     }
+    if (wallpaper.source.type === "Color") {
+      return wallpaper.source.color;
+    }
+    if (wallpaper.source.type === "Gradient") {
+      return GRADIENT_KINDS[wallpaper.source.kind];
+    }
 
     return await basename(wallpaper.source.location);
   });
@@ -79,7 +183,19 @@ export function WallpaperCard(props: { id: string; wallpaper: Wallpaper }) {
     >
       <div class="h-44">
         <div class="w-full h-full">
-          <Thumbnail wallpaper={wallpaper} />
+          <Show
+            when={wallpaper.preview}
+            fallback={<Thumbnail wallpaper={wallpaper} />}
+          >
+            {(preview) => (
+              <img
+                src={convertFileSrc(preview())}
+                alt="🖼"
+                draggable="false"
+                class="w-full h-full object-cover rounded-lg"
+              />
+            )}
+          </Show>
         </div>
       </div>
 
@@ -112,6 +228,15 @@ function Thumbnail(props: { wallpaper: Wallpaper }) {
           class="w-full h-full object-cover rounded-lg"
         />
       );
+    case "RemotePicture":
+      return (
+        <img
+          src={wallpaper.source.location}
+          alt="🖼"
+          draggable="false"
+          class="w-full h-full object-cover rounded-lg"
+        />
+      );
     case "Video":
       return (
         <video
@@ -125,7 +250,11 @@ function Thumbnail(props: { wallpaper: Wallpaper }) {
       );
     case "YouTube":
       return (
-        <YouTubeEmbed url={wallpaper.source.location} className="rounded-lg" />
+        <YouTubeEmbed
+          url={wallpaper.source.location}
+          options={wallpaper.source.options}
+          className="rounded-lg"
+        />
       );
     case "LocalWebPage":
       return (
@@ -136,5 +265,19 @@ function Thumbnail(props: { wallpaper: Wallpaper }) {
       );
     case "RemoteWebPage":
       return <iframe title={wallpaper.name} src={wallpaper.source.location} />;
+    case "Color":
+    case "Gradient":
+      return (
+        <div
+          class="w-full h-full rounded-lg"
+          style={{ background: backgroundOf(wallpaper.source) }}
+        />
+      );
+    case "Slideshow":
+      return (
+        <div class="w-full h-full flex items-center justify-center rounded-lg bg-black/40 text-4xl">
+          🎞
+        </div>
+      );
   }
 }
@@ -0,0 +1,96 @@
+import {
+  Field,
+  type FormStore,
+  getValue,
+  setValue,
+} from "@modular-forms/solid";
+import { open } from "@tauri-apps/plugin-dialog";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import { For, Show } from "solid-js";
+import { BATTERY_BEHAVIORS } from "../../lib/binding/payload_config";
+import { fieldClass, iconClass, inputClass, selectClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function BatteryField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  const selectFile = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [
+        {
+          name: "画像",
+          extensions: ["png", "jpg", "jpeg", "gif", "webp", "avif", "bmp"],
+        },
+      ],
+    });
+
+    if (path !== null) {
+      setValue(form, "onBattery.location", path);
+    }
+  };
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="onBattery.type">
+        {(field, props) => (
+          <>
+            <label for={props.name}>バッテリー駆動時</label>
+
+            <div class={selectSlots.base()}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+              >
+                <For each={Object.entries(BATTERY_BEHAVIORS)}>
+                  {([value, label]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={value}
+                      selected={value === (field.value ?? "Keep")}
+                    >
+                      {label}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+          </>
+        )}
+      </Field>
+
+      <Show when={getValue(form, "onBattery.type") === "StaticFallback"}>
+        <Field of={form} name="onBattery.location">
+          {(field, props) => (
+            <>
+              <input {...props} type="text" value={field.value} hidden />
+
+              <button
+                type="button"
+                class={inputClass({
+                  file: true,
+                  class: "text-left font-mono overflow-hidden",
+                })}
+                onClick={selectFile}
+              >
+                {field.value || "クリックで代わりの画像を選択"}
+              </button>
+
+              <div class={error()}>{field.error}</div>
+            </>
+          )}
+        </Field>
+      </Show>
+    </div>
+  );
+}
@@ -0,0 +1,32 @@
+import { Field, type FormStore, required } from "@modular-forms/solid";
+import { fieldClass, inputClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function ColorField(props: { form: FormStore<WallpaperForm> }) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  return (
+    <Field
+      of={form}
+      name="source.color"
+      validate={[required("色を指定してください。")]}
+    >
+      {(field, props) => (
+        <div class={base()}>
+          <label for={props.name} class="text-sm">
+            壁紙の色
+          </label>
+          <input
+            {...props}
+            id={props.name}
+            type="color"
+            value={field.value}
+            class={inputClass({ class: "w-24 h-10 p-1" })}
+          />
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
@@ -0,0 +1,192 @@
+import {
+  Field,
+  FieldArray,
+  type FormStore,
+  getValue,
+  insert,
+  remove,
+} from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import Plus from "lucide-solid/icons/plus";
+import X from "lucide-solid/icons/x";
+import { For, Show } from "solid-js";
+import {
+  EFFECT_TYPES,
+  type Effect,
+  type EffectType,
+} from "../../lib/binding/payload_config";
+import {
+  fieldClass,
+  iconButtonClass,
+  iconClass,
+  inputClass,
+  selectClass,
+  textMutedClass,
+} from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+/** An effect with the fields of every type, so that switching types keeps them. */
+export type EffectForm = {
+  type: EffectType;
+  /** The radius for blurs, the strength for tints, and the amount for the rest. */
+  amount: number;
+  color: string;
+};
+
+export function toEffectForm(effect: Effect): EffectForm {
+  switch (effect.type) {
+    case "Blur":
+      return { type: effect.type, amount: effect.radius, color: "#000000" };
+    case "Tint":
+      return {
+        type: effect.type,
+        amount: effect.strength,
+        color: effect.color,
+      };
+    default:
+      return { type: effect.type, amount: effect.amount, color: "#000000" };
+  }
+}
+
+export function toEffect(effect: EffectForm): Effect {
+  const { type, amount, color } = effect;
+
+  switch (type) {
+    case "Blur":
+      return { type, radius: amount };
+    case "Tint":
+      return { type, color, strength: amount };
+    default:
+      return { type, amount };
+  }
+}
+
+const AMOUNT_LABELS: { [key in EffectType]: string } = {
+  Blur: "半径（px）",
+  Brightness: "明るさ（%、100で元のまま）",
+  Contrast: "コントラスト（%、100で元のまま）",
+  Saturation: "彩度（%、100で元のまま）",
+  Grayscale: "強さ（%）",
+  Tint: "濃さ（%）",
+};
+
+export default function EffectsField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  const typeAt = (index: number) =>
+    getValue(form, `effects.${index}.type`) ?? "Blur";
+
+  return (
+    <div class={base()}>
+      <div>効果</div>
+
+      <FieldArray of={form} name="effects">
+        {(fieldArray) => (
+          <>
+            <For each={fieldArray.items}>
+              {(_, index) => (
+                <div class="flex items-end gap-2">
+                  <Field of={form} name={`${fieldArray.name}.${index()}.type`}>
+                    {(field, props) => (
+                      <div class={selectSlots.base({ size: "sm" })}>
+                        <select
+                          {...props}
+                          class={selectSlots.select()}
+                          value={field.value}
+                        >
+                          <For each={Object.entries(EFFECT_TYPES)}>
+                            {([value, label]) => (
+                              <option
+                                class={selectSlots.optionClass()}
+                                value={value}
+                                selected={value === (field.value ?? "Blur")}
+                              >
+                                {label}
+                              </option>
+                            )}
+                          </For>
+                        </select>
+
+                        <span class={selectSlots.chevron()}>
+                          <ChevronDown class={iconClass()} />
+                        </span>
+                      </div>
+                    )}
+                  </Field>
+
+                  <Field
+                    of={form}
+                    name={`${fieldArray.name}.${index()}.amount`}
+                    type="number"
+                  >
+                    {(field, props) => (
+                      <label class="flex flex-col text-sm">
+                        {AMOUNT_LABELS[typeAt(index())]}
+                        <input
+                          {...props}
+                          type="number"
+                          min={0}
+                          step={1}
+                          value={field.value ?? 0}
+                          class={inputClass({ class: "w-24" })}
+                        />
+                      </label>
+                    )}
+                  </Field>
+
+                  <Show when={typeAt(index()) === "Tint"}>
+                    <Field
+                      of={form}
+                      name={`${fieldArray.name}.${index()}.color`}
+                    >
+                      {(field, props) => (
+                        <input
+                          {...props}
+                          type="color"
+                          value={field.value}
+                          class={inputClass({ class: "w-16 h-10 p-1" })}
+                        />
+                      )}
+                    </Field>
+                  </Show>
+
+                  <button
+                    type="button"
+                    class={iconButtonClass()}
+                    onClick={() =>
+                      remove(form, fieldArray.name, { at: index() })
+                    }
+                  >
+                    <X class={iconClass()} />
+                  </button>
+                </div>
+              )}
+            </For>
+
+            <button
+              type="button"
+              class={iconButtonClass()}
+              onClick={() =>
+                insert(form, fieldArray.name, {
+                  value: { type: "Blur", amount: 8, color: "#000000" },
+                })
+              }
+            >
+              <Plus class={iconClass()} />
+            </button>
+
+            <div class={error()}>{fieldArray.error}</div>
+          </>
+        )}
+      </FieldArray>
+
+      <p class={textMutedClass({ class: "text-sm" })}>
+        壁紙をぼかしたり暗くしたりして、アプリの文字を読みやすくします。上から順に適用されます。
+      </p>
+    </div>
+  );
+}
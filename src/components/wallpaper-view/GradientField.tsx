@@ -0,0 +1,161 @@
+import {
+  Field,
+  FieldArray,
+  type FormStore,
+  getValue,
+  insert,
+  remove,
+} from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import Plus from "lucide-solid/icons/plus";
+import X from "lucide-solid/icons/x";
+import { For, Show } from "solid-js";
+import { GRADIENT_KINDS } from "../../lib/binding/payload_config";
+import {
+  fieldClass,
+  iconButtonClass,
+  iconClass,
+  inputClass,
+  selectClass,
+} from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function GradientField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="source.gradient.kind">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              グラデーションの形
+            </label>
+
+            <div class={selectSlots.base({ size: "sm" })}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+              >
+                <For each={Object.entries(GRADIENT_KINDS)}>
+                  {([value, label]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={value}
+                      selected={value === (field.value ?? "Linear")}
+                    >
+                      {label}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+          </>
+        )}
+      </Field>
+
+      <Show when={getValue(form, "source.gradient.kind") !== "Radial"}>
+        <Field of={form} name="source.gradient.angle" type="number">
+          {(field, props) => (
+            <>
+              <label for={props.name} class="text-sm">
+                向き（度、0で下から上）
+              </label>
+              <input
+                {...props}
+                id={props.name}
+                type="number"
+                min={0}
+                max={360}
+                step={15}
+                value={field.value ?? 180}
+                class={inputClass({ class: "w-24" })}
+              />
+            </>
+          )}
+        </Field>
+      </Show>
+
+      <FieldArray of={form} name="source.gradient.stops">
+        {(fieldArray) => (
+          <>
+            <div class="text-sm">色と位置（%）</div>
+
+            <For each={fieldArray.items}>
+              {(_, index) => (
+                <div class="flex items-center gap-2">
+                  <Field
+                    of={form}
+                    name={`${fieldArray.name}.${index()}.color`}
+                  >
+                    {(field, props) => (
+                      <input
+                        {...props}
+                        type="color"
+                        value={field.value}
+                        class={inputClass({ class: "w-16 h-10 p-1" })}
+                      />
+                    )}
+                  </Field>
+
+                  <Field
+                    of={form}
+                    name={`${fieldArray.name}.${index()}.position`}
+                    type="number"
+                  >
+                    {(field, props) => (
+                      <input
+                        {...props}
+                        type="number"
+                        min={0}
+                        max={100}
+                        step={1}
+                        value={field.value ?? 0}
+                        class={inputClass({ class: "w-24" })}
+                      />
+                    )}
+                  </Field>
+
+                  <button
+                    type="button"
+                    class={iconButtonClass()}
+                    disabled={fieldArray.items.length <= 2}
+                    onClick={() =>
+                      remove(form, fieldArray.name, { at: index() })
+                    }
+                  >
+                    <X class={iconClass()} />
+                  </button>
+                </div>
+              )}
+            </For>
+
+            <button
+              type="button"
+              class={iconButtonClass()}
+              onClick={() =>
+                insert(form, fieldArray.name, {
+                  value: { color: "#ffffff", position: 100 },
+                })
+              }
+            >
+              <Plus class={iconClass()} />
+            </button>
+
+            <div class={error()}>{fieldArray.error}</div>
+          </>
+        )}
+      </FieldArray>
+    </div>
+  );
+}
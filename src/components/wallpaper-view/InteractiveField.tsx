@@ -0,0 +1,59 @@
+import { Field, type FormStore, getValue } from "@modular-forms/solid";
+import { Show } from "solid-js";
+import { fieldClass, textMutedClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function InteractiveField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  return (
+    <>
+      <Field of={form} name="interactive" type="boolean">
+        {(field, props) => (
+          <div class={base()}>
+            <label class="flex items-center gap-2">
+              <input
+                {...props}
+                type="checkbox"
+                checked={field.value ?? false}
+                class="size-4 cursor-pointer"
+              />
+              壁紙を操作できるようにする
+            </label>
+            <p class={textMutedClass({ class: "text-sm" })}>
+              Webページの壁紙が指定した範囲だけ、クリックなどの操作を壁紙が受け取るようにします。
+              それ以外の場所の操作は、これまで通りウィンドウに届きます。
+            </p>
+            <div class={error()}>{field.error}</div>
+          </div>
+        )}
+      </Field>
+
+      <Show when={getValue(form, "interactive")}>
+        <Field of={form} name="allowRemoteInput" type="boolean">
+          {(field, props) => (
+            <div class={base()}>
+              <label class="flex items-center gap-2">
+                <input
+                  {...props}
+                  type="checkbox"
+                  checked={field.value ?? false}
+                  class="size-4 cursor-pointer"
+                />
+                リモートのWebページの操作を許可する
+              </label>
+              <p class={textMutedClass({ class: "text-sm" })}>
+                インターネット上のWebページの壁紙からも、操作を受け取る範囲を指定できるようにします。
+                ローカルのWebページからは常に指定できます。
+              </p>
+              <div class={error()}>{field.error}</div>
+            </div>
+          )}
+        </Field>
+      </Show>
+    </>
+  );
+}
@@ -0,0 +1,144 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import { For } from "solid-js";
+import { ANCHORS, FITS } from "../../lib/binding/payload_config";
+import {
+  fieldClass,
+  iconClass,
+  inputClass,
+  selectClass,
+  textMutedClass,
+} from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function LayoutField(props: { form: FormStore<WallpaperForm> }) {
+  const { form } = props;
+  const { base } = fieldClass();
+  const selectSlots = selectClass();
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="source.layout.fit">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              表示のしかた
+            </label>
+
+            <div class={selectSlots.base({ size: "sm" })}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+              >
+                <For each={Object.entries(FITS)}>
+                  {([value, label]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={value}
+                      selected={value === (field.value ?? "Cover")}
+                    >
+                      {label}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+          </>
+        )}
+      </Field>
+
+      <Field of={form} name="source.layout.anchor">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              揃える位置
+            </label>
+
+            <div class={selectSlots.base({ size: "sm" })}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+              >
+                <For each={Object.entries(ANCHORS)}>
+                  {([value, label]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={value}
+                      selected={value === (field.value ?? "Center")}
+                    >
+                      {label}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+          </>
+        )}
+      </Field>
+
+      <p class={textMutedClass({ class: "text-sm" })}>
+        切り取られる時は、揃える位置の側が残ります。
+      </p>
+
+      <div class="flex items-end gap-2">
+        <Field of={form} name="source.layout.scale" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              拡大率
+              <input
+                {...props}
+                type="number"
+                min={0.05}
+                step={0.05}
+                value={field.value ?? 1}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+
+        <Field of={form} name="source.layout.offsetX" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              横のずれ（%）
+              <input
+                {...props}
+                type="number"
+                step={1}
+                value={field.value ?? 0}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+
+        <Field of={form} name="source.layout.offsetY" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              縦のずれ（%）
+              <input
+                {...props}
+                type="number"
+                step={1}
+                value={field.value ?? 0}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+      </div>
+    </div>
+  );
+}
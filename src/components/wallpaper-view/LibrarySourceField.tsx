@@ -0,0 +1,181 @@
+import {
+  Field,
+  type FormStore,
+  getValue,
+  getValues,
+  setValue,
+  setValues,
+} from "@modular-forms/solid";
+import { confirm } from "@tauri-apps/plugin-dialog";
+import BookPlus from "lucide-solid/icons/book-plus";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import Trash2 from "lucide-solid/icons/trash-2";
+import { createSignal, For, Show } from "solid-js";
+import { useConfig, useSources, useWallpapers } from "../../GlobalState";
+import { saveConfig } from "../../lib/binding/command_config";
+import { applyLibrarySource } from "../../lib/binding/command_wallpaper";
+import {
+  fieldClass,
+  iconButtonClass,
+  iconClass,
+  inputClass,
+  selectClass,
+  textMutedClass,
+} from "../ui";
+import { toSource, toSourceForm, type WallpaperForm } from "./WallpaperForm";
+
+export default function LibrarySourceField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  const [sources, setSources] = useSources();
+  const [, setWallpapers] = useWallpapers();
+  const [config] = useConfig();
+  const [name, setName] = createSignal("");
+
+  const hasSource = () => {
+    const type = getValue(form, "source.type");
+    return (
+      type === "Color" ||
+      type === "Gradient" ||
+      Boolean(getValue(form, "source.location"))
+    );
+  };
+
+  const useSource = (id: string) => {
+    setValue(form, "librarySource", id);
+
+    const entry = sources()[id];
+    if (entry !== undefined) {
+      setValues(form, { source: toSourceForm(entry.source) });
+    }
+  };
+
+  const addToLibrary = async () => {
+    const id = crypto.randomUUID();
+    const entry = {
+      name: name(),
+      source: toSource(getValues(form).source as WallpaperForm["source"]),
+    };
+
+    setSources((sources) => {
+      sources[id] = entry;
+      return sources;
+    });
+    await applyLibrarySource(id, entry);
+    saveConfig(config());
+
+    setName("");
+    setValue(form, "librarySource", id);
+  };
+
+  const removeFromLibrary = async () => {
+    const id = getValue(form, "librarySource");
+    if (!id) return;
+
+    const entry = sources()[id];
+    if (!(await confirm(`ライブラリから「${entry?.name}」を削除しますか？`))) {
+      return;
+    }
+
+    // The wallpapers using it keep the source as their own.
+    setSources((sources) => {
+      delete sources[id];
+      return sources;
+    });
+    setWallpapers((wallpapers) => {
+      for (const wallpaper of Object.values(wallpapers)) {
+        if (wallpaper.librarySource === id) wallpaper.librarySource = null;
+      }
+      return wallpapers;
+    });
+    saveConfig(config());
+
+    setValue(form, "librarySource", "");
+  };
+
+  return (
+    <Field of={form} name="librarySource">
+      {(field, props) => (
+        <div class={base()}>
+          <label for={props.name}>ソースライブラリ</label>
+
+          <div class="flex items-center gap-2">
+            <div class={selectSlots.base()}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+                onChange={(event) => useSource(event.currentTarget.value)}
+              >
+                <option class={selectSlots.optionClass()} value="">
+                  使わない（この壁紙だけのソース）
+                </option>
+                <For each={Object.entries(sources())}>
+                  {([id, entry]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={id}
+                      selected={id === field.value}
+                    >
+                      {entry.name}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+
+            <Show when={field.value}>
+              <button
+                type="button"
+                class={iconButtonClass()}
+                title="ライブラリから削除する"
+                onClick={removeFromLibrary}
+              >
+                <Trash2 class={iconClass()} />
+              </button>
+            </Show>
+          </div>
+
+          <Show
+            when={field.value}
+            fallback={
+              <div class="flex items-center gap-2">
+                <input
+                  type="text"
+                  placeholder="ライブラリでの名前"
+                  class={inputClass()}
+                  value={name()}
+                  onInput={(event) => setName(event.currentTarget.value)}
+                />
+                <button
+                  type="button"
+                  class={iconButtonClass()}
+                  title="今のソースをライブラリに追加する"
+                  disabled={!name() || !hasSource()}
+                  onClick={addToLibrary}
+                >
+                  <BookPlus class={iconClass()} />
+                </button>
+              </div>
+            }
+          >
+            <p class={textMutedClass({ class: "text-sm" })}>
+              保存すると、ソースの変更はこのソースを使うすべての壁紙に反映されます。
+            </p>
+          </Show>
+
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
@@ -0,0 +1,37 @@
+import { Field, type FormStore, setValue } from "@modular-forms/solid";
+import { fieldClass, inputClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function MaxLiveOverlaysField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+
+  const { base, error } = fieldClass();
+
+  return (
+    <Field of={form} name="maxLiveOverlays" type="number">
+      {(field, props) => (
+        <div class={base()}>
+          <label for={props.name}>同時に表示する壁紙の上限（0 で無制限）</label>
+          <input
+            {...props}
+            id={props.name}
+            type="number"
+            min={0}
+            step={1}
+            value={field.value ?? 0}
+            class={inputClass({ class: "w-24" })}
+            onChange={(e) => {
+              if (e.target.value === "") {
+                e.target.value = "0";
+                setValue(form, "maxLiveOverlays", 0);
+              }
+            }}
+          />
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
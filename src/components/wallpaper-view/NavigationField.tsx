@@ -0,0 +1,124 @@
+import {
+  Field,
+  FieldArray,
+  type FormStore,
+  getValue,
+  insert,
+  remove,
+} from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import Plus from "lucide-solid/icons/plus";
+import X from "lucide-solid/icons/x";
+import { For, Show } from "solid-js";
+import { NAVIGATION_POLICIES } from "../../lib/binding/payload_config";
+import {
+  fieldClass,
+  iconButtonClass,
+  iconClass,
+  inputClass,
+  selectClass,
+  textMutedClass,
+} from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function NavigationField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  const isWebPage = () => {
+    const type = getValue(form, "source.type");
+    return type === "RemoteWebPage" || type === "LocalWebPage";
+  };
+
+  return (
+    <Show when={isWebPage()}>
+      <div class={base()}>
+        <Field of={form} name="navigation.type">
+          {(field, props) => (
+            <>
+              <label for={props.name}>ページの移動</label>
+
+              <div class={selectSlots.base()}>
+                <select
+                  {...props}
+                  id={props.name}
+                  class={selectSlots.select()}
+                  value={field.value}
+                >
+                  <For each={Object.entries(NAVIGATION_POLICIES)}>
+                    {([value, label]) => (
+                      <option
+                        class={selectSlots.optionClass()}
+                        value={value}
+                        selected={value === (field.value ?? "SameOrigin")}
+                      >
+                        {label}
+                      </option>
+                    )}
+                  </For>
+                </select>
+
+                <span class={selectSlots.chevron()}>
+                  <ChevronDown class={iconClass()} />
+                </span>
+              </div>
+            </>
+          )}
+        </Field>
+
+        <Show when={getValue(form, "navigation.type") === "Allowlist"}>
+          <FieldArray of={form} name="navigation.origins">
+            {(fieldArray) => (
+              <>
+                <For each={fieldArray.items}>
+                  {(_, index) => (
+                    <Field of={form} name={`${fieldArray.name}.${index()}`}>
+                      {(field, props) => (
+                        <div class="flex items-center gap-2">
+                          <input
+                            {...props}
+                            type="text"
+                            placeholder="https://*.example.com"
+                            class={inputClass({ class: "font-mono" })}
+                            value={field.value}
+                          />
+                          <button
+                            type="button"
+                            class={iconButtonClass()}
+                            onClick={() =>
+                              remove(form, fieldArray.name, { at: index() })
+                            }
+                          >
+                            <X class={iconClass()} />
+                          </button>
+                        </div>
+                      )}
+                    </Field>
+                  )}
+                </For>
+
+                <button
+                  type="button"
+                  class={iconButtonClass()}
+                  onClick={() => insert(form, fieldArray.name, { value: "" })}
+                >
+                  <Plus class={iconClass()} />
+                </button>
+
+                <div class={error()}>{fieldArray.error}</div>
+              </>
+            )}
+          </FieldArray>
+        </Show>
+
+        <p class={textMutedClass({ class: "text-sm" })}>
+          壁紙のページから他のページへの移動を制限します。
+          ポップアップや新しいウィンドウ、カメラなどの許可の要求は常に拒否されます。
+        </p>
+      </div>
+    </Show>
+  );
+}
@@ -0,0 +1,56 @@
+import Pause from "lucide-solid/icons/pause";
+import Play from "lucide-solid/icons/play";
+import SkipBack from "lucide-solid/icons/skip-back";
+import { controlPlayback } from "../../lib/binding/command_wallpaper";
+import { buttonClass, fieldClass, textMutedClass } from "../ui";
+
+/** Controls the playback of the video shown by every overlay of the wallpaper at once. */
+export default function PlaybackControlField(props: { id: string }) {
+  const { base } = fieldClass();
+  const button = buttonClass({
+    color: "secondary",
+    size: "sm",
+    withIcon: true,
+  });
+
+  return (
+    <div class={base()}>
+      <span>動画の操作</span>
+
+      <div class="flex gap-2">
+        <button
+          type="button"
+          class={button}
+          onClick={() => controlPlayback(props.id, { type: "Pause" })}
+        >
+          <Pause />
+          一時停止
+        </button>
+
+        <button
+          type="button"
+          class={button}
+          onClick={() => controlPlayback(props.id, { type: "Resume" })}
+        >
+          <Play />
+          再開
+        </button>
+
+        <button
+          type="button"
+          class={button}
+          onClick={() =>
+            controlPlayback(props.id, { type: "Seek", position: 0 })
+          }
+        >
+          <SkipBack />
+          最初から
+        </button>
+      </div>
+
+      <p class={textMutedClass({ class: "text-sm" })}>
+        すべてのウィンドウの動画にすぐ反映されます。設定には保存されません。
+      </p>
+    </div>
+  );
+}
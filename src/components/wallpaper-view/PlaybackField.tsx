@@ -0,0 +1,51 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import { For } from "solid-js";
+import { PLAYBACK_POLICIES } from "../../lib/binding/payload_config";
+import { fieldClass, iconClass, selectClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function PlaybackField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  return (
+    <Field of={form} name="playback">
+      {(field, props) => (
+        <div class={base()}>
+          <label for={props.name}>動画などの再生</label>
+
+          <div class={selectSlots.base()}>
+            <select
+              {...props}
+              id={props.name}
+              class={selectSlots.select()}
+              value={field.value}
+            >
+              <For each={Object.entries(PLAYBACK_POLICIES)}>
+                {([value, label]) => (
+                  <option
+                    class={selectSlots.optionClass()}
+                    value={value}
+                    selected={value === (field.value ?? "Always")}
+                  >
+                    {label}
+                  </option>
+                )}
+              </For>
+            </select>
+
+            <span class={selectSlots.chevron()}>
+              <ChevronDown class={iconClass()} />
+            </span>
+          </div>
+
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
@@ -0,0 +1,65 @@
+import { Field, type FormStore, setValue } from "@modular-forms/solid";
+import { open } from "@tauri-apps/plugin-dialog";
+import X from "lucide-solid/icons/x";
+import { Show } from "solid-js";
+import { fieldClass, iconButtonClass, iconClass, inputClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function PreviewField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  const selectFile = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [
+        {
+          name: "画像",
+          extensions: ["png", "jpg", "jpeg", "gif", "webp", "avif", "bmp"],
+        },
+      ],
+    });
+
+    if (path !== null) {
+      setValue(form, "preview", path);
+    }
+  };
+
+  return (
+    <Field of={form} name="preview">
+      {(field, props) => (
+        <div class={base()}>
+          <label for={props.name}>一覧に表示する画像</label>
+          <input {...props} type="text" value={field.value} hidden />
+
+          <div class="flex items-center gap-2">
+            <button
+              type="button"
+              class={inputClass({
+                file: true,
+                class: "text-left font-mono overflow-hidden",
+              })}
+              onClick={selectFile}
+            >
+              {field.value || "クリックで画像を選択（選ばない場合は壁紙を表示）"}
+            </button>
+
+            <Show when={field.value}>
+              <button
+                type="button"
+                class={iconButtonClass()}
+                onClick={() => setValue(form, "preview", "")}
+              >
+                <X class={iconClass()} />
+              </button>
+            </Show>
+          </div>
+
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
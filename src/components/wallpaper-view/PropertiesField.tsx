@@ -0,0 +1,223 @@
+import {
+  Field,
+  type FormStore,
+  getValue,
+  setValue,
+} from "@modular-forms/solid";
+import { open } from "@tauri-apps/plugin-dialog";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import { createResource, For, Match, Show, Switch } from "solid-js";
+import { getWallpaperManifest } from "../../lib/binding/command_manifest";
+import type { Property } from "../../lib/binding/payload_manifest";
+import { fieldClass, iconClass, inputClass, selectClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+/** Editors of the properties declared in the manifest of the local web page. */
+export default function PropertiesField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+
+  const page = () =>
+    getValue(form, "source.type") === "LocalWebPage"
+      ? getValue(form, "source.location") || undefined
+      : undefined;
+  const [manifest] = createResource(page, async (path) => {
+    try {
+      return await getWallpaperManifest(path);
+    } catch (e) {
+      console.error(e);
+      return null;
+    }
+  });
+
+  return (
+    <Show when={manifest()?.properties.length}>
+      <div>
+        <div class="mb-2">壁紙の設定</div>
+
+        <For each={manifest()?.properties}>
+          {(property) => <PropertyField form={form} property={property} />}
+        </For>
+      </div>
+    </Show>
+  );
+}
+
+/** How the values of the input elements are converted. */
+const FIELD_TYPES = {
+  color: "string",
+  number: "number",
+  bool: "boolean",
+  text: "string",
+  select: "string",
+  file: "string",
+} as const;
+
+function PropertyField(props: {
+  form: FormStore<WallpaperForm>;
+  property: Property;
+}) {
+  const { form, property } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+  const name = `properties.${property.key}` as const;
+  const label = property.label ?? property.key;
+
+  const selectFile = async () => {
+    if (property.type !== "file") return;
+
+    const path = await open({
+      multiple: false,
+      filters:
+        property.extensions.length > 0
+          ? [{ name: label, extensions: property.extensions }]
+          : [],
+    });
+
+    if (path !== null) {
+      setValue(form, name, path);
+    }
+  };
+
+  return (
+    <Field of={form} name={name} type={FIELD_TYPES[property.type]}>
+      {(field, props) => (
+        <div class={base()}>
+          <Switch>
+            <Match when={property.type === "bool" && property}>
+              {(property) => (
+                <label class="flex items-center gap-2">
+                  <input
+                    {...props}
+                    type="checkbox"
+                    checked={Boolean(field.value ?? property().default)}
+                    class="size-4 cursor-pointer"
+                  />
+                  {label}
+                </label>
+              )}
+            </Match>
+            <Match when={property.type === "color" && property}>
+              {(property) => (
+                <>
+                  <label for={props.name} class="text-sm">
+                    {label}
+                  </label>
+                  <input
+                    {...props}
+                    id={props.name}
+                    type="color"
+                    value={String(field.value ?? property().default)}
+                    class={inputClass({ size: "sm" })}
+                  />
+                </>
+              )}
+            </Match>
+            <Match when={property.type === "number" && property}>
+              {(property) => (
+                <>
+                  <label for={props.name} class="text-sm">
+                    {label}
+                  </label>
+                  <input
+                    {...props}
+                    id={props.name}
+                    type="number"
+                    min={property().min ?? undefined}
+                    max={property().max ?? undefined}
+                    step={property().step ?? "any"}
+                    value={Number(field.value ?? property().default)}
+                    class={inputClass({ size: "sm" })}
+                  />
+                </>
+              )}
+            </Match>
+            <Match when={property.type === "text" && property}>
+              {(property) => (
+                <>
+                  <label for={props.name} class="text-sm">
+                    {label}
+                  </label>
+                  <input
+                    {...props}
+                    id={props.name}
+                    type="text"
+                    value={String(field.value ?? property().default)}
+                    class={inputClass()}
+                  />
+                </>
+              )}
+            </Match>
+            <Match when={property.type === "select" && property}>
+              {(property) => (
+                <>
+                  <label for={props.name} class="text-sm">
+                    {label}
+                  </label>
+                  <div class={selectSlots.base()}>
+                    <select
+                      {...props}
+                      id={props.name}
+                      class={selectSlots.select()}
+                      value={String(field.value ?? property().default)}
+                    >
+                      <For each={property().options}>
+                        {(option) => (
+                          <option
+                            class={selectSlots.optionClass()}
+                            value={option.value}
+                            selected={
+                              option.value ===
+                              (field.value ?? property().default)
+                            }
+                          >
+                            {option.label}
+                          </option>
+                        )}
+                      </For>
+                    </select>
+
+                    <span class={selectSlots.chevron()}>
+                      <ChevronDown class={iconClass()} />
+                    </span>
+                  </div>
+                </>
+              )}
+            </Match>
+            <Match when={property.type === "file" && property}>
+              {(property) => (
+                <>
+                  <label for={props.name} class="text-sm">
+                    {label}
+                  </label>
+                  <input
+                    {...props}
+                    type="text"
+                    value={String(field.value ?? "")}
+                    hidden
+                  />
+
+                  <button
+                    type="button"
+                    class={inputClass({
+                      file: true,
+                      class: "text-left font-mono overflow-hidden",
+                    })}
+                    onClick={selectFile}
+                  >
+                    {field.value ||
+                      property().default ||
+                      "クリックでファイルを選択"}
+                  </button>
+                </>
+              )}
+            </Match>
+          </Switch>
+
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
@@ -0,0 +1,37 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import { fieldClass, inputClass, textMutedClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function RemotePictureField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="source.refreshInterval" type="number">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              更新を確認する間隔（分）
+            </label>
+            <input
+              {...props}
+              id={props.name}
+              type="number"
+              min={1}
+              step={1}
+              value={field.value ?? 60}
+              class={inputClass({ class: "w-24" })}
+            />
+            <div class={error()}>{field.error}</div>
+            <p class={textMutedClass({ class: "text-sm" })}>
+              ダウンロードした画像はキャッシュされ、オフラインの時は最後に取得した画像を表示します。
+            </p>
+          </>
+        )}
+      </Field>
+    </div>
+  );
+}
@@ -0,0 +1,33 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import { fieldClass, textMutedClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function RemoteSystemDataField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  return (
+    <Field of={form} name="allowRemoteSystemData" type="boolean">
+      {(field, props) => (
+        <div class={base()}>
+          <label class="flex items-center gap-2">
+            <input
+              {...props}
+              type="checkbox"
+              checked={field.value ?? false}
+              class="size-4 cursor-pointer"
+            />
+            リモートのWebページにシステムの情報を渡す
+          </label>
+          <p class={textMutedClass({ class: "text-sm" })}>
+            インターネット上のWebページの壁紙からも、CPU使用率やメモリ使用量、
+            起動中のアプリなどを読めるようにします。ローカルのWebページからは常に読めます。
+          </p>
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
@@ -0,0 +1,127 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import { For } from "solid-js";
+import { SLIDESHOW_ORDERS } from "../../lib/binding/payload_config";
+import {
+  fieldClass,
+  iconClass,
+  inputClass,
+  selectClass,
+  textMutedClass,
+} from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function SlideshowField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="source.options.interval" type="number">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              1枚を表示する時間（秒）
+            </label>
+            <input
+              {...props}
+              id={props.name}
+              type="number"
+              min={1}
+              step={1}
+              value={field.value ?? 60}
+              class={inputClass({ class: "w-24" })}
+            />
+            <div class={error()}>{field.error}</div>
+          </>
+        )}
+      </Field>
+
+      <Field of={form} name="source.options.order">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              表示する順番
+            </label>
+
+            <div class={selectSlots.base({ size: "sm" })}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+              >
+                <For each={Object.entries(SLIDESHOW_ORDERS)}>
+                  {([value, label]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={value}
+                      selected={value === (field.value ?? "Listed")}
+                    >
+                      {label}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+          </>
+        )}
+      </Field>
+
+      <Field of={form} name="source.options.shuffle" type="boolean">
+        {(field, props) => (
+          <label class="flex items-center gap-2 text-sm">
+            <input
+              {...props}
+              type="checkbox"
+              checked={field.value ?? false}
+              class="size-4 cursor-pointer"
+            />
+            ランダムな順番で表示する
+          </label>
+        )}
+      </Field>
+
+      <Field of={form} name="source.options.recursive" type="boolean">
+        {(field, props) => (
+          <label class="flex items-center gap-2 text-sm">
+            <input
+              {...props}
+              type="checkbox"
+              checked={field.value ?? false}
+              class="size-4 cursor-pointer"
+            />
+            サブフォルダのファイルも表示する
+          </label>
+        )}
+      </Field>
+
+      <Field of={form} name="source.options.independent" type="boolean">
+        {(field, props) => (
+          <>
+            <label class="flex items-center gap-2 text-sm">
+              <input
+                {...props}
+                type="checkbox"
+                checked={field.value ?? false}
+                class="size-4 cursor-pointer"
+              />
+              ウィンドウごとに別々に切り替える
+            </label>
+            <p class={textMutedClass({ class: "text-sm" })}>
+              オフの時は、すべてのウィンドウで同じファイルを同時に切り替えます。
+              フォルダに追加・削除したファイルは自動で反映されます。
+            </p>
+          </>
+        )}
+      </Field>
+    </div>
+  );
+}
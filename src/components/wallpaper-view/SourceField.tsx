@@ -10,15 +10,30 @@ import {
 import { basename } from "@tauri-apps/api/path";
 import { type DialogFilter, open } from "@tauri-apps/plugin-dialog";
 import ChevronDown from "lucide-solid/icons/chevron-down";
-import { createSignal, onMount } from "solid-js";
+import { Match, onMount, Show, Switch } from "solid-js";
 import type { WallpaperSource } from "../../lib/binding/payload_config";
-import { fieldClass, iconClass, inputClass, selectClass } from "../ui";
+import {
+  buttonClass,
+  fieldClass,
+  iconClass,
+  inputClass,
+  selectClass,
+} from "../ui";
+import ColorField from "./ColorField";
+import GradientField from "./GradientField";
+import LayoutField from "./LayoutField";
+import RemotePictureField from "./RemotePictureField";
+import SlideshowField from "./SlideshowField";
+import TemplateField from "./TemplateField";
+import VideoOptionsField from "./VideoOptionsField";
 import type { WallpaperForm } from "./WallpaperForm";
+import YouTubeOptionsField from "./YouTubeOptionsField";
 
 export default function SourceField(props: { form: FormStore<WallpaperForm> }) {
   const { form } = props;
 
-  const [type, setType] = createSignal<WallpaperSource["type"]>("Picture");
+  // Read from the form, since choosing a library source changes it too.
+  const type = () => getValue(form, "source.type") ?? "Picture";
 
   const selectFile = async () => {
     let filter: DialogFilter | undefined;
@@ -56,122 +71,196 @@ export default function SourceField(props: { form: FormStore<WallpaperForm> }) {
     }
   };
 
+  const selectSlideshow = async (playlist: boolean) => {
+    const path = await open(
+      playlist
+        ? {
+            multiple: false,
+            filters: [
+              { name: "プレイリスト", extensions: ["m3u", "m3u8", "txt"] },
+            ],
+          }
+        : { multiple: false, directory: true },
+    );
+
+    if (path !== null) {
+      setValue(form, "source.location", path);
+    }
+  };
+
   return (
     <div>
       <div class="mb-2">壁紙</div>
 
-      <SourceTypeField
-        form={form}
-        onChange={(type) => {
-          setType(type);
-        }}
-      />
-
-      <Field
-        of={form}
-        name="source.location"
-        validate={[required("壁紙に使うファイルを指定してください。")]}
+      <SourceTypeField form={form} />
+
+      <Switch
+        fallback={
+          <Field
+            of={form}
+            name="source.location"
+            validate={[required("壁紙に使うファイルを指定してください。")]}
+          >
+            {(field, props) => {
+              console.log(field.value);
+              const { base, error } = fieldClass();
+              let fileName = field.value;
+
+              let buttonElement!: HTMLButtonElement;
+              onMount(async () => {
+                if (fileName) {
+                  fileName = await basename(fileName);
+                } else {
+                  fileName =
+                    type() === "Slideshow"
+                      ? "クリックでフォルダを選択"
+                      : "クリックでファイルを選択";
+                }
+
+                buttonElement.innerText = fileName;
+              });
+
+              let title: string | undefined;
+              switch (type()) {
+                case "Picture":
+                  title = "壁紙に使う画像ファイル";
+                  break;
+                case "Video":
+                  title = "壁紙に使う動画ファイル";
+                  break;
+                case "RemotePicture":
+                  title = "壁紙に使う画像のURL";
+                  break;
+                case "YouTube":
+                  title = "壁紙に使うYouTubeの動画URL";
+                  break;
+                case "LocalWebPage":
+                  title = "壁紙に使うHTMLファイル";
+                  break;
+                case "Slideshow":
+                  title = "順番に表示する画像や動画のフォルダ、またはプレイリスト";
+                  break;
+              }
+
+              if (type() === "YouTube" || type() === "RemotePicture") {
+                return (
+                  <div class={base()}>
+                    <label for={props.name} class="text-sm">
+                      {title}
+                    </label>
+
+                    <input
+                      {...props}
+                      id={props.name}
+                      class={inputClass({
+                        class: "text-left font-mono",
+                      })}
+                      type="url"
+                      value={field.value}
+                    />
+
+                    <div class={error()}>{field.error}</div>
+                  </div>
+                );
+              }
+
+              return (
+                <div class={base()}>
+                  <label for={props.name} class="text-sm">
+                    {title}
+                  </label>
+                  <input
+                    {...props}
+                    id={props.name}
+                    type="text"
+                    value={field.value}
+                    hidden
+                  />
+
+                  <div class="flex items-center gap-2">
+                    <button
+                      type="button"
+                      class={inputClass({
+                        file: true,
+                        class: "text-left font-mono overflow-hidden",
+                      })}
+                      onClick={() =>
+                        type() === "Slideshow"
+                          ? selectSlideshow(false)
+                          : selectFile()
+                      }
+                      ref={buttonElement}
+                    />
+
+                    <Show when={type() === "Slideshow"}>
+                      <button
+                        type="button"
+                        class={buttonClass({
+                          color: "secondary",
+                          class: "shrink-0 text-sm",
+                        })}
+                        onClick={() => selectSlideshow(true)}
+                      >
+                        プレイリストを選ぶ
+                      </button>
+                    </Show>
+                  </div>
+
+                  <div class={error()}>{field.error}</div>
+                </div>
+              );
+            }}
+          </Field>
+        }
       >
-        {(field, props) => {
-          console.log(field.value);
-          const { base, error } = fieldClass();
-          let fileName = field.value;
-
-          let buttonElement!: HTMLButtonElement;
-          onMount(async () => {
-            if (fileName) {
-              fileName = await basename(fileName);
-            } else {
-              fileName = "クリックでファイルを選択";
-            }
-
-            buttonElement.innerText = fileName;
-          });
-
-          let title: string | undefined;
-          switch (type()) {
-            case "Picture":
-              title = "壁紙に使う画像ファイル";
-              break;
-            case "Video":
-              title = "壁紙に使う動画ファイル";
-              break;
-            case "YouTube":
-              title = "壁紙に使うYouTubeの動画URL";
-              break;
-          }
+        <Match when={type() === "Color"}>
+          <ColorField form={form} />
+        </Match>
+        <Match when={type() === "Gradient"}>
+          <GradientField form={form} />
+        </Match>
+      </Switch>
 
-          if (type() === "YouTube") {
-            return (
-              <div class={base()}>
-                <label for={props.name} class="text-sm">
-                  {title}
-                </label>
-
-                <input
-                  {...props}
-                  id={props.name}
-                  class={inputClass({
-                    class: "text-left font-mono",
-                  })}
-                  type="url"
-                  value={field.value}
-                />
-
-                <div class={error()}>{field.error}</div>
-              </div>
-            );
-          }
+      <Show
+        when={
+          type() === "Picture" ||
+          type() === "RemotePicture" ||
+          type() === "Video"
+        }
+      >
+        <LayoutField form={form} />
+      </Show>
+
+      <Show when={type() === "Picture" || type() === "Video"}>
+        <TemplateField form={form} />
+      </Show>
 
-          return (
-            <div class={base()}>
-              <label for={props.name} class="text-sm">
-                {title}
-              </label>
-              <input
-                {...props}
-                id={props.name}
-                type="text"
-                value={field.value}
-                hidden
-              />
-
-              <button
-                type="button"
-                class={inputClass({
-                  file: true,
-                  class: "text-left font-mono overflow-hidden",
-                })}
-                onClick={selectFile}
-                ref={buttonElement}
-              />
-
-              <div class={error()}>{field.error}</div>
-            </div>
-          );
-        }}
-      </Field>
+      <Show when={type() === "RemotePicture"}>
+        <RemotePictureField form={form} />
+      </Show>
+
+      <Show when={type() === "Video"}>
+        <VideoOptionsField form={form} />
+      </Show>
+
+      <Show when={type() === "YouTube"}>
+        <YouTubeOptionsField form={form} />
+      </Show>
+
+      <Show when={type() === "Slideshow"}>
+        <SlideshowField form={form} />
+      </Show>
     </div>
   );
 }
 
-export function SourceTypeField(props: {
-  form: FormStore<WallpaperForm>;
-  onChange: (type: WallpaperSource["type"]) => void;
-}) {
-  const { form, onChange } = props;
+export function SourceTypeField(props: { form: FormStore<WallpaperForm> }) {
+  const { form } = props;
   const { base, error } = fieldClass();
 
-  const [isFirst, setFirst] = createSignal(true);
-
   return (
     <Field of={form} name="source.type">
       {(field, props) => {
-        if (isFirst()) {
-          onChange(field.value ?? "Picture");
-          setFirst(false);
-        }
-
         return (
           <div class={base()}>
             <label for={props.name} class="text-sm">
@@ -182,9 +271,8 @@ export function SourceTypeField(props: {
               field={field}
               fieldProps={props}
               id={props.name}
-              onChange={(value) => {
+              onChange={() => {
                 setValue(form, "source.location", "");
-                onChange(value);
               }}
             />
             <div class={error()}>{field.error}</div>
@@ -216,12 +304,27 @@ function SourceTypeSelect(props: {
         <option class={optionClass()} value="Picture" selected>
           画像
         </option>
+        <option class={optionClass()} value="RemotePicture">
+          Web上の画像
+        </option>
         <option class={optionClass()} value="Video">
           動画
         </option>
         <option class={optionClass()} value="YouTube">
           YouTube
         </option>
+        <option class={optionClass()} value="LocalWebPage">
+          Webページ
+        </option>
+        <option class={optionClass()} value="Slideshow">
+          スライドショー
+        </option>
+        <option class={optionClass()} value="Color">
+          単色
+        </option>
+        <option class={optionClass()} value="Gradient">
+          グラデーション
+        </option>
       </select>
 
       <span class={chevron()}>
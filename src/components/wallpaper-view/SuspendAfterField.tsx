@@ -0,0 +1,42 @@
+import { Field, type FormStore, setValue } from "@modular-forms/solid";
+import { fieldClass, inputClass, textMutedClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function SuspendAfterField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+
+  const { base, error } = fieldClass();
+
+  return (
+    <Field of={form} name="suspendAfter" type="number">
+      {(field, props) => (
+        <div class={base()}>
+          <label for={props.name}>
+            隠れた壁紙を休止するまでの秒数（0 で休止しない）
+          </label>
+          <input
+            {...props}
+            id={props.name}
+            type="number"
+            min={0}
+            step={1}
+            value={field.value ?? 60}
+            class={inputClass({ class: "w-24" })}
+            onChange={(e) => {
+              if (e.target.value === "") {
+                e.target.value = "0";
+                setValue(form, "suspendAfter", 0);
+              }
+            }}
+          />
+          <p class={textMutedClass({ class: "text-sm" })}>
+            休止した壁紙はメモリを解放し、ウィンドウが再び表示されると読み込み直します。
+          </p>
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
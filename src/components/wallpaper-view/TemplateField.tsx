@@ -0,0 +1,98 @@
+import {
+  Field,
+  type FormStore,
+  getValue,
+  setValue,
+} from "@modular-forms/solid";
+import { open } from "@tauri-apps/plugin-dialog";
+import X from "lucide-solid/icons/x";
+import { Show } from "solid-js";
+import {
+  fieldClass,
+  iconButtonClass,
+  iconClass,
+  inputClass,
+  textMutedClass,
+} from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function TemplateField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  const selectFallback = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [
+        {
+          name: "画像",
+          extensions: ["png", "jpg", "jpeg", "gif", "webp", "avif", "bmp"],
+        },
+      ],
+    });
+
+    if (path !== null) {
+      setValue(form, "templateFallback", path);
+    }
+  };
+
+  return (
+    <div class={base()}>
+      <label for="source-location-template" class="text-sm">
+        ファイルのパス
+      </label>
+      <input
+        id="source-location-template"
+        type="text"
+        value={getValue(form, "source.location") ?? ""}
+        onChange={(event) =>
+          setValue(form, "source.location", event.currentTarget.value)
+        }
+        class={inputClass({ class: "text-left font-mono" })}
+      />
+      <p class={textMutedClass({ class: "text-sm" })}>
+        {
+          "{title}（ウィンドウのタイトル）、{app}（アプリの名前）、正規表現のフィルタのグループ（{1}や{name}）を書くと、ウィンドウごとに別のファイルを表示します。"
+        }
+      </p>
+
+      <Field of={form} name="templateFallback">
+        {(field, props) => (
+          <>
+            <label for={props.name} class="text-sm">
+              ファイルが無い時に表示する画像
+            </label>
+            <input {...props} type="text" value={field.value} hidden />
+
+            <div class="flex items-center gap-2">
+              <button
+                type="button"
+                class={inputClass({
+                  file: true,
+                  class: "text-left font-mono overflow-hidden",
+                })}
+                onClick={selectFallback}
+              >
+                {field.value || "クリックで画像を選択"}
+              </button>
+
+              <Show when={field.value}>
+                <button
+                  type="button"
+                  class={iconButtonClass()}
+                  onClick={() => setValue(form, "templateFallback", "")}
+                >
+                  <X class={iconClass()} />
+                </button>
+              </Show>
+            </div>
+
+            <div class={error()}>{field.error}</div>
+          </>
+        )}
+      </Field>
+    </div>
+  );
+}
@@ -0,0 +1,69 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import ChevronDown from "lucide-solid/icons/chevron-down";
+import { For } from "solid-js";
+import { TRANSITION_KINDS } from "../../lib/binding/payload_config";
+import { fieldClass, iconClass, inputClass, selectClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function TransitionField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+  const selectSlots = selectClass();
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="transition.kind">
+        {(field, props) => (
+          <>
+            <label for={props.name}>壁紙を切り替える時の効果</label>
+
+            <div class={selectSlots.base()}>
+              <select
+                {...props}
+                id={props.name}
+                class={selectSlots.select()}
+                value={field.value}
+              >
+                <For each={Object.entries(TRANSITION_KINDS)}>
+                  {([value, label]) => (
+                    <option
+                      class={selectSlots.optionClass()}
+                      value={value}
+                      selected={value === (field.value ?? "Crossfade")}
+                    >
+                      {label}
+                    </option>
+                  )}
+                </For>
+              </select>
+
+              <span class={selectSlots.chevron()}>
+                <ChevronDown class={iconClass()} />
+              </span>
+            </div>
+          </>
+        )}
+      </Field>
+
+      <Field of={form} name="transition.duration" type="number">
+        {(field, props) => (
+          <>
+            <label for={props.name}>効果の長さ（ミリ秒）</label>
+            <input
+              {...props}
+              id={props.name}
+              type="number"
+              min={0}
+              step={50}
+              value={field.value ?? 300}
+              class={inputClass({ class: "w-24" })}
+            />
+            <div class={error()}>{field.error}</div>
+          </>
+        )}
+      </Field>
+    </div>
+  );
+}
@@ -0,0 +1,121 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import { fieldClass, inputClass, textMutedClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function VideoOptionsField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base } = fieldClass();
+
+  return (
+    <div class={base()}>
+      <div class="flex items-end gap-2">
+        <Field of={form} name="source.videoOptions.volume" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              音量（0〜100）
+              <input
+                {...props}
+                type="number"
+                min={0}
+                max={100}
+                step={5}
+                value={field.value ?? 0}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+
+        <Field of={form} name="source.videoOptions.rate" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              再生速度
+              <input
+                {...props}
+                type="number"
+                min={0.1}
+                step={0.25}
+                value={field.value ?? 1}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+      </div>
+
+      <Field
+        of={form}
+        name="source.videoOptions.muteWhenUnfocused"
+        type="boolean"
+      >
+        {(field, props) => (
+          <label class="flex items-center gap-2 text-sm">
+            <input
+              {...props}
+              type="checkbox"
+              checked={field.value ?? true}
+              class="size-4 cursor-pointer"
+            />
+            アプリが最前面でない時は消音する
+          </label>
+        )}
+      </Field>
+
+      <div class="flex items-end gap-2">
+        <Field of={form} name="source.videoOptions.start" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              開始位置（秒）
+              <input
+                {...props}
+                type="number"
+                min={0}
+                step={0.5}
+                value={field.value ?? 0}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+
+        <Field of={form} name="source.videoOptions.loopStart" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              ループの始め（秒）
+              <input
+                {...props}
+                type="number"
+                min={0}
+                step={0.5}
+                value={field.value ?? 0}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+
+        <Field of={form} name="source.videoOptions.loopEnd" type="number">
+          {(field, props) => (
+            <label class="flex flex-col text-sm">
+              ループの終わり（秒）
+              <input
+                {...props}
+                type="number"
+                min={0}
+                step={0.5}
+                value={field.value ?? 0}
+                class={inputClass({ class: "w-24" })}
+              />
+            </label>
+          )}
+        </Field>
+      </div>
+
+      <p class={textMutedClass({ class: "text-sm" })}>
+        開始位置から再生し、その後はループの始めから終わりまでを繰り返します。ループの終わりが0の時は動画の最後までです。
+      </p>
+    </div>
+  );
+}
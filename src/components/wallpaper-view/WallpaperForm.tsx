@@ -1,43 +1,147 @@
 import {
   createFormStore,
   Form,
+  getValue,
   getValues,
   type SubmitHandler,
 } from "@modular-forms/solid";
-import { confirm } from "@tauri-apps/plugin-dialog";
+import { confirm, save } from "@tauri-apps/plugin-dialog";
 import Save from "lucide-solid/icons/save";
+import Share from "lucide-solid/icons/share";
 import Trash2 from "lucide-solid/icons/trash-2";
 import WandSparkles from "lucide-solid/icons/wand-sparkles";
-import { createEffect, onCleanup } from "solid-js";
-import { useConfig, useView, useWallpapers } from "../../GlobalState";
+import { createEffect, createSignal, onCleanup, Show } from "solid-js";
+import {
+  useConfig,
+  useSources,
+  useView,
+  useWallpapers,
+} from "../../GlobalState";
+import {
+  BUNDLE_EXTENSION,
+  exportWallpaperBundle,
+} from "../../lib/binding/command_bundle";
 import { saveConfig } from "../../lib/binding/command_config";
 import {
   addWallpaper,
+  applyLibrarySource,
   applyWallpaper,
   removeWallpaper,
 } from "../../lib/binding/command_wallpaper";
 import type {
+  BatteryBehavior,
   Filter,
+  GradientSource,
+  Layout,
+  NavigationPolicy,
+  PlaybackPolicy,
+  PropertyValues,
+  SlideshowOptions,
+  Transition,
+  VideoOptions,
   Wallpaper,
   WallpaperSource,
+  YouTubeOptions,
 } from "../../lib/binding/payload_config";
 import type { ApplyWallpaper } from "../../lib/binding/payload_wallpaper";
+import { DEFAULT_LAYOUT } from "../../lib/layout";
+import { DEFAULT_VIDEO_OPTIONS } from "../../lib/playback_clock";
 import { buttonClass } from "../ui";
+import { DEFAULT_YOUTUBE_OPTIONS } from "../wallpaper/YouTubeEmbed";
 import ApplicationField from "./ApplicationField";
+import BatteryField from "./BatteryField";
+import EffectsField, {
+  type EffectForm,
+  toEffect,
+  toEffectForm,
+} from "./EffectsField";
 import FilterFields from "./FilterFields";
+import InteractiveField from "./InteractiveField";
+import LibrarySourceField from "./LibrarySourceField";
+import MaxLiveOverlaysField from "./MaxLiveOverlaysField";
 import WallpaperNameField from "./NameField";
+import NavigationField from "./NavigationField";
 import OpacityField from "./OpacityField";
+import PlaybackControlField from "./PlaybackControlField";
+import PlaybackField from "./PlaybackField";
+import PreviewField from "./PreviewField";
+import PropertiesField from "./PropertiesField";
+import RemoteSystemDataField from "./RemoteSystemDataField";
 import SourceField from "./SourceField";
+import SuspendAfterField from "./SuspendAfterField";
+import TransitionField from "./TransitionField";
+import WindowBridgeField from "./WindowBridgeField";
+
+/** The source with the fields of every type, so that switching types keeps them. */
+export type SourceForm = {
+  type: WallpaperSource["type"];
+  location: string;
+  layout: Layout;
+  options: SlideshowOptions;
+  /** `loopEnd` of 0 loops at the end of the video. */
+  videoOptions: Omit<VideoOptions, "loopEnd"> & { loopEnd: number };
+  /** An empty `playlist` plays the single video. */
+  youtubeOptions: Omit<YouTubeOptions, "playlist"> & { playlist: string };
+  /** Minutes between checks for updates of the remote picture. */
+  refreshInterval: number;
+  color: string;
+  gradient: Omit<GradientSource, "type">;
+};
 
 export type WallpaperForm = {
   name: string;
   applicationName: string;
   filters: Filter[];
-  source: WallpaperSource;
+  source: SourceForm;
+  /** An empty string means the source is inline. */
+  librarySource: string;
   opacity: number;
+  playback: PlaybackPolicy;
+  onBattery: BatteryBehavior;
+  maxLiveOverlays: number;
+  suspendAfter: number;
+  transition: Transition;
+  windowBridge: boolean;
+  allowRemoteSystemData: boolean;
+  interactive: boolean;
+  allowRemoteInput: boolean;
+  properties: PropertyValues;
+  navigation: NavigationPolicy;
+  effects: EffectForm[];
+  /** An empty string means no preview. */
+  preview: string;
+  /** An empty string means no fallback. */
+  templateFallback: string;
+};
+
+const DEFAULT_SLIDESHOW_OPTIONS: SlideshowOptions = {
+  interval: 60,
+  recursive: false,
+  order: "Listed",
+  shuffle: false,
+  independent: false,
 };
 
-const DEFAULT_WALLPAPER_VALUE: WallpaperForm = {
+const DEFAULT_SOURCE: SourceForm = {
+  type: "Picture",
+  location: "",
+  layout: DEFAULT_LAYOUT,
+  options: DEFAULT_SLIDESHOW_OPTIONS,
+  videoOptions: { ...DEFAULT_VIDEO_OPTIONS, loopEnd: 0 },
+  youtubeOptions: { ...DEFAULT_YOUTUBE_OPTIONS, playlist: "" },
+  refreshInterval: 60,
+  color: "#1e1e2e",
+  gradient: {
+    kind: "Linear",
+    angle: 180,
+    stops: [
+      { color: "#1e1e2e", position: 0 },
+      { color: "#45475a", position: 100 },
+    ],
+  },
+};
+
+const DEFAULT_WALLPAPER_VALUE: Wallpaper = {
   name: "",
   applicationName: "",
   filters: [
@@ -50,10 +154,114 @@ const DEFAULT_WALLPAPER_VALUE: WallpaperForm = {
   source: {
     type: "Picture",
     location: "",
+    layout: DEFAULT_LAYOUT,
   },
+  librarySource: null,
   opacity: 0.2,
+  playback: "Always",
+  onBattery: { type: "Keep" },
+  maxLiveOverlays: 0,
+  suspendAfter: 60,
+  transition: { kind: "Crossfade", duration: 300 },
+  windowBridge: false,
+  allowRemoteSystemData: false,
+  interactive: false,
+  allowRemoteInput: false,
+  properties: {},
+  navigation: { type: "SameOrigin" },
+  effects: [],
+  preview: null,
+  templateFallback: null,
 };
 
+export function toSourceForm(source: WallpaperSource): SourceForm {
+  const form = { ...DEFAULT_SOURCE, type: source.type };
+
+  switch (source.type) {
+    case "Color":
+      return { ...form, color: source.color };
+    case "Gradient": {
+      const { type: _, ...gradient } = source;
+      return { ...form, gradient };
+    }
+    case "Picture":
+      return {
+        ...form,
+        location: source.location,
+        layout: { ...DEFAULT_LAYOUT, ...source.layout },
+      };
+    case "RemotePicture":
+      return {
+        ...form,
+        location: source.location,
+        layout: { ...DEFAULT_LAYOUT, ...source.layout },
+        refreshInterval: source.refreshInterval / 60,
+      };
+    case "Video": {
+      const options = { ...DEFAULT_VIDEO_OPTIONS, ...source.options };
+      return {
+        ...form,
+        location: source.location,
+        layout: { ...DEFAULT_LAYOUT, ...source.layout },
+        videoOptions: { ...options, loopEnd: options.loopEnd ?? 0 },
+      };
+    }
+    case "YouTube": {
+      const options = { ...DEFAULT_YOUTUBE_OPTIONS, ...source.options };
+      return {
+        ...form,
+        location: source.location,
+        youtubeOptions: { ...options, playlist: options.playlist ?? "" },
+      };
+    }
+    case "Slideshow":
+      return { ...form, location: source.location, options: source.options };
+    default:
+      return { ...form, location: source.location };
+  }
+}
+
+export function toSource(source: SourceForm): WallpaperSource {
+  const { type, location, layout, options, color, gradient } = source;
+  const { videoOptions, youtubeOptions, refreshInterval } = source;
+
+  switch (type) {
+    case "Picture":
+      return { type, location, layout };
+    case "RemotePicture":
+      return {
+        type,
+        location,
+        layout,
+        refreshInterval: Math.round(refreshInterval * 60),
+      };
+    case "Video":
+      return {
+        type,
+        location,
+        layout,
+        options: { ...videoOptions, loopEnd: videoOptions.loopEnd || null },
+      };
+    case "YouTube":
+      return {
+        type,
+        location,
+        options: {
+          ...youtubeOptions,
+          playlist: youtubeOptions.playlist || null,
+        },
+      };
+    case "Color":
+      return { type, color };
+    case "Gradient":
+      return { type, ...gradient };
+    case "Slideshow":
+      return { type, location, options };
+    default:
+      return { type, location };
+  }
+}
+
 function filterObject<K extends string, V>(
   obj: Partial<Record<K, V>>,
   filter: (key: K, value: V) => boolean,
@@ -97,17 +305,32 @@ function mapObjUndefined(
 export default function WallpaperForm(props: {
   id: string;
   wallpaper: Wallpaper | undefined;
+  template?: Partial<Wallpaper>;
   setDirty: (dirty: boolean) => void;
 }) {
   let { wallpaper, id, setDirty } = props;
   let isNew = wallpaper === undefined;
-  const initialValues = wallpaper || DEFAULT_WALLPAPER_VALUE;
+  // Only wallpapers known by the backend can be exported.
+  const [exportable, setExportable] = createSignal(!isNew);
+  const { preview, templateFallback, librarySource, ...rest } = wallpaper ?? {
+    ...DEFAULT_WALLPAPER_VALUE,
+    ...props.template,
+  };
+  const initialValues: WallpaperForm = {
+    ...rest,
+    source: toSourceForm(rest.source),
+    effects: (rest.effects ?? []).map(toEffectForm),
+    librarySource: librarySource ?? "",
+    preview: preview ?? "",
+    templateFallback: templateFallback ?? "",
+  };
 
   const form = createFormStore<WallpaperForm>({
     initialValues,
   });
 
   const [, setWallpapers] = useWallpapers();
+  const [sources, setSources] = useSources();
   const [, setView] = useView();
   const [config] = useConfig();
 
@@ -115,15 +338,58 @@ export default function WallpaperForm(props: {
     setDirty(form.internal.dirty.get());
   });
 
-  const handleSubmit: SubmitHandler<WallpaperForm> = (newWallpaper, event) => {
+  const handleSubmit: SubmitHandler<WallpaperForm> = (values, event) => {
+    const newWallpaper: Wallpaper = {
+      ...values,
+      source: toSource(values.source),
+      effects: (values.effects ?? []).map(toEffect),
+      librarySource: values.librarySource || null,
+      preview: values.preview || null,
+      templateFallback: values.templateFallback || null,
+    };
+
     if ((event.submitter as HTMLButtonElement).value === "save") {
+      const libraryId = newWallpaper.librarySource;
+      const entry = libraryId ? sources()[libraryId] : undefined;
+      // A changed library source changes every wallpaper using it.
+      const libraryEntry =
+        libraryId &&
+        entry &&
+        JSON.stringify(entry.source) !== JSON.stringify(newWallpaper.source)
+          ? { id: libraryId, entry: { ...entry, source: newWallpaper.source } }
+          : undefined;
+
+      if (libraryEntry) {
+        setSources((sources) => {
+          sources[libraryEntry.id] = libraryEntry.entry;
+          return sources;
+        });
+      }
+
       setWallpapers((wallpapers) => {
+        if (libraryEntry) {
+          for (const wallpaper of Object.values(wallpapers)) {
+            if (wallpaper.librarySource === libraryEntry.id) {
+              wallpaper.source = newWallpaper.source;
+            }
+          }
+        }
+
         wallpapers[id] = newWallpaper;
         return wallpapers;
       });
 
       setView({ type: "home" });
-      saveConfig(config());
+
+      // The backend skips the wallpapers already showing the library source,
+      // so this one is applied first and the config is saved last.
+      handleApply(newWallpaper).then(async () => {
+        if (libraryEntry) {
+          await applyLibrarySource(libraryEntry.id, libraryEntry.entry);
+        }
+        saveConfig(config());
+      });
+      return;
     }
 
     handleApply(newWallpaper);
@@ -131,11 +397,12 @@ export default function WallpaperForm(props: {
 
   let undo: ApplyWallpaper = {};
 
-  const handleApply = (newWallpaper: WallpaperForm) => {
+  const handleApply = async (newWallpaper: Wallpaper) => {
     if (isNew) {
-      addWallpaper(id, newWallpaper);
+      await addWallpaper(id, newWallpaper);
 
       isNew = false;
+      setExportable(true);
       wallpaper = newWallpaper;
     } else {
       const changedValues = getValues(form, { shouldDirty: true });
@@ -147,11 +414,44 @@ export default function WallpaperForm(props: {
             : undefined,
         source:
           changedValues.source !== undefined ? newWallpaper.source : undefined,
+        librarySource:
+          changedValues.librarySource !== undefined
+            ? newWallpaper.librarySource
+            : undefined,
+        onBattery:
+          changedValues.onBattery !== undefined
+            ? newWallpaper.onBattery
+            : undefined,
+        transition:
+          changedValues.transition !== undefined
+            ? newWallpaper.transition
+            : undefined,
+        properties:
+          changedValues.properties !== undefined
+            ? newWallpaper.properties
+            : undefined,
+        navigation:
+          changedValues.navigation !== undefined
+            ? newWallpaper.navigation
+            : undefined,
+        effects:
+          changedValues.effects !== undefined
+            ? newWallpaper.effects
+            : undefined,
       };
 
       undo = Object.assign(
         undo,
-        mapObj(payload, initialValues, (_, value) => value !== undefined),
+        mapObj(
+          payload,
+          {
+            ...initialValues,
+            source: toSource(initialValues.source),
+            effects: initialValues.effects.map(toEffect),
+            librarySource: initialValues.librarySource || null,
+          },
+          (_, value) => value !== undefined,
+        ),
       );
 
       // Include values that have been changed twice and then reverted back
@@ -166,7 +466,7 @@ export default function WallpaperForm(props: {
       ) as ApplyWallpaper;
       payload = Object.assign(payload, reverted);
 
-      applyWallpaper(id, payload);
+      const applied = applyWallpaper(id, payload);
 
       // `reverted` fields are already used and reverted fields on `undo`
       // are not needed anymore. So we can remove them.
@@ -174,6 +474,19 @@ export default function WallpaperForm(props: {
         undo,
         mapObjUndefined(reverted, (_, value) => value !== undefined),
       );
+
+      await applied;
+    }
+  };
+
+  const exportWallpaper = async () => {
+    const path = await save({
+      defaultPath: `${wallpaper?.name || "wallpaper"}.${BUNDLE_EXTENSION}`,
+      filters: [{ name: "overkamiの壁紙", extensions: [BUNDLE_EXTENSION] }],
+    });
+
+    if (path !== null) {
+      await exportWallpaperBundle(id, path);
     }
   };
 
@@ -206,8 +519,27 @@ export default function WallpaperForm(props: {
 
       <ApplicationField form={form} />
       <FilterFields form={form} />
+      <LibrarySourceField form={form} />
       <SourceField form={form} />
+      <PreviewField form={form} />
+      <PropertiesField form={form} />
+      <NavigationField form={form} />
+      <EffectsField form={form} />
+      <TransitionField form={form} />
+      <WindowBridgeField form={form} />
+      <RemoteSystemDataField form={form} />
+      <InteractiveField form={form} />
       <OpacityField form={form} />
+      <PlaybackField form={form} />
+      {/* Only wallpapers known by the backend have a playback clock. */}
+      <Show
+        when={exportable() && getValue(form, "source.type") === "Video"}
+      >
+        <PlaybackControlField id={id} />
+      </Show>
+      <BatteryField form={form} />
+      <MaxLiveOverlaysField form={form} />
+      <SuspendAfterField form={form} />
 
       <div class="my-4 flex gap-2">
         <button
@@ -228,6 +560,17 @@ export default function WallpaperForm(props: {
           試してみる
         </button>
 
+        <Show when={exportable()}>
+          <button
+            type="button"
+            class={buttonClass({ color: "secondary", withIcon: true })}
+            onClick={exportWallpaper}
+          >
+            <Share />
+            書き出す
+          </button>
+        </Show>
+
         <button
           type="button"
           class={buttonClass({ color: "error", withIcon: true })}
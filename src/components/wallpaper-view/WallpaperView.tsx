@@ -9,8 +9,9 @@ import WallpaperForm from "./WallpaperForm";
 export default function WallpaperView(props: {
   id: string;
   wallpaper: Wallpaper | undefined;
+  template?: Partial<Wallpaper>;
 }) {
-  const { wallpaper, id } = props;
+  const { wallpaper, template, id } = props;
   const [, setView] = useView();
   const [dirty, setDirty] = createSignal(false);
 
@@ -50,7 +51,12 @@ export default function WallpaperView(props: {
         class="px-16 py-2 mt-24 overflow-y-auto"
         style="height: calc(100vh - 48px * 2);"
       >
-        <WallpaperForm id={id} wallpaper={wallpaper} setDirty={setDirty} />
+        <WallpaperForm
+          id={id}
+          wallpaper={wallpaper}
+          template={template}
+          setDirty={setDirty}
+        />
       </div>
     </>
   );
@@ -0,0 +1,33 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import { fieldClass, textMutedClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function WindowBridgeField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base, error } = fieldClass();
+
+  return (
+    <Field of={form} name="windowBridge" type="boolean">
+      {(field, props) => (
+        <div class={base()}>
+          <label class="flex items-center gap-2">
+            <input
+              {...props}
+              type="checkbox"
+              checked={field.value ?? false}
+              class="size-4 cursor-pointer"
+            />
+            ウィンドウの情報をWebページに渡す
+          </label>
+          <p class={textMutedClass({ class: "text-sm" })}>
+            Webページの壁紙で、ウィンドウの大きさやタイトルなどを
+            <code>window.overkami</code>から読めるようにします。
+          </p>
+          <div class={error()}>{field.error}</div>
+        </div>
+      )}
+    </Field>
+  );
+}
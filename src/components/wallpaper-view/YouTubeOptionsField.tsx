@@ -0,0 +1,59 @@
+import { Field, type FormStore } from "@modular-forms/solid";
+import { fieldClass, inputClass } from "../ui";
+import type { WallpaperForm } from "./WallpaperForm";
+
+export default function YouTubeOptionsField(props: {
+  form: FormStore<WallpaperForm>;
+}) {
+  const { form } = props;
+  const { base } = fieldClass();
+
+  return (
+    <div class={base()}>
+      <Field of={form} name="source.youtubeOptions.start" type="number">
+        {(field, props) => (
+          <label class="flex flex-col text-sm">
+            開始位置（秒）
+            <input
+              {...props}
+              type="number"
+              min={0}
+              step={1}
+              value={field.value ?? 0}
+              class={inputClass({ class: "w-24" })}
+            />
+          </label>
+        )}
+      </Field>
+
+      <Field of={form} name="source.youtubeOptions.playlist">
+        {(field, props) => (
+          <label class="flex flex-col text-sm">
+            再生リスト（IDかURL、空なら動画だけを再生）
+            <input
+              {...props}
+              type="text"
+              placeholder="https://www.youtube.com/playlist?list=..."
+              value={field.value}
+              class={inputClass({ class: "font-mono" })}
+            />
+          </label>
+        )}
+      </Field>
+
+      <Field of={form} name="source.youtubeOptions.loop" type="boolean">
+        {(field, props) => (
+          <label class="flex items-center gap-2 text-sm">
+            <input
+              {...props}
+              type="checkbox"
+              checked={field.value ?? true}
+              class="size-4 cursor-pointer"
+            />
+            繰り返し再生する
+          </label>
+        )}
+      </Field>
+    </div>
+  );
+}
@@ -1,5 +1,13 @@
+import { createEffect } from "solid-js";
+import type { YouTubeOptions } from "../../lib/binding/payload_config";
 import { cl } from "../../lib/utils";
 
+export const DEFAULT_YOUTUBE_OPTIONS: YouTubeOptions = {
+  start: 0,
+  playlist: null,
+  loop: true,
+};
+
 function extractId(urlRaw: string): string | null {
   const url = new URL(urlRaw);
 
@@ -13,19 +21,64 @@ function extractId(urlRaw: string): string | null {
   return null;
 }
 
-function makeEmbedUrl(id: string) {
-  return `https://www.youtube.com/embed/${id}?mute=1&autoplay=1&loop=1&playlist=${id}&controls=0&disablekb=1`;
+/** Accepts both a playlist ID and a URL with `list` parameter. */
+function extractPlaylistId(playlist: string): string {
+  try {
+    return new URL(playlist).searchParams.get("list") ?? playlist;
+  } catch {
+    return playlist;
+  }
+}
+
+function makeEmbedUrl(id: string | null, options: YouTubeOptions) {
+  const params = new URLSearchParams({
+    mute: "1",
+    autoplay: "1",
+    controls: "0",
+    disablekb: "1",
+    enablejsapi: "1",
+  });
+
+  if (options.start > 0) params.set("start", String(options.start));
+  if (options.loop) params.set("loop", "1");
+
+  if (options.playlist) {
+    params.set("list", extractPlaylistId(options.playlist));
+  } else if (options.loop && id !== null) {
+    // A single video loops only as a playlist of itself.
+    params.set("playlist", id);
+  }
+
+  return `https://www.youtube.com/embed/${id ?? "videoseries"}?${params}`;
+}
+
+// 参考: https://developers.google.com/youtube/iframe_api_reference
+function postCommand(iframe: HTMLIFrameElement, func: string) {
+  iframe.contentWindow?.postMessage(
+    JSON.stringify({ event: "command", func, args: [] }),
+    "https://www.youtube.com",
+  );
 }
 
 // 参考: https://stackoverflow.com/a/79395341/14113394
 export default function YouTubeEmbed(props: {
   url: string;
+  options?: YouTubeOptions;
   className?: string;
+  playing?: boolean;
 }) {
   const className = props.className ?? "";
+  const options = props.options ?? DEFAULT_YOUTUBE_OPTIONS;
   const id = extractId(props.url);
+  let iframe: HTMLIFrameElement | undefined;
+
+  createEffect(() => {
+    if (iframe === undefined || props.playing === undefined) return;
+
+    postCommand(iframe, props.playing ? "playVideo" : "pauseVideo");
+  });
 
-  if (id === null) {
+  if (id === null && !options.playlist) {
     return (
       <div class="w-full h-full flex justify-center content-center">
         <div class="text-3xl">YouTubeの動画URLが正しくありません。</div>
@@ -43,13 +96,14 @@ export default function YouTubeEmbed(props: {
       )}
     >
       <iframe
+        ref={iframe}
         title="YouTube Video"
         class="border-0 pointer-events-none"
         style={{
           "min-width": "max(calc(100cqh * 16 / 9), 100vw)",
           "aspect-ratio": "16 / 9",
         }}
-        src={makeEmbedUrl(id)}
+        src={makeEmbedUrl(id, options)}
         allow="accelerometer; autoplay; encrypted-media; gyroscope"
         allowfullscreen
       />
@@ -1,17 +1,35 @@
 /* @refresh reload */
 import { render } from "solid-js/web";
 import App from "./App";
+import ColorApp from "./builtin-wallpapers/color";
 import PictureApp from "./builtin-wallpapers/picture";
 import VideoApp from "./builtin-wallpapers/video";
 import YouTubeApp from "./builtin-wallpapers/youtube";
+import { DEFAULT_YOUTUBE_OPTIONS } from "./components/wallpaper/YouTubeEmbed";
+import { backgroundOf } from "./lib/background";
+import type { GradientSource } from "./lib/binding/payload_config";
+import { parseLayout } from "./lib/layout";
+import { DEFAULT_VIDEO_OPTIONS } from "./lib/playback_clock";
+import { parseOptions } from "./lib/utils";
 
 const root = document.getElementById("root") as HTMLElement;
 
 const params = new URLSearchParams(window.location.search);
 const wallpaperType = params.get("wallpaper");
 
-if (wallpaperType) {
+if (wallpaperType === "empty") {
+  // A slideshow without any file to show.
+  root.innerHTML = '<h1 class="text-2xl">表示できるファイルがありません。</h1>';
+} else if (wallpaperType === "color") {
+  const background = params.get("color") ?? "";
+  render(() => <ColorApp background={background} />, root);
+} else if (wallpaperType === "gradient") {
+  const gradient: GradientSource = JSON.parse(params.get("gradient") ?? "{}");
+  render(() => <ColorApp background={backgroundOf(gradient)} />, root);
+} else if (wallpaperType) {
   const location = params.get("location");
+  const layout = params.get("layout");
+  const options = params.get("options");
 
   if (!location) {
     root.innerHTML =
@@ -19,13 +37,33 @@ if (wallpaperType) {
   } else {
     switch (wallpaperType) {
       case "picture":
-        render(() => <PictureApp path={location} />, root);
+        render(
+          () => <PictureApp path={location} layout={parseLayout(layout)} />,
+          root,
+        );
         break;
       case "video":
-        render(() => <VideoApp path={location} />, root);
+        render(
+          () => (
+            <VideoApp
+              path={location}
+              layout={parseLayout(layout)}
+              options={parseOptions(options, DEFAULT_VIDEO_OPTIONS)}
+            />
+          ),
+          root,
+        );
         break;
       case "youtube":
-        render(() => <YouTubeApp url={location} />, root);
+        render(
+          () => (
+            <YouTubeApp
+              url={location}
+              options={parseOptions(options, DEFAULT_YOUTUBE_OPTIONS)}
+            />
+          ),
+          root,
+        );
         break;
       default:
         root.innerHTML =
@@ -0,0 +1,17 @@
+import type { ColorSource, GradientSource } from "./binding/payload_config";
+
+/** Returns the CSS `background` showing the color or the gradient. */
+export function backgroundOf(source: ColorSource | GradientSource): string {
+  if (source.type === "Color") return source.color;
+
+  // CSS gradients need two stops at least, so hand-written configs may have too few.
+  if (source.stops.length < 2) return source.stops[0]?.color ?? "transparent";
+
+  const stops = source.stops
+    .map((stop) => `${stop.color} ${stop.position}%`)
+    .join(", ");
+
+  return source.kind === "Radial"
+    ? `radial-gradient(circle, ${stops})`
+    : `linear-gradient(${source.angle}deg, ${stops})`;
+}
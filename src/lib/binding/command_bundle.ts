@@ -0,0 +1,55 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ask } from "@tauri-apps/plugin-dialog";
+import type { ConflictStrategy, ImportedBundle } from "./payload_bundle";
+import { errorMessage } from "./utils";
+
+/** The extension of wallpaper bundles. */
+export const BUNDLE_EXTENSION = "overkami";
+
+type CommandError = { code: string; detail: string | null };
+
+export async function exportWallpaperBundle(id: string, path: string) {
+  try {
+    await invoke("export_wallpaper_bundle", { id, path });
+  } catch (error) {
+    const { code, detail } = error as CommandError;
+    await errorMessage({
+      message: "壁紙を書き出せませんでした。",
+      detail: detail ?? code,
+    });
+  }
+}
+
+/**
+ * Adds the wallpaper in the bundle. If a wallpaper with the same ID exists,
+ * users are asked whether to replace it. Returns `null` if it fails.
+ */
+export async function importWallpaperBundle(
+  path: string,
+  onConflict?: ConflictStrategy,
+): Promise<ImportedBundle | null> {
+  try {
+    return await invoke("import_wallpaper_bundle", { path, onConflict });
+  } catch (error) {
+    const { code, detail } = error as CommandError;
+
+    if (code === "duplicate_id" && onConflict === undefined) {
+      const replace = await ask(
+        `同じ壁紙「${detail}」がすでにあります。置き換えますか？`,
+        { okLabel: "置き換える", cancelLabel: "別の壁紙として追加" },
+      );
+
+      return await importWallpaperBundle(
+        path,
+        replace ? "Replace" : "KeepBoth",
+      );
+    }
+
+    await errorMessage({
+      message: "壁紙を読み込めませんでした。",
+      detail: detail ?? code,
+    });
+
+    return null;
+  }
+}
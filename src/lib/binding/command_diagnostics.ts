@@ -0,0 +1,6 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Diagnostics } from "./payload_diagnostics";
+
+export async function getDiagnostics(): Promise<Diagnostics> {
+  return await invoke("get_diagnostics");
+}
@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ImportedProject } from "./payload_import";
+import { errorMessage } from "./utils";
+
+/** Reads the Wallpaper Engine project in the directory. Returns `null` if it fails. */
+export async function importWallpaperEngineProject(
+  path: string,
+): Promise<ImportedProject | null> {
+  try {
+    return await invoke("import_wallpaper_engine_project", { path });
+  } catch (error) {
+    const { code, detail } = error as { code: string; detail: string | null };
+
+    await errorMessage({
+      message:
+        code === "unsupported_project"
+          ? "この種類のWallpaper Engineの壁紙には対応していません。対応しているのはWebと動画の壁紙です。"
+          : "Wallpaper Engineの壁紙を読み込めませんでした。",
+      detail: detail ?? code,
+    });
+
+    return null;
+  }
+}
@@ -0,0 +1,8 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { WallpaperManifest } from "./payload_manifest";
+
+export async function getWallpaperManifest(
+  path: string,
+): Promise<WallpaperManifest | null> {
+  return await invoke("get_wallpaper_manifest", { path });
+}
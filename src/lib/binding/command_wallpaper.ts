@@ -1,5 +1,10 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { AddWallpaper, ApplyWallpaper } from "./payload_wallpaper";
+import type { LibrarySource } from "./payload_config";
+import type {
+  AddWallpaper,
+  ApplyWallpaper,
+  PlaybackControl,
+} from "./payload_wallpaper";
 
 export async function applyWallpaper(id: string, payload: ApplyWallpaper) {
   await invoke("apply_wallpaper", { id, payload });
@@ -12,3 +17,20 @@ export async function addWallpaper(id: string, payload: AddWallpaper) {
 export async function removeWallpaper(id: string) {
   await invoke("remove_wallpaper", { id });
 }
+
+/** Saves the library entry and applies it to the wallpapers using it. */
+export async function applyLibrarySource(id: string, entry: LibrarySource) {
+  await invoke("apply_library_source", { id, entry });
+}
+
+export async function controlPlayback(id: string, control: PlaybackControl) {
+  await invoke("control_playback", { id, control });
+}
+
+/**
+ * Tells whether the media of the calling built-in page is loaded.
+ * The page is shown only after this reports the success.
+ */
+export async function reportMediaLoad(error?: string) {
+  await invoke("report_media_load", { error: error ?? null });
+}
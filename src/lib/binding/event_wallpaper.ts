@@ -0,0 +1,18 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export type WallpaperSourceError = {
+  wallpaperId: string;
+  detail: string;
+};
+
+/**
+ * Listens failures of loading new wallpaper sources.
+ * The old source is kept shown when it happens.
+ */
+export async function listenWallpaperSourceError(
+  handler: (error: WallpaperSourceError) => void,
+): Promise<UnlistenFn> {
+  return await listen<WallpaperSourceError>("wallpaper-source-error", (event) =>
+    handler(event.payload),
+  );
+}
@@ -0,0 +1,9 @@
+import type { Wallpaper } from "./payload_config";
+
+/** What to do when the wallpaper in a bundle has the same ID as an existing one. */
+export type ConflictStrategy = "Replace" | "KeepBoth";
+
+export type ImportedBundle = {
+  id: string;
+  wallpaper: Wallpaper;
+};
@@ -8,33 +8,168 @@ export type LocalWebPageSource = {
   location: string;
 };
 
+export type YouTubeOptions = {
+  /** Seconds */
+  start: number;
+  /** The ID or the URL of the playlist to play instead of the single video. */
+  playlist: string | null;
+  loop: boolean;
+};
+
 export type YouTubeSource = {
   type: "YouTube";
   location: string;
+  options: YouTubeOptions;
+};
+
+export const FITS = {
+  Cover: "ウィンドウを埋める（はみ出た部分は切り取る）",
+  Contain: "全体を表示する",
+  Fill: "引き伸ばす",
+  None: "元の大きさ",
+  Tile: "並べて表示する",
+} as const;
+export type Fit = keyof typeof FITS;
+
+export const ANCHORS = {
+  TopLeft: "左上",
+  Top: "上",
+  TopRight: "右上",
+  Left: "左",
+  Center: "中央",
+  Right: "右",
+  BottomLeft: "左下",
+  Bottom: "下",
+  BottomRight: "右下",
+} as const;
+export type Anchor = keyof typeof ANCHORS;
+
+/** How a picture or a video is laid out in the window. */
+export type Layout = {
+  fit: Fit;
+  anchor: Anchor;
+  /** Multiplies the size given by `fit`. */
+  scale: number;
+  /** Percent of the width of the window. */
+  offsetX: number;
+  /** Percent of the height of the window. */
+  offsetY: number;
 };
 
 export type PictureSource = {
   type: "Picture";
   location: string;
+  layout: Layout;
+};
+
+/** A picture on the web, which is downloaded and cached by the app. */
+export type RemotePictureSource = {
+  type: "RemotePicture";
+  location: string;
+  layout: Layout;
+  /** Seconds between checks for updates. */
+  refreshInterval: number;
+};
+
+/** Positions are in seconds of the video. */
+export type VideoOptions = {
+  /** From 0 to 100. 0 keeps the video muted. */
+  volume: number;
+  muteWhenUnfocused: boolean;
+  /** Multiplies the rate of the playback clock. */
+  rate: number;
+  start: number;
+  loopStart: number;
+  /** `null` loops at the end of the video. */
+  loopEnd: number | null;
 };
 
 export type VideoSource = {
   type: "Video";
   location: string;
+  layout: Layout;
+  options: VideoOptions;
 };
 
-export type RemoteWallpaperSource = RemoteWebPageSource | YouTubeSource;
+export const SLIDESHOW_ORDERS = {
+  Listed: "リストの順（フォルダは名前順）",
+  Name: "名前順",
+  Modified: "更新日時の古い順",
+} as const;
+export type SlideshowOrder = keyof typeof SLIDESHOW_ORDERS;
+
+export type SlideshowOptions = {
+  /** Seconds to show each item. */
+  interval: number;
+  recursive: boolean;
+  order: SlideshowOrder;
+  shuffle: boolean;
+  /** Whether each window rotates on its own, instead of all windows together. */
+  independent: boolean;
+};
+
+/** The pictures and videos in a folder, or listed in a playlist file, in turn. */
+export type SlideshowSource = {
+  type: "Slideshow";
+  location: string;
+  options: SlideshowOptions;
+};
+
+export type ColorSource = {
+  type: "Color";
+  /** A color written in CSS. */
+  color: string;
+};
+
+export const GRADIENT_KINDS = {
+  Linear: "直線",
+  Radial: "円形",
+} as const;
+export type GradientKind = keyof typeof GRADIENT_KINDS;
+
+export type GradientStop = {
+  color: string;
+  /** Percent */
+  position: number;
+};
+
+export type GradientSource = {
+  type: "Gradient";
+  kind: GradientKind;
+  /** Degrees. Not used by radial gradients. */
+  angle: number;
+  stops: GradientStop[];
+};
+
+export type RemoteWallpaperSource =
+  | RemoteWebPageSource
+  | RemotePictureSource
+  | YouTubeSource;
 export type LocalWallpaperSource =
   | LocalWebPageSource
   | PictureSource
-  | VideoSource;
-export type WallpaperSource = RemoteWallpaperSource | LocalWallpaperSource;
+  | VideoSource
+  | SlideshowSource;
+export type WallpaperSource =
+  | RemoteWallpaperSource
+  | LocalWallpaperSource
+  | ColorSource
+  | GradientSource;
+
+/** A named source in the library, which wallpapers can share. */
+export type LibrarySource = {
+  name: string;
+  source: WallpaperSource;
+};
+
+export type SourceLibrary = { [key: string]: LibrarySource };
 
 export const STRING_FILTER_STRATEGIES = {
   Prefix: "前方一致",
   Suffix: "後方一致",
   Contains: "部分一致",
   Exact: "完全一致",
+  Regex: "正規表現",
 } as const;
 export type StringFilterStrategy = keyof typeof STRING_FILTER_STRATEGIES;
 
@@ -46,12 +181,104 @@ export type WindowNameFilter = {
 
 export type Filter = WindowNameFilter;
 
+export const PLAYBACK_POLICIES = {
+  Always: "常に再生",
+  WhenFocused: "アプリが最前面の時のみ再生",
+  WhenVisible: "ウィンドウが表示されている時のみ再生",
+} as const;
+export type PlaybackPolicy = keyof typeof PLAYBACK_POLICIES;
+
+export const BATTERY_BEHAVIORS = {
+  Keep: "そのまま",
+  PauseAnimation: "動画などを停止",
+  StaticFallback: "代わりの画像を表示",
+  Disable: "壁紙を表示しない",
+} as const;
+
+export type BatteryBehavior =
+  | { type: "Keep" }
+  | { type: "PauseAnimation" }
+  | { type: "StaticFallback"; location: string }
+  | { type: "Disable" };
+
+export const TRANSITION_KINDS = {
+  Cut: "すぐに切り替える",
+  Fade: "フェードアウトしてからフェードイン",
+  Crossfade: "クロスフェード",
+} as const;
+export type TransitionKind = keyof typeof TRANSITION_KINDS;
+
+export type Transition = {
+  kind: TransitionKind;
+  /** Milliseconds */
+  duration: number;
+};
+
+export const NAVIGATION_POLICIES = {
+  SameOrigin: "同じサイトの中だけ",
+  Allowlist: "同じサイトと指定したサイトだけ",
+  Block: "移動させない",
+} as const;
+
+export type NavigationPolicy =
+  | { type: "SameOrigin" }
+  | { type: "Allowlist"; origins: string[] }
+  | { type: "Block" };
+
+export const EFFECT_TYPES = {
+  Blur: "ぼかし",
+  Brightness: "明るさ",
+  Contrast: "コントラスト",
+  Saturation: "彩度",
+  Grayscale: "白黒",
+  Tint: "色をかぶせる",
+} as const;
+export type EffectType = keyof typeof EFFECT_TYPES;
+
+/** Amounts are in percent, and the radius of blurs is in pixels. */
+export type Effect =
+  | { type: "Blur"; radius: number }
+  | { type: "Brightness"; amount: number }
+  | { type: "Contrast"; amount: number }
+  | { type: "Saturation"; amount: number }
+  | { type: "Grayscale"; amount: number }
+  | { type: "Tint"; color: string; strength: number };
+
+export type PropertyValues = { [key: string]: string | number | boolean };
+
 export type Wallpaper = {
   name: string;
   applicationName: string;
   filters: Filter[];
   source: WallpaperSource;
+  /** The ID of the source in the library which `source` follows. */
+  librarySource: string | null;
   opacity: number;
+  playback: PlaybackPolicy;
+  onBattery: BatteryBehavior;
+  /** The maximum number of live overlays. `0` means unlimited. */
+  maxLiveOverlays: number;
+  /** Seconds after which hidden overlays are suspended. `0` means never. */
+  suspendAfter: number;
+  transition: Transition;
+  /** Whether to expose the state of the target window through `window.overkami`. */
+  windowBridge: boolean;
+  /** Whether to allow remote pages to read system data. Local pages are always allowed. */
+  allowRemoteSystemData: boolean;
+  /** Whether the page can accept pointer events inside the regions it declares. */
+  interactive: boolean;
+  /** Whether to allow remote pages to declare input regions. Local pages are always allowed. */
+  allowRemoteInput: boolean;
+  /** The values of the properties declared in the manifest of the page. */
+  properties: PropertyValues;
+  /** Where web page wallpapers can navigate to. */
+  navigation: NavigationPolicy;
+  /** Applied to the wallpaper in order. */
+  effects: Effect[];
+  /** The image shown in the list of wallpapers instead of the source. */
+  preview: string | null;
+  /** The picture shown when the file of the templated source does not exist. */
+  templateFallback: string | null;
 };
 
 export type Wallpapers = { [key: string]: Wallpaper };
@@ -60,4 +287,5 @@ export type Config = {
   version: string;
   open_window_on_startup: boolean,
   wallpapers: Wallpapers;
+  sources: SourceLibrary;
 };
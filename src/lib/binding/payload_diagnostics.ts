@@ -0,0 +1,11 @@
+export type Diagnostics = {
+  /** Resident memory of overkami in bytes. */
+  memory: {
+    main: number;
+    /** Memory of descendant processes, and of WebKit processes on macOS. */
+    children: number;
+  };
+  wallpapers: {
+    [key: string]: { liveOverlays: number; suspendedOverlays: number };
+  };
+};
@@ -0,0 +1,8 @@
+import type { WallpaperSource } from "./payload_config";
+
+/** The wallpaper made from a Wallpaper Engine project. */
+export type ImportedProject = {
+  name: string;
+  source: WallpaperSource;
+  preview: string | null;
+};
@@ -0,0 +1,32 @@
+export type PropertyKind =
+  | { type: "color"; default: string }
+  | {
+      type: "number";
+      default: number;
+      min: number | null;
+      max: number | null;
+      step: number | null;
+    }
+  | { type: "bool"; default: boolean }
+  | { type: "text"; default: string }
+  | { type: "select"; default: string; options: SelectOption[] }
+  | { type: "file"; default: string | null; extensions: string[] };
+
+export type SelectOption = {
+  label: string;
+  value: string;
+};
+
+export type Property = {
+  key: string;
+  /** The label shown in the settings UI. `key` is shown if this is `null`. */
+  label: string | null;
+} & PropertyKind;
+
+/**
+ * The manifest placed next to the HTML of a local web page wallpaper as `overkami.json`.
+ * The properties of `project.json` are used for Wallpaper Engine projects without it.
+ */
+export type WallpaperManifest = {
+  properties: Property[];
+};
@@ -1,4 +1,14 @@
-import type { Filter, Wallpaper, WallpaperSource } from "./payload_config";
+import type {
+  BatteryBehavior,
+  Effect,
+  Filter,
+  NavigationPolicy,
+  PlaybackPolicy,
+  PropertyValues,
+  Transition,
+  Wallpaper,
+  WallpaperSource,
+} from "./payload_config";
 
 export type ApplyWallpaper = {
   name?: string;
@@ -6,6 +16,30 @@ export type ApplyWallpaper = {
   filters?: Filter[];
   opacity?: number;
   source?: WallpaperSource;
+  /** `null` makes the source inline. */
+  librarySource?: string | null;
+  playback?: PlaybackPolicy;
+  onBattery?: BatteryBehavior;
+  maxLiveOverlays?: number;
+  suspendAfter?: number;
+  transition?: Transition;
+  windowBridge?: boolean;
+  allowRemoteSystemData?: boolean;
+  interactive?: boolean;
+  allowRemoteInput?: boolean;
+  properties?: PropertyValues;
+  navigation?: NavigationPolicy;
+  effects?: Effect[];
+  /** An empty string removes the preview. */
+  preview?: string;
+  /** An empty string removes the fallback. */
+  templateFallback?: string;
 };
 
 export type AddWallpaper = Wallpaper;
+
+export type PlaybackControl =
+  | { type: "Pause" }
+  | { type: "Resume" }
+  | { type: "Seek"; position: number }
+  | { type: "SetRate"; rate: number };
@@ -0,0 +1,58 @@
+import type { JSX } from "solid-js";
+import type { Anchor, Layout } from "./binding/payload_config";
+import { parseOptions } from "./utils";
+
+export const DEFAULT_LAYOUT: Layout = {
+  fit: "Cover",
+  anchor: "Center",
+  scale: 1,
+  offsetX: 0,
+  offsetY: 0,
+};
+
+/** The position of the anchor in percent of the window. */
+const ANCHOR_POSITIONS: { [key in Anchor]: [number, number] } = {
+  TopLeft: [0, 0],
+  Top: [50, 0],
+  TopRight: [100, 0],
+  Left: [0, 50],
+  Center: [50, 50],
+  Right: [100, 50],
+  BottomLeft: [0, 100],
+  Bottom: [50, 100],
+  BottomRight: [100, 100],
+};
+
+/** Reads the layout in the query string, filling missing fields with the default. */
+export function parseLayout(query: string | null): Layout {
+  return parseOptions(query, DEFAULT_LAYOUT);
+}
+
+/** The style of an `img` or a `video` filling the window. Tiles are laid out by `tileOffset`. */
+export function mediaStyle(layout: Layout): JSX.CSSProperties {
+  const [x, y] = ANCHOR_POSITIONS[layout.anchor];
+
+  return {
+    "object-fit": layout.fit === "Tile" ? "none" : layout.fit.toLowerCase(),
+    "object-position": `${x}% ${y}%`,
+    "transform-origin": `${x}% ${y}%`,
+    transform: `translate(${layout.offsetX}%, ${layout.offsetY}%) scale(${layout.scale})`,
+  };
+}
+
+/**
+ * Returns where the first tile of the size is put in the area,
+ * in the same way as `background-position` in percent.
+ */
+export function tileOffset(
+  layout: Layout,
+  area: { width: number; height: number },
+  tile: { width: number; height: number },
+): { x: number; y: number } {
+  const [x, y] = ANCHOR_POSITIONS[layout.anchor];
+
+  return {
+    x: ((area.width - tile.width) * x + area.width * layout.offsetX) / 100,
+    y: ((area.height - tile.height) * y + area.height * layout.offsetY) / 100,
+  };
+}
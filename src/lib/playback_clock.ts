@@ -0,0 +1,129 @@
+import type { VideoOptions } from "./binding/payload_config";
+import {
+  type ClockEventDetail,
+  listenClock,
+  listenPlayback,
+} from "./wallpaper_event";
+
+/** Seeking is skipped if the video is off from the clock less than this in seconds. */
+const DRIFT_TOLERANCE = 0.25;
+const DRIFT_CHECK_INTERVAL = 5000;
+
+export const DEFAULT_VIDEO_OPTIONS: VideoOptions = {
+  volume: 0,
+  muteWhenUnfocused: true,
+  rate: 1,
+  start: 0,
+  loopStart: 0,
+  loopEnd: null,
+};
+
+/**
+ * Whether the video loops between its own points instead of over the whole video.
+ * Such videos must not loop natively, since the browser always loops from the beginning.
+ */
+export function hasLoopPoints(options: VideoOptions): boolean {
+  return options.loopStart > 0 || options.loopEnd !== null;
+}
+
+/**
+ * Returns the time of the video at the position of the clock.
+ * The video plays from the start point, and then loops between the loop points.
+ */
+function mediaTime(
+  position: number,
+  duration: number,
+  options: VideoOptions,
+): number {
+  const end = Math.min(options.loopEnd ?? duration, duration);
+  const loopStart = Math.min(options.loopStart, end);
+  const time = options.start + position;
+  if (time < end) return time;
+
+  const length = end - loopStart;
+  return length > 0 ? loopStart + ((time - end) % length) : loopStart;
+}
+
+/**
+ * Makes the video follow the playback clock shared by all overlays of the wallpaper,
+ * so that copies of the video on each window play and loop together.
+ * Returns a function to stop following.
+ */
+export function followPlaybackClock(
+  video: HTMLVideoElement,
+  options: VideoOptions = DEFAULT_VIDEO_OPTIONS,
+): () => void {
+  let clock: (ClockEventDetail & { receivedAt: number }) | undefined;
+  let playing = true;
+
+  const clockPosition = () => {
+    if (clock === undefined) return undefined;
+    if (clock.paused) return clock.position;
+
+    const elapsed = (performance.now() - clock.receivedAt) / 1000;
+    return clock.position + elapsed * clock.rate;
+  };
+
+  /** `force` seeks even if the video is close to the clock, like when it passes the loop end. */
+  const seek = (force = false) => {
+    const position = clockPosition();
+    if (position === undefined || !Number.isFinite(video.duration)) return;
+
+    const target = mediaTime(position * options.rate, video.duration, options);
+    if (force || Math.abs(video.currentTime - target) > DRIFT_TOLERANCE) {
+      video.currentTime = target;
+    }
+  };
+
+  const update = () => {
+    const paused = clock?.paused || clock?.rate === 0;
+
+    if (clock !== undefined && clock.rate > 0) {
+      video.playbackRate = clock.rate * options.rate;
+    }
+
+    seek();
+    if (playing && !paused) {
+      video.play();
+    } else {
+      video.pause();
+    }
+  };
+
+  const unlistenClock = listenClock((detail) => {
+    clock = { ...detail, receivedAt: performance.now() };
+    update();
+  });
+  const unlistenPlayback = listenPlayback((detail) => {
+    playing = detail.playing;
+    update();
+  });
+
+  // The duration is unknown until the metadata is loaded.
+  const onLoadedMetadata = () => seek();
+  video.addEventListener("loadedmetadata", onLoadedMetadata);
+  // Go back to the loop start as soon as the loop end is passed.
+  const checkLoopEnd = () => {
+    const end = Math.min(options.loopEnd ?? video.duration, video.duration);
+    if (video.currentTime >= end) seek(true);
+  };
+  video.addEventListener("timeupdate", checkLoopEnd);
+  // Videos with loop points don't loop natively, so they end at the end of the video.
+  const onEnded = () => {
+    seek(true);
+    update();
+  };
+  video.addEventListener("ended", onEnded);
+  const interval = setInterval(() => {
+    if (!video.paused) seek();
+  }, DRIFT_CHECK_INTERVAL);
+
+  return () => {
+    unlistenClock();
+    unlistenPlayback();
+    video.removeEventListener("loadedmetadata", onLoadedMetadata);
+    video.removeEventListener("timeupdate", checkLoopEnd);
+    video.removeEventListener("ended", onEnded);
+    clearInterval(interval);
+  };
+}
@@ -5,3 +5,12 @@ export function cl(...classes: (string | boolean | undefined | null)[]) {
 export type OptionalizeAll<T> = {
   [P in keyof T]?: T[P];
 };
+
+/** Reads the options in JSON, filling missing fields with the defaults. */
+export function parseOptions<T>(json: string | null, defaults: T): T {
+  try {
+    return { ...defaults, ...JSON.parse(json ?? "{}") };
+  } catch {
+    return defaults;
+  }
+}
@@ -8,6 +8,8 @@ export interface WallpaperView {
   type: "wallpaper";
   id: string;
   wallpaper: Wallpaper | undefined;
+  /** The initial values of a new wallpaper, such as the ones imported from other apps. */
+  template?: Partial<Wallpaper>;
 }
 
 export type View = HomeView | WallpaperView;
@@ -0,0 +1,76 @@
+// Events sent from overkami to wallpaper pages in overlay windows.
+// Custom wallpaper pages can listen them on `window` as well.
+
+import type { Layout } from "./binding/payload_config";
+
+export type PlaybackEventDetail = {
+  playing: boolean;
+};
+
+/**
+ * Listens `overkami:playback` event that tells whether the wallpaper should play animations.
+ * Returns a function to unlisten.
+ */
+export function listenPlayback(
+  handler: (detail: PlaybackEventDetail) => void,
+): () => void {
+  const listener = (event: Event) =>
+    handler((event as CustomEvent<PlaybackEventDetail>).detail);
+
+  window.addEventListener("overkami:playback", listener);
+  return () => window.removeEventListener("overkami:playback", listener);
+}
+
+export type AudioEventDetail = {
+  audible: boolean;
+};
+
+/**
+ * Listens `overkami:audio` event that tells whether the wallpaper may play sound.
+ * Pages should stay muted until they are told so.
+ * Returns a function to unlisten.
+ */
+export function listenAudio(
+  handler: (detail: AudioEventDetail) => void,
+): () => void {
+  const listener = (event: Event) =>
+    handler((event as CustomEvent<AudioEventDetail>).detail);
+
+  window.addEventListener("overkami:audio", listener);
+  return () => window.removeEventListener("overkami:audio", listener);
+}
+
+export type ClockEventDetail = {
+  /** Seconds from the start of the media. Take the remainder by the duration to loop. */
+  position: number;
+  rate: number;
+  paused: boolean;
+};
+
+/**
+ * Listens `overkami:clock` event that tells the state of the playback clock
+ * shared by all overlays of the wallpaper.
+ * Returns a function to unlisten.
+ */
+export function listenClock(
+  handler: (detail: ClockEventDetail) => void,
+): () => void {
+  const listener = (event: Event) =>
+    handler((event as CustomEvent<ClockEventDetail>).detail);
+
+  window.addEventListener("overkami:clock", listener);
+  return () => window.removeEventListener("overkami:clock", listener);
+}
+
+/**
+ * Listens `overkami:layout` event that tells the new layout of the built-in
+ * picture and video wallpapers.
+ * Returns a function to unlisten.
+ */
+export function listenLayout(handler: (detail: Layout) => void): () => void {
+  const listener = (event: Event) =>
+    handler((event as CustomEvent<Layout>).detail);
+
+  window.addEventListener("overkami:layout", listener);
+  return () => window.removeEventListener("overkami:layout", listener);
+}